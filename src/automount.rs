@@ -0,0 +1,44 @@
+//! # D-Bus interface proxy for: `org.freedesktop.systemd1.Automount`
+//!
+//! Automount units implement this interface in addition to org.freedesktop.systemd1.Unit, on the
+//! same object. Like for the unit proxy, the path of the unit has to be given when building the
+//! proxy.
+//!
+//! Only the most commonly used members are included here, see "man org.freedesktop.systemd1"
+//! and "man systemd.automount" for the full list.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+use zbus::proxy;
+#[proxy(
+    interface = "org.freedesktop.systemd1.Automount",
+    default_service = "org.freedesktop.systemd1"
+)]
+pub trait Automount {
+    /// # DirectoryMode
+    /// ## PROPERTY
+    /// The access mode of directories created for the mount point.
+    #[zbus(property)]
+    fn directory_mode(&self) -> zbus::Result<u32>;
+
+    /// # Result
+    /// ## PROPERTY
+    /// Why the automount last stopped, e.g. "success", "resources" or "mount-start-limit-hit".
+    #[zbus(property)]
+    fn result(&self) -> zbus::Result<String>;
+
+    /// # TimeoutIdleUSec
+    /// ## PROPERTY
+    /// How long the mount may be idle in microseconds before it is unmounted again, 0 if it
+    /// stays mounted.
+    #[zbus(property, name = "TimeoutIdleUSec")]
+    fn timeout_idle_usec(&self) -> zbus::Result<u64>;
+
+    /// # Where
+    /// ## PROPERTY
+    /// The mount point, which the name of the unit is derived from.
+    #[zbus(property, name = "Where")]
+    fn where_(&self) -> zbus::Result<String>;
+}
@@ -1,13 +1,93 @@
 use thiserror::Error;
 
+use crate::systemctl::{unit_config::ConfigError, unit_name::UnitType};
+
 #[derive(Debug, Error)]
 pub enum SystemdError {
     #[error("Error occurred inside of the Zbus library.")]
     DbusError(#[from] zbus::Error),
 
+    #[error("Error occurred while accessing the file system")]
+    IoError(#[from] std::io::Error),
+
     #[error("Connection not initialised")]
     InitialisationError,
 
     #[error("Invalid use of isolate mode in start unit")]
     IsolateModeUnavailable,
+
+    #[error("Unit {0} may not be isolated, it does not set AllowIsolate=yes")]
+    IsolateNotAllowed(String),
+
+    #[error("Timed out waiting for systemd")]
+    Timeout,
+
+    #[error("The signal stream was closed before the expected signal arrived")]
+    SignalStreamClosed,
+
+    #[error("Invalid unit name: {0}")]
+    InvalidUnitName(String),
+
+    #[error("Unknown unit type: {0}")]
+    InvalidUnitType(String),
+
+    #[error("No unit found for process {0}")]
+    NoUnitForProcess(u32),
+
+    #[error("Unit {unit} does not support cleaning {unsupported}")]
+    CleanNotSupported { unit: String, unsupported: String },
+
+    #[error("Unit {unit} is {active_state}, it needs to be inactive")]
+    UnitNotInactive { unit: String, active_state: String },
+
+    #[error("Cannot {action}, it is inhibited by {}", inhibitors.join("; "))]
+    PowerActionInhibited {
+        action: String,
+        inhibitors: Vec<String>,
+    },
+
+    #[error("Cannot {action}, logind reports it as {capability}")]
+    PowerActionUnavailable { action: String, capability: String },
+
+    #[error("The power action {0} is not supported here")]
+    PowerActionUnsupported(String),
+
+    #[error("Invalid environment variable name: {0}")]
+    InvalidEnvironmentName(String),
+
+    #[error("Invalid environment variable assignment: {0}")]
+    InvalidEnvironmentAssignment(String),
+
+    #[error("Unit file {0} not found")]
+    UnitFileNotFound(String),
+
+    #[error("Unit file {0} is masked")]
+    UnitFileMasked(String),
+
+    #[error("{0} already exists")]
+    UnitFileExists(String),
+
+    #[error("Unit {0} is not a service")]
+    NotAService(String),
+
+    #[error("Unit {unit} is not a {expected} unit")]
+    UnexpectedUnitType { unit: String, expected: UnitType },
+
+    #[error("Invalid unit file, {} problem(s) found", .0.len())]
+    InvalidUnitConfig(Vec<ConfigError>),
+
+    #[error("Invalid time span: {0}")]
+    InvalidTimespan(String),
+
+    #[error("Invalid timestamp: {0}")]
+    InvalidTimestamp(String),
+
+    #[error("Invalid calendar event: {0}")]
+    InvalidCalendarSpec(String),
+
+    #[error("Invalid transient unit {unit}: {reason}")]
+    InvalidTransientUnit { unit: String, reason: &'static str },
+
+    #[error("Unknown time zone: {0}")]
+    UnknownTimeZone(String),
 }
@@ -0,0 +1,95 @@
+//! # D-Bus interface proxy for: `org.freedesktop.systemd1.Job`
+//!
+//! Job objects live at "/org/freedesktop/systemd1/job/<id>", so there is no default path and the
+//! path of the job has to be given when building the proxy.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+use zbus::proxy;
+#[proxy(
+    interface = "org.freedesktop.systemd1.Job",
+    default_service = "org.freedesktop.systemd1"
+)]
+pub trait Job {
+    /// # Cancel()
+    /// ## METHOD
+    /// Cancels the job. Note that this will remove a job from the queue if it is not yet executed
+    /// but generally will not cause a job that is already in the process of being executed to be
+    /// aborted. This operation may also be requested via the CancelJob() method of the Manager
+    /// object.
+    fn cancel(&self) -> zbus::Result<()>;
+
+    /// # GetAfter()
+    /// ## METHOD
+    /// Returns an array of jobs that are waiting for this job to complete before they can be
+    /// executed, i.e. the jobs ordered after it. The structures have the same format as the ones
+    /// returned by ListJobs() on the Manager object.
+    #[allow(clippy::type_complexity)]
+    fn get_after(
+        &self,
+    ) -> zbus::Result<
+        Vec<(
+            u32,
+            String,
+            String,
+            String,
+            zbus::zvariant::OwnedObjectPath,
+            zbus::zvariant::OwnedObjectPath,
+        )>,
+    >;
+
+    /// # GetBefore()
+    /// ## METHOD
+    /// Returns an array of jobs this job is waiting for before it can be executed, i.e. the jobs
+    /// ordered before it. The structures have the same format as the ones returned by
+    /// GetAfter().
+    #[allow(clippy::type_complexity)]
+    fn get_before(
+        &self,
+    ) -> zbus::Result<
+        Vec<(
+            u32,
+            String,
+            String,
+            String,
+            zbus::zvariant::OwnedObjectPath,
+            zbus::zvariant::OwnedObjectPath,
+        )>,
+    >;
+
+    /// # ActivationDetails
+    /// ## PROPERTY
+    /// Describes what caused the job to be enqueued, as pairs of field name and value, e.g. the
+    /// unit that triggered this one.
+    #[zbus(property)]
+    fn activation_details(&self) -> zbus::Result<Vec<(String, String)>>;
+
+    /// # Id
+    /// ## PROPERTY
+    /// The numeric job id.
+    #[zbus(property)]
+    fn id(&self) -> zbus::Result<u32>;
+
+    /// # JobType
+    /// ## PROPERTY
+    /// The job type as string, e.g. "start", "stop" or "restart".
+    #[zbus(property)]
+    fn job_type(&self) -> zbus::Result<String>;
+
+    /// # State
+    /// ## PROPERTY
+    /// The job state as string, either "waiting" or "running". "waiting" indicates that a job is
+    /// queued but has not begun to execute yet. "running" indicates that a job is currently being
+    /// executed.
+    #[zbus(property)]
+    fn state(&self) -> zbus::Result<String>;
+
+    /// # Unit
+    /// ## PROPERTY
+    /// The unit this job belongs to. It is a structure consisting of the name of the unit and a
+    /// bus path to the unit's object.
+    #[zbus(property)]
+    fn unit(&self) -> zbus::Result<(String, zbus::zvariant::OwnedObjectPath)>;
+}
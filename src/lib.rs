@@ -79,18 +79,79 @@
 //! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
 //! [D-Bus standard interfaces]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces,
 
+pub mod automount;
 pub mod errors;
+pub mod job;
+pub mod login1;
 pub mod manager;
+pub mod mount;
+pub mod service;
 pub mod systemctl;
+pub mod unit;
+pub use automount::AutomountProxy;
+pub use job::JobProxy;
+pub use login1::inhibitor::{InhibitLock, InhibitMode, InhibitWhat, Inhibitor};
+pub use login1::loginctl::loginctl_async::LoginCtlBuilder;
+pub use login1::loginctl::loginctl_blocking::LoginCtlBlockingBuilder;
+pub use login1::loginctl::seat::{Seat, SeatDetails};
+pub use login1::loginctl::session::{
+    Session, SessionClass, SessionDetails, SessionState, SessionType,
+};
+pub use login1::loginctl::user::{User, UserDetails, UserState};
+pub use login1::manager::ManagerProxy as LoginManagerProxy;
 pub use manager::ManagerProxy;
-pub use systemctl::job::Job;
+pub use mount::MountProxy;
+pub use service::ServiceProxy;
+pub use systemctl::clean::CleanMask;
+pub use systemctl::connection_level::ConnectionLevel;
+pub use systemctl::delta::{Delta, DeltaType};
+pub use systemctl::freezer::{FreezeGuard, FreezerState};
+pub use systemctl::ini::Ini;
+pub use systemctl::install::InstallInfo;
+pub use systemctl::job::{EnqueuedTransaction, Job, JobDependencies, JobState, JobType};
+pub use systemctl::logging::{LogLevel, LogTarget, ShowStatus};
+pub use systemctl::lookup_paths::{
+    LookupPaths, OverriddenUnitFile, UnitDir, UnitDirKind, UnitFileLookup,
+};
+pub use systemctl::offline::OfflineSystemCtl;
+pub use systemctl::power::{PowerAction, PowerCapability, ScheduledShutdown};
+pub use systemctl::preset::{PresetAction, PresetRule, Presets};
+pub use systemctl::process::{CgroupNode, ProcessOwner, UnitProcess};
+pub use systemctl::reset_failed::ResetFailedSelector;
+pub use systemctl::run::{
+    CommandOutput, CommandResult, ExitStatus, RunningCommand, ServiceResult, TransientCommand,
+};
+pub use systemctl::security::{ExposureLevel, SandboxSettings, SecurityFinding, SecurityReport};
+pub use systemctl::system_state::{SystemRunningState, SystemState};
 pub use systemctl::systemctl_async::SystemCtlBuilder;
 pub use systemctl::systemctl_blocking::SystemCtlBlockingBuilder;
+pub use systemctl::time::{
+    CivilDateTime,
+    calendar::CalendarSpec,
+    timespan::{format_timespan, parse_timespan},
+    timestamp::{format_timestamp, parse_timestamp},
+    tz::TimeZone,
+};
+pub use systemctl::transient::{TransientMount, TransientTimer};
 pub use systemctl::unit::{
     Unit, UnitActiveState, UnitChangeKind, UnitEnablementChange, UnitEnablementResponse,
     UnitLoadState, UnitMode,
 };
-pub use systemctl::unit_file::{EnablementStatus, UnitFile};
+pub use systemctl::unit_config::{
+    ConfigError, ConfigErrorKind, UnitSection,
+    exec::{ExecSettings, KillMode, ProtectHome, ProtectSystem},
+    service::{
+        ExitType, NotifyAccess, RestartPolicy, ServiceSection, ServiceType, ServiceUnitConfig,
+    },
+    socket::{BindIPv6Only, SocketSection, SocketUnitConfig},
+    timer::{TimerSection, TimerUnitConfig},
+};
+pub use systemctl::unit_file::{EnablementStatus, UnitFile, UnitFileQuery};
+pub use systemctl::unit_name::{UnitName, UnitType};
+pub use systemctl::unit_query::UnitQuery;
+pub use systemctl::verify::{Diagnostic, DiagnosticKind, Severity, Verifier};
+pub use systemctl::watchdog::Watchdog;
+pub use unit::UnitProxy;
 
 pub use zbus::{Connection, zvariant::OwnedObjectPath};
 
@@ -0,0 +1,236 @@
+use std::{
+    fmt::Display,
+    os::fd::{AsFd, BorrowedFd, OwnedFd},
+};
+
+// NOTE: These docs are mostly from the man page of org.freedesktop.login1
+
+/// What an inhibitor lock inhibits.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum InhibitWhat {
+    /// Inhibits system power-off and reboot
+    Shutdown,
+    /// Inhibits system suspend and hibernation
+    Sleep,
+    /// Inhibits the system from going idle
+    Idle,
+    /// Inhibits the low-level handling of the power key
+    HandlePowerKey,
+    /// Inhibits the low-level handling of the suspend key
+    HandleSuspendKey,
+    /// Inhibits the low-level handling of the hibernate key
+    HandleHibernateKey,
+    /// Inhibits the low-level handling of the lid switch
+    HandleLidSwitch,
+    /// Inhibits the low-level handling of the reboot key
+    HandleRebootKey,
+    Other(String),
+}
+
+impl From<&str> for InhibitWhat {
+    fn from(value: &str) -> Self {
+        match value {
+            "shutdown" => InhibitWhat::Shutdown,
+            "sleep" => InhibitWhat::Sleep,
+            "idle" => InhibitWhat::Idle,
+            "handle-power-key" => InhibitWhat::HandlePowerKey,
+            "handle-suspend-key" => InhibitWhat::HandleSuspendKey,
+            "handle-hibernate-key" => InhibitWhat::HandleHibernateKey,
+            "handle-lid-switch" => InhibitWhat::HandleLidSwitch,
+            "handle-reboot-key" => InhibitWhat::HandleRebootKey,
+            _ => InhibitWhat::Other(value.to_string()),
+        }
+    }
+}
+
+impl Display for InhibitWhat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            InhibitWhat::Shutdown => "shutdown",
+            InhibitWhat::Sleep => "sleep",
+            InhibitWhat::Idle => "idle",
+            InhibitWhat::HandlePowerKey => "handle-power-key",
+            InhibitWhat::HandleSuspendKey => "handle-suspend-key",
+            InhibitWhat::HandleHibernateKey => "handle-hibernate-key",
+            InhibitWhat::HandleLidSwitch => "handle-lid-switch",
+            InhibitWhat::HandleRebootKey => "handle-reboot-key",
+            InhibitWhat::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum InhibitMode {
+    /// The operation is refused for as long as the lock is held
+    Block,
+    /// Like block, but only applies to unprivileged callers
+    BlockWeak,
+    /// The operation is delayed for a limited time, giving the holder a chance to prepare for it
+    Delay,
+    Other(String),
+}
+
+impl From<String> for InhibitMode {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "block" => InhibitMode::Block,
+            "block-weak" => InhibitMode::BlockWeak,
+            "delay" => InhibitMode::Delay,
+            _ => InhibitMode::Other(value),
+        }
+    }
+}
+
+impl Display for InhibitMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            InhibitMode::Block => "block",
+            InhibitMode::BlockWeak => "block-weak",
+            InhibitMode::Delay => "delay",
+            InhibitMode::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+/// An active inhibitor lock, as listed by `systemd-inhibit --list`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Inhibitor {
+    /// What the lock inhibits
+    pub what: Vec<InhibitWhat>,
+    /// A human readable name of the program holding the lock
+    pub who: String,
+    /// A human readable reason for holding the lock
+    pub why: String,
+    /// The lock mode
+    pub mode: InhibitMode,
+    /// The user id of the holder
+    pub uid: u32,
+    /// The process id of the holder
+    pub pid: u32,
+}
+
+impl Inhibitor {
+    /// Whether this lock inhibits the given operation.
+    pub fn inhibits(&self, what: &InhibitWhat) -> bool {
+        self.what.contains(what)
+    }
+
+    /// Whether this lock refuses the operation outright, rather than just delaying it.
+    pub fn is_blocking(&self) -> bool {
+        matches!(self.mode, InhibitMode::Block | InhibitMode::BlockWeak)
+    }
+
+    /// Describes who holds the lock and why, the way systemctl does when an operation is
+    /// inhibited.
+    pub fn description(&self) -> String {
+        format!(
+            "\"{}\" (PID {}, UID {}), reason is \"{}\"",
+            self.who, self.pid, self.uid, self.why
+        )
+    }
+}
+
+impl From<(String, String, String, String, u32, u32)> for Inhibitor {
+    fn from(value: (String, String, String, String, u32, u32)) -> Self {
+        Self {
+            what: value
+                .0
+                .split(':')
+                .filter(|what| !what.is_empty())
+                .map(InhibitWhat::from)
+                .collect(),
+            who: value.1,
+            why: value.2,
+            mode: value.3.into(),
+            uid: value.4,
+            pid: value.5,
+        }
+    }
+}
+
+/// An inhibitor lock taken with Inhibit(), equivalent to what `systemd-inhibit` holds while its
+/// command runs. logind keeps the lock for as long as the file descriptor in here is open, so the
+/// lock is released when this value is dropped, or with [`InhibitLock::release`].
+#[derive(Debug)]
+pub struct InhibitLock {
+    fd: OwnedFd,
+    what: Vec<InhibitWhat>,
+    mode: InhibitMode,
+}
+
+impl InhibitLock {
+    pub(crate) fn new(fd: OwnedFd, what: &[InhibitWhat], mode: InhibitMode) -> Self {
+        Self {
+            fd,
+            what: what.to_vec(),
+            mode,
+        }
+    }
+
+    /// What the lock inhibits.
+    pub fn what(&self) -> &[InhibitWhat] {
+        &self.what
+    }
+
+    /// The lock mode.
+    pub fn mode(&self) -> &InhibitMode {
+        &self.mode
+    }
+
+    /// Releases the lock. This is the same as dropping it, but makes the intent explicit.
+    pub fn release(self) {}
+}
+
+impl AsFd for InhibitLock {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+/// Joins the operations to inhibit into the colon separated list Inhibit() expects.
+pub(crate) fn join_what(what: &[InhibitWhat]) -> String {
+    what.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_colon_separated_what() {
+        let inhibitor: Inhibitor = (
+            "shutdown:sleep:handle-lid-switch".to_string(),
+            "backup".to_string(),
+            "Backup in progress".to_string(),
+            "block".to_string(),
+            1000,
+            42,
+        )
+            .into();
+
+        assert_eq!(
+            inhibitor.what,
+            vec![
+                InhibitWhat::Shutdown,
+                InhibitWhat::Sleep,
+                InhibitWhat::HandleLidSwitch
+            ]
+        );
+        assert!(inhibitor.inhibits(&InhibitWhat::Sleep));
+        assert!(!inhibitor.inhibits(&InhibitWhat::Idle));
+        assert!(inhibitor.is_blocking());
+        assert_eq!(
+            join_what(&inhibitor.what),
+            "shutdown:sleep:handle-lid-switch"
+        );
+        assert_eq!(
+            inhibitor.description(),
+            "\"backup\" (PID 42, UID 1000), reason is \"Backup in progress\""
+        );
+    }
+}
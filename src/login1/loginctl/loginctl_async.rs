@@ -0,0 +1,303 @@
+use zbus::Connection;
+
+use crate::{
+    errors::SystemdError,
+    login1::{
+        inhibitor::{self, InhibitLock, InhibitMode, InhibitWhat},
+        loginctl::{
+            seat::{Seat, SeatDetails},
+            session::{Session, SessionDetails, non_empty},
+            user::{User, UserDetails},
+        },
+        manager::ManagerProxy,
+        seat::SeatProxy,
+        session::SessionProxy,
+        user::UserProxy,
+    },
+};
+
+pub struct LoginCtlBuilder {
+    interactive: bool,
+}
+
+impl LoginCtlBuilder {
+    pub fn new() -> Self {
+        Self { interactive: false }
+    }
+
+    /// Allow logind to ask the user for authentication through polkit, for the operations that
+    /// support it. By default, operations that need authorization fail instead.
+    pub fn with_interactive_authorization(mut self) -> Self {
+        self.interactive = true;
+        self
+    }
+
+    /// logind only lives on the system bus, so this always connects to the system bus.
+    pub async fn init<'a>(self) -> Result<LoginCtl<'a>, SystemdError> {
+        let connection = Connection::system().await?;
+        let proxy = ManagerProxy::new(&connection).await?;
+        Ok(LoginCtl {
+            manager_proxy: proxy,
+            interactive: self.interactive,
+        })
+    }
+}
+
+impl Default for LoginCtlBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct LoginCtl<'a> {
+    manager_proxy: ManagerProxy<'a>,
+    interactive: bool,
+}
+
+impl<'a> LoginCtl<'a> {
+    /// Get access to the logind manager proxy directly, i.e. everything in
+    /// org.freedesktop.login1.Manager.
+    pub fn get_manager_proxy(&self) -> &ManagerProxy<'a> {
+        &self.manager_proxy
+    }
+
+    /// Lists all current sessions, equivalent to `loginctl list-sessions`.
+    pub async fn list_sessions(&self) -> Result<Vec<Session>, SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .list_sessions()
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Returns a proxy for the session object, giving access to everything in
+    /// org.freedesktop.login1.Session.
+    pub async fn get_session_proxy(&self, id: &str) -> Result<SessionProxy<'static>, SystemdError> {
+        let path = self.get_manager_proxy().get_session(id).await?;
+
+        Ok(
+            SessionProxy::builder(self.get_manager_proxy().inner().connection())
+                .path(path)?
+                .build()
+                .await?,
+        )
+    }
+
+    /// Returns the properties of the session, equivalent to `loginctl session-status`.
+    pub async fn session_details(&self, id: &str) -> Result<SessionDetails, SystemdError> {
+        let session = self.get_session_proxy(id).await?;
+
+        Ok(SessionDetails {
+            id: session.id().await?,
+            uid: session.user().await?.0,
+            user_name: session.name().await?,
+            seat_id: non_empty(session.seat().await?.0),
+            tty: non_empty(session.tty().await?),
+            remote_host: non_empty(session.remote_host().await?),
+            remote: session.remote().await?,
+            service: session.service().await?,
+            scope: session.scope().await?,
+            leader: session.leader().await?,
+            session_type: session.session_type().await?.into(),
+            class: session.class().await?.into(),
+            state: session.state().await?.into(),
+            active: session.active().await?,
+            idle: session.idle_hint().await?,
+        })
+    }
+
+    /// Forcibly terminates the session, killing all of its processes. Equivalent to
+    /// `loginctl terminate-session`.
+    pub async fn terminate_session(&self, id: &str) -> Result<(), SystemdError> {
+        Ok(self.get_manager_proxy().terminate_session(id).await?)
+    }
+
+    /// Asks the session to activate the screen lock, equivalent to `loginctl lock-session`.
+    pub async fn lock_session(&self, id: &str) -> Result<(), SystemdError> {
+        Ok(self.get_manager_proxy().lock_session(id).await?)
+    }
+
+    /// Asks the session to deactivate the screen lock, equivalent to `loginctl unlock-session`.
+    pub async fn unlock_session(&self, id: &str) -> Result<(), SystemdError> {
+        Ok(self.get_manager_proxy().unlock_session(id).await?)
+    }
+
+    /// Lists all currently logged in or lingering users, equivalent to `loginctl list-users`.
+    pub async fn list_users(&self) -> Result<Vec<User>, SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .list_users()
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Returns a proxy for the user object, giving access to everything in
+    /// org.freedesktop.login1.User. Fails if the user is neither logged in nor lingering.
+    pub async fn get_user_proxy(&self, uid: u32) -> Result<UserProxy<'static>, SystemdError> {
+        let path = self.get_manager_proxy().get_user(uid).await?;
+
+        Ok(
+            UserProxy::builder(self.get_manager_proxy().inner().connection())
+                .path(path)?
+                .build()
+                .await?,
+        )
+    }
+
+    /// Returns the properties of the user, equivalent to `loginctl user-status`.
+    pub async fn user_details(&self, uid: u32) -> Result<UserDetails, SystemdError> {
+        let user = self.get_user_proxy(uid).await?;
+
+        Ok(UserDetails {
+            uid: user.uid().await?,
+            gid: user.gid().await?,
+            name: user.name().await?,
+            state: user.state().await?.into(),
+            linger: user.linger().await?,
+            sessions: user
+                .sessions()
+                .await?
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect(),
+            runtime_path: user.runtime_path().await?,
+            service: user.service().await?,
+            slice: user.slice().await?,
+        })
+    }
+
+    /// Forcibly terminates all sessions of the user, equivalent to `loginctl terminate-user`.
+    pub async fn terminate_user(&self, uid: u32) -> Result<(), SystemdError> {
+        Ok(self.get_manager_proxy().terminate_user(uid).await?)
+    }
+
+    /// Whether lingering is enabled for the user, i.e. whether the user service manager keeps
+    /// running while the user is not logged in. Users logind does not know about are not
+    /// lingering.
+    pub async fn is_lingering(&self, uid: u32) -> Result<bool, SystemdError> {
+        match self.get_user_proxy(uid).await {
+            Ok(user) => Ok(user.linger().await?),
+            Err(SystemdError::DbusError(error)) if is_no_such_user(&error) => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Enables or disables lingering for the user, equivalent to `loginctl enable-linger` and
+    /// `loginctl disable-linger`. Enabling it starts the user service manager right away, and at
+    /// every boot, so that `--user` units of the user keep running without a login.
+    pub async fn set_user_linger(&self, uid: u32, enable: bool) -> Result<(), SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .set_user_linger(uid, enable, self.interactive)
+            .await?)
+    }
+
+    /// Lists all currently available seats, equivalent to `loginctl list-seats`.
+    pub async fn list_seats(&self) -> Result<Vec<Seat>, SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .list_seats()
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Returns a proxy for the seat object, giving access to everything in
+    /// org.freedesktop.login1.Seat.
+    pub async fn get_seat_proxy(&self, id: &str) -> Result<SeatProxy<'static>, SystemdError> {
+        let path = self.get_manager_proxy().get_seat(id).await?;
+
+        Ok(
+            SeatProxy::builder(self.get_manager_proxy().inner().connection())
+                .path(path)?
+                .build()
+                .await?,
+        )
+    }
+
+    /// Returns the properties of the seat, equivalent to `loginctl seat-status`.
+    pub async fn seat_details(&self, id: &str) -> Result<SeatDetails, SystemdError> {
+        let seat = self.get_seat_proxy(id).await?;
+
+        Ok(SeatDetails {
+            id: seat.id().await?,
+            active_session: non_empty(seat.active_session().await?.0),
+            sessions: seat
+                .sessions()
+                .await?
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect(),
+            can_graphical: seat.can_graphical().await?,
+            can_tty: seat.can_tty().await?,
+            idle: seat.idle_hint().await?,
+        })
+    }
+
+    /// Forcibly terminates all sessions on the seat, equivalent to `loginctl terminate-seat`.
+    pub async fn terminate_seat(&self, id: &str) -> Result<(), SystemdError> {
+        Ok(self.get_manager_proxy().terminate_seat(id).await?)
+    }
+
+    /// Takes an inhibitor lock, equivalent to `systemd-inhibit`. The lock is held until the
+    /// returned guard is dropped. who and why are shown to users in `systemd-inhibit --list`.
+    pub async fn inhibit(
+        &self,
+        what: &[InhibitWhat],
+        who: &str,
+        why: &str,
+        mode: InhibitMode,
+    ) -> Result<InhibitLock, SystemdError> {
+        let fd = self
+            .get_manager_proxy()
+            .inhibit(&inhibitor::join_what(what), who, why, &mode.to_string())
+            .await?;
+
+        Ok(InhibitLock::new(fd.into(), what, mode))
+    }
+}
+
+pub(crate) fn is_no_such_user(error: &zbus::Error) -> bool {
+    matches!(
+        error,
+        zbus::Error::MethodError(name, _, _) if name.as_str() == "org.freedesktop.login1.NoSuchUser"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_list_sessions() {
+        let res: Result<(), SystemdError> = smol::block_on(async {
+            let loginctl = LoginCtlBuilder::new().init().await?;
+            let sessions = loginctl.list_sessions().await?;
+
+            for session in sessions {
+                let details = loginctl.session_details(&session.id).await?;
+                assert_eq!(details.id, session.id);
+                assert_eq!(details.uid, session.uid);
+            }
+
+            Ok(())
+        });
+
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn users_unknown_to_logind_are_not_lingering() {
+        let res: Result<bool, SystemdError> = smol::block_on(async {
+            let loginctl = LoginCtlBuilder::new().init().await?;
+            loginctl.is_lingering(u32::MAX - 1).await
+        });
+
+        assert!(!res.expect("Unknown users should not be an error"));
+    }
+}
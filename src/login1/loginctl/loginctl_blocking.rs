@@ -0,0 +1,273 @@
+use zbus::blocking::Connection;
+
+use crate::{
+    errors::SystemdError,
+    login1::{
+        inhibitor::{self, InhibitLock, InhibitMode, InhibitWhat},
+        loginctl::loginctl_async::is_no_such_user,
+        loginctl::{
+            seat::{Seat, SeatDetails},
+            session::{Session, SessionDetails, non_empty},
+            user::{User, UserDetails},
+        },
+        manager::ManagerProxyBlocking,
+        seat::SeatProxyBlocking,
+        session::SessionProxyBlocking,
+        user::UserProxyBlocking,
+    },
+};
+
+pub struct LoginCtlBlockingBuilder {
+    interactive: bool,
+}
+
+impl LoginCtlBlockingBuilder {
+    pub fn new() -> Self {
+        Self { interactive: false }
+    }
+
+    /// Allow logind to ask the user for authentication through polkit, for the operations that
+    /// support it. By default, operations that need authorization fail instead.
+    pub fn with_interactive_authorization(mut self) -> Self {
+        self.interactive = true;
+        self
+    }
+
+    /// logind only lives on the system bus, so this always connects to the system bus.
+    pub fn init<'a>(self) -> Result<LoginCtlBlocking<'a>, SystemdError> {
+        let connection = Connection::system()?;
+        let proxy = ManagerProxyBlocking::new(&connection)?;
+        Ok(LoginCtlBlocking {
+            manager_proxy: proxy,
+            interactive: self.interactive,
+        })
+    }
+}
+
+impl Default for LoginCtlBlockingBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct LoginCtlBlocking<'a> {
+    manager_proxy: ManagerProxyBlocking<'a>,
+    interactive: bool,
+}
+
+impl<'a> LoginCtlBlocking<'a> {
+    /// Get access to the logind manager proxy directly, i.e. everything in
+    /// org.freedesktop.login1.Manager.
+    pub fn get_manager_proxy(&self) -> &ManagerProxyBlocking<'a> {
+        &self.manager_proxy
+    }
+
+    /// Lists all current sessions, equivalent to `loginctl list-sessions`.
+    pub fn list_sessions(&self) -> Result<Vec<Session>, SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .list_sessions()?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Returns a proxy for the session object, giving access to everything in
+    /// org.freedesktop.login1.Session.
+    pub fn get_session_proxy(
+        &self,
+        id: &str,
+    ) -> Result<SessionProxyBlocking<'static>, SystemdError> {
+        let path = self.get_manager_proxy().get_session(id)?;
+
+        Ok(
+            SessionProxyBlocking::builder(self.get_manager_proxy().inner().connection())
+                .path(path)?
+                .build()?,
+        )
+    }
+
+    /// Returns the properties of the session, equivalent to `loginctl session-status`.
+    pub fn session_details(&self, id: &str) -> Result<SessionDetails, SystemdError> {
+        let session = self.get_session_proxy(id)?;
+
+        Ok(SessionDetails {
+            id: session.id()?,
+            uid: session.user()?.0,
+            user_name: session.name()?,
+            seat_id: non_empty(session.seat()?.0),
+            tty: non_empty(session.tty()?),
+            remote_host: non_empty(session.remote_host()?),
+            remote: session.remote()?,
+            service: session.service()?,
+            scope: session.scope()?,
+            leader: session.leader()?,
+            session_type: session.session_type()?.into(),
+            class: session.class()?.into(),
+            state: session.state()?.into(),
+            active: session.active()?,
+            idle: session.idle_hint()?,
+        })
+    }
+
+    /// Forcibly terminates the session, killing all of its processes. Equivalent to
+    /// `loginctl terminate-session`.
+    pub fn terminate_session(&self, id: &str) -> Result<(), SystemdError> {
+        Ok(self.get_manager_proxy().terminate_session(id)?)
+    }
+
+    /// Asks the session to activate the screen lock, equivalent to `loginctl lock-session`.
+    pub fn lock_session(&self, id: &str) -> Result<(), SystemdError> {
+        Ok(self.get_manager_proxy().lock_session(id)?)
+    }
+
+    /// Asks the session to deactivate the screen lock, equivalent to `loginctl unlock-session`.
+    pub fn unlock_session(&self, id: &str) -> Result<(), SystemdError> {
+        Ok(self.get_manager_proxy().unlock_session(id)?)
+    }
+
+    /// Lists all currently logged in or lingering users, equivalent to `loginctl list-users`.
+    pub fn list_users(&self) -> Result<Vec<User>, SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .list_users()?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Returns a proxy for the user object, giving access to everything in
+    /// org.freedesktop.login1.User. Fails if the user is neither logged in nor lingering.
+    pub fn get_user_proxy(&self, uid: u32) -> Result<UserProxyBlocking<'static>, SystemdError> {
+        let path = self.get_manager_proxy().get_user(uid)?;
+
+        Ok(
+            UserProxyBlocking::builder(self.get_manager_proxy().inner().connection())
+                .path(path)?
+                .build()?,
+        )
+    }
+
+    /// Returns the properties of the user, equivalent to `loginctl user-status`.
+    pub fn user_details(&self, uid: u32) -> Result<UserDetails, SystemdError> {
+        let user = self.get_user_proxy(uid)?;
+
+        Ok(UserDetails {
+            uid: user.uid()?,
+            gid: user.gid()?,
+            name: user.name()?,
+            state: user.state()?.into(),
+            linger: user.linger()?,
+            sessions: user.sessions()?.into_iter().map(|(id, _)| id).collect(),
+            runtime_path: user.runtime_path()?,
+            service: user.service()?,
+            slice: user.slice()?,
+        })
+    }
+
+    /// Forcibly terminates all sessions of the user, equivalent to `loginctl terminate-user`.
+    pub fn terminate_user(&self, uid: u32) -> Result<(), SystemdError> {
+        Ok(self.get_manager_proxy().terminate_user(uid)?)
+    }
+
+    /// Whether lingering is enabled for the user, i.e. whether the user service manager keeps
+    /// running while the user is not logged in. Users logind does not know about are not
+    /// lingering.
+    pub fn is_lingering(&self, uid: u32) -> Result<bool, SystemdError> {
+        match self.get_user_proxy(uid) {
+            Ok(user) => Ok(user.linger()?),
+            Err(SystemdError::DbusError(error)) if is_no_such_user(&error) => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Enables or disables lingering for the user, equivalent to `loginctl enable-linger` and
+    /// `loginctl disable-linger`. Enabling it starts the user service manager right away, and at
+    /// every boot, so that `--user` units of the user keep running without a login.
+    pub fn set_user_linger(&self, uid: u32, enable: bool) -> Result<(), SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .set_user_linger(uid, enable, self.interactive)?)
+    }
+
+    /// Lists all currently available seats, equivalent to `loginctl list-seats`.
+    pub fn list_seats(&self) -> Result<Vec<Seat>, SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .list_seats()?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Returns a proxy for the seat object, giving access to everything in
+    /// org.freedesktop.login1.Seat.
+    pub fn get_seat_proxy(&self, id: &str) -> Result<SeatProxyBlocking<'static>, SystemdError> {
+        let path = self.get_manager_proxy().get_seat(id)?;
+
+        Ok(
+            SeatProxyBlocking::builder(self.get_manager_proxy().inner().connection())
+                .path(path)?
+                .build()?,
+        )
+    }
+
+    /// Returns the properties of the seat, equivalent to `loginctl seat-status`.
+    pub fn seat_details(&self, id: &str) -> Result<SeatDetails, SystemdError> {
+        let seat = self.get_seat_proxy(id)?;
+
+        Ok(SeatDetails {
+            id: seat.id()?,
+            active_session: non_empty(seat.active_session()?.0),
+            sessions: seat.sessions()?.into_iter().map(|(id, _)| id).collect(),
+            can_graphical: seat.can_graphical()?,
+            can_tty: seat.can_tty()?,
+            idle: seat.idle_hint()?,
+        })
+    }
+
+    /// Forcibly terminates all sessions on the seat, equivalent to `loginctl terminate-seat`.
+    pub fn terminate_seat(&self, id: &str) -> Result<(), SystemdError> {
+        Ok(self.get_manager_proxy().terminate_seat(id)?)
+    }
+
+    /// Takes an inhibitor lock, equivalent to `systemd-inhibit`. The lock is held until the
+    /// returned guard is dropped. who and why are shown to users in `systemd-inhibit --list`.
+    pub fn inhibit(
+        &self,
+        what: &[InhibitWhat],
+        who: &str,
+        why: &str,
+        mode: InhibitMode,
+    ) -> Result<InhibitLock, SystemdError> {
+        let fd = self.get_manager_proxy().inhibit(
+            &inhibitor::join_what(what),
+            who,
+            why,
+            &mode.to_string(),
+        )?;
+
+        Ok(InhibitLock::new(fd.into(), what, mode))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_list_users() {
+        let loginctl = LoginCtlBlockingBuilder::new()
+            .init()
+            .expect("Should be able to initialise connection");
+
+        let users = loginctl.list_users().expect("Should be able to list users");
+
+        for user in users {
+            let details = loginctl
+                .user_details(user.uid)
+                .expect("Listed users are known to logind");
+            assert_eq!(details.name, user.name);
+        }
+    }
+}
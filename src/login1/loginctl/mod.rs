@@ -0,0 +1,6 @@
+//! Typed access to logind, similar to what `loginctl` offers
+pub mod loginctl_async;
+pub mod loginctl_blocking;
+pub mod seat;
+pub mod session;
+pub mod user;
@@ -0,0 +1,36 @@
+use zbus::zvariant::OwnedObjectPath;
+
+/// A seat as listed by ListSeats(), equivalent to a line of `loginctl list-seats`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Seat {
+    /// The seat ID, e.g. "seat0"
+    pub id: String,
+    /// The seat object path
+    pub object_path: OwnedObjectPath,
+}
+
+impl From<(String, OwnedObjectPath)> for Seat {
+    fn from(value: (String, OwnedObjectPath)) -> Self {
+        Self {
+            id: value.0,
+            object_path: value.1,
+        }
+    }
+}
+
+/// The properties of a seat, equivalent to `loginctl seat-status`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SeatDetails {
+    /// The seat ID
+    pub id: String,
+    /// The ID of the session in the foreground, if any
+    pub active_session: Option<String>,
+    /// The IDs of all sessions on the seat
+    pub sessions: Vec<String>,
+    /// Whether the seat is suitable for graphical sessions
+    pub can_graphical: bool,
+    /// Whether the seat is suitable for text logins
+    pub can_tty: bool,
+    /// Whether all sessions on the seat are idle
+    pub idle: bool,
+}
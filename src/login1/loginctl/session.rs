@@ -0,0 +1,204 @@
+use std::fmt::Display;
+
+use zbus::zvariant::OwnedObjectPath;
+
+// NOTE: These docs are mostly from the man page of org.freedesktop.login1
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SessionState {
+    /// The user is logged in, but the session is not in the foreground
+    Online,
+    /// The user is logged in and the session is in the foreground of its seat
+    Active,
+    /// The user logged out, but some processes of the session are still around
+    Closing,
+    Other(String),
+}
+
+impl From<String> for SessionState {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "online" => SessionState::Online,
+            "active" => SessionState::Active,
+            "closing" => SessionState::Closing,
+            _ => SessionState::Other(value),
+        }
+    }
+}
+
+impl Display for SessionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            SessionState::Online => "online",
+            SessionState::Active => "active",
+            SessionState::Closing => "closing",
+            SessionState::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SessionClass {
+    /// A regular user session
+    User,
+    /// A display manager login screen
+    Greeter,
+    /// A screen lock
+    LockScreen,
+    /// A session without a seat or TTY, e.g. a cron job
+    Background,
+    /// The session of the user service manager
+    Manager,
+    Other(String),
+}
+
+impl From<String> for SessionClass {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "user" => SessionClass::User,
+            "greeter" => SessionClass::Greeter,
+            "lock-screen" => SessionClass::LockScreen,
+            "background" => SessionClass::Background,
+            "manager" => SessionClass::Manager,
+            _ => SessionClass::Other(value),
+        }
+    }
+}
+
+impl Display for SessionClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            SessionClass::User => "user",
+            SessionClass::Greeter => "greeter",
+            SessionClass::LockScreen => "lock-screen",
+            SessionClass::Background => "background",
+            SessionClass::Manager => "manager",
+            SessionClass::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SessionType {
+    Unspecified,
+    Tty,
+    X11,
+    Wayland,
+    Mir,
+    Web,
+    Other(String),
+}
+
+impl From<String> for SessionType {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "unspecified" => SessionType::Unspecified,
+            "tty" => SessionType::Tty,
+            "x11" => SessionType::X11,
+            "wayland" => SessionType::Wayland,
+            "mir" => SessionType::Mir,
+            "web" => SessionType::Web,
+            _ => SessionType::Other(value),
+        }
+    }
+}
+
+impl Display for SessionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            SessionType::Unspecified => "unspecified",
+            SessionType::Tty => "tty",
+            SessionType::X11 => "x11",
+            SessionType::Wayland => "wayland",
+            SessionType::Mir => "mir",
+            SessionType::Web => "web",
+            SessionType::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+/// A session as listed by ListSessions(), equivalent to a line of `loginctl list-sessions`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Session {
+    /// The session ID
+    pub id: String,
+    /// The user ID of the session owner
+    pub uid: u32,
+    /// The user name of the session owner
+    pub user_name: String,
+    /// The seat the session is attached to, if any
+    pub seat_id: Option<String>,
+    /// The session object path
+    pub object_path: OwnedObjectPath,
+}
+
+impl From<(String, u32, String, String, OwnedObjectPath)> for Session {
+    fn from(value: (String, u32, String, String, OwnedObjectPath)) -> Self {
+        Self {
+            id: value.0,
+            uid: value.1,
+            user_name: value.2,
+            seat_id: (!value.3.is_empty()).then_some(value.3),
+            object_path: value.4,
+        }
+    }
+}
+
+/// The properties of a session, equivalent to `loginctl session-status`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SessionDetails {
+    /// The session ID
+    pub id: String,
+    /// The user ID of the session owner
+    pub uid: u32,
+    /// The user name of the session owner
+    pub user_name: String,
+    /// The seat the session is attached to, if any
+    pub seat_id: Option<String>,
+    /// The TTY of the session, if any
+    pub tty: Option<String>,
+    /// The remote host of the session, if this is a remote session and the host is known
+    pub remote_host: Option<String>,
+    /// Whether the session is remote
+    pub remote: bool,
+    /// The PAM service that registered the session
+    pub service: String,
+    /// The scope unit the processes of the session are in
+    pub scope: String,
+    /// The process ID of the session leader
+    pub leader: u32,
+    pub session_type: SessionType,
+    pub class: SessionClass,
+    pub state: SessionState,
+    /// Whether the session is in the foreground of its seat
+    pub active: bool,
+    /// Whether the session is idle
+    pub idle: bool,
+}
+
+pub(crate) fn non_empty(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_without_seat_have_no_seat_id() {
+        let session: Session = (
+            "c1".to_string(),
+            1000,
+            "user".to_string(),
+            String::new(),
+            OwnedObjectPath::try_from("/org/freedesktop/login1/session/c1")
+                .expect("This is a valid object path"),
+        )
+            .into();
+
+        assert_eq!(session.seat_id, None);
+    }
+}
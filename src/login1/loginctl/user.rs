@@ -0,0 +1,90 @@
+use std::fmt::Display;
+
+use zbus::zvariant::OwnedObjectPath;
+
+// NOTE: These docs are mostly from the man page of org.freedesktop.login1
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum UserState {
+    /// The user is not logged in
+    Offline,
+    /// The user is not logged in, but lingering is enabled so the user service manager runs
+    Lingering,
+    /// The user is logged in, but none of the sessions is in the foreground
+    Online,
+    /// The user is logged in and at least one session is in the foreground
+    Active,
+    /// The user logged out, but some processes are still around
+    Closing,
+    Other(String),
+}
+
+impl From<String> for UserState {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "offline" => UserState::Offline,
+            "lingering" => UserState::Lingering,
+            "online" => UserState::Online,
+            "active" => UserState::Active,
+            "closing" => UserState::Closing,
+            _ => UserState::Other(value),
+        }
+    }
+}
+
+impl Display for UserState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            UserState::Offline => "offline",
+            UserState::Lingering => "lingering",
+            UserState::Online => "online",
+            UserState::Active => "active",
+            UserState::Closing => "closing",
+            UserState::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+/// A user as listed by ListUsers(), equivalent to a line of `loginctl list-users`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct User {
+    /// The user ID
+    pub uid: u32,
+    /// The user name
+    pub name: String,
+    /// The user object path
+    pub object_path: OwnedObjectPath,
+}
+
+impl From<(u32, String, OwnedObjectPath)> for User {
+    fn from(value: (u32, String, OwnedObjectPath)) -> Self {
+        Self {
+            uid: value.0,
+            name: value.1,
+            object_path: value.2,
+        }
+    }
+}
+
+/// The properties of a user, equivalent to `loginctl user-status`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UserDetails {
+    /// The user ID
+    pub uid: u32,
+    /// The primary group ID
+    pub gid: u32,
+    /// The user name
+    pub name: String,
+    pub state: UserState,
+    /// Whether lingering is enabled, i.e. the user service manager keeps running without a login
+    pub linger: bool,
+    /// The IDs of all sessions of the user
+    pub sessions: Vec<String>,
+    /// The runtime directory of the user, i.e. $XDG_RUNTIME_DIR
+    pub runtime_path: String,
+    /// The unit name of the user service manager, e.g. "user@1000.service"
+    pub service: String,
+    /// The slice unit of the user, e.g. "user-1000.slice"
+    pub slice: String,
+}
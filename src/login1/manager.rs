@@ -0,0 +1,245 @@
+//! # D-Bus interface proxy for: `org.freedesktop.login1.Manager`
+//!
+//! logind only runs on the system bus, so this proxy always needs a system bus connection, even
+//! when talking to the user service manager.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+use zbus::proxy;
+#[proxy(
+    interface = "org.freedesktop.login1.Manager",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1"
+)]
+pub trait Manager {
+    /// # CanHalt()
+    /// ## METHOD
+    /// Tests whether the system may be halted. Returns one of "na", "yes", "no" or "challenge".
+    /// If "na" is returned, the operation is not available because hardware, kernel, or drivers do
+    /// not support it. If "yes" is returned, the operation is supported and the user may execute
+    /// the operation without further authentication. If "no" is returned, the operation is
+    /// available but the user is not allowed to execute the operation. If "challenge" is returned,
+    /// the operation is available but only after authorization.
+    fn can_halt(&self) -> zbus::Result<String>;
+
+    /// # CanHibernate()
+    /// ## METHOD
+    /// Tests whether the system may be hibernated, see CanHalt().
+    fn can_hibernate(&self) -> zbus::Result<String>;
+
+    /// # CanHybridSleep()
+    /// ## METHOD
+    /// Tests whether the system may be put into hybrid sleep, see CanHalt().
+    fn can_hybrid_sleep(&self) -> zbus::Result<String>;
+
+    /// # CanPowerOff()
+    /// ## METHOD
+    /// Tests whether the system may be powered off, see CanHalt().
+    fn can_power_off(&self) -> zbus::Result<String>;
+
+    /// # CanReboot()
+    /// ## METHOD
+    /// Tests whether the system may be rebooted, see CanHalt().
+    fn can_reboot(&self) -> zbus::Result<String>;
+
+    /// # CanRebootToKexec()
+    /// ## METHOD
+    /// Tests whether the system may be rebooted through kexec, see CanHalt(). Not every logind
+    /// offers this method.
+    fn can_reboot_to_kexec(&self) -> zbus::Result<String>;
+
+    /// # CanSuspend()
+    /// ## METHOD
+    /// Tests whether the system may be suspended, see CanHalt().
+    fn can_suspend(&self) -> zbus::Result<String>;
+
+    /// # CanSuspendThenHibernate()
+    /// ## METHOD
+    /// Tests whether the system may be suspended and then hibernated, see CanHalt().
+    fn can_suspend_then_hibernate(&self) -> zbus::Result<String>;
+
+    /// # CancelScheduledShutdown()
+    /// ## METHOD
+    /// Cancels a scheduled shutdown. Returns whether a shutdown was scheduled.
+    fn cancel_scheduled_shutdown(&self) -> zbus::Result<bool>;
+
+    /// # GetSeat()
+    /// ## METHOD
+    /// May be used to get the seat object path for the seat with the specified ID.
+    fn get_seat(&self, seat_id: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+    /// # GetSession()
+    /// ## METHOD
+    /// May be used to get the session object path for the session with the specified ID.
+    fn get_session(&self, session_id: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+    /// # GetSessionByPID()
+    /// ## METHOD
+    /// May be used to get the session object path for the session a specific process belongs to.
+    #[zbus(name = "GetSessionByPID")]
+    fn get_session_by_pid(&self, pid: u32) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+    /// # GetUser()
+    /// ## METHOD
+    /// May be used to get the user object path for the user with the specified ID.
+    fn get_user(&self, uid: u32) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+    /// # GetUserByPID()
+    /// ## METHOD
+    /// May be used to get the user object path for the user a specific process belongs to.
+    #[zbus(name = "GetUserByPID")]
+    fn get_user_by_pid(&self, pid: u32) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+    /// # Halt()
+    /// ## METHOD
+    /// Halts the system. The argument controls whether the operation may be interactive, i.e.
+    /// whether the user may be asked for authentication.
+    fn halt(&self, interactive: bool) -> zbus::Result<()>;
+
+    /// # Hibernate()
+    /// ## METHOD
+    /// Hibernates the system, see Halt().
+    fn hibernate(&self, interactive: bool) -> zbus::Result<()>;
+
+    /// # HybridSleep()
+    /// ## METHOD
+    /// Puts the system into hybrid sleep, see Halt().
+    fn hybrid_sleep(&self, interactive: bool) -> zbus::Result<()>;
+
+    /// # Inhibit()
+    /// ## METHOD
+    /// Creates an inhibitor lock. It takes four parameters: what, who, why, and mode. what is one
+    /// or more of "shutdown", "sleep", "idle", "handle-power-key", "handle-suspend-key",
+    /// "handle-hibernate-key", "handle-lid-switch", separated by colons. who is a short human
+    /// readable string identifying the program taking the lock, why a short human readable
+    /// string describing the reason, and mode is either "block" or "delay". A file descriptor is
+    /// returned, and the lock is released as soon as it is closed.
+    fn inhibit(
+        &self,
+        what: &str,
+        who: &str,
+        why: &str,
+        mode: &str,
+    ) -> zbus::Result<zbus::zvariant::OwnedFd>;
+
+    /// # KillSession()
+    /// ## METHOD
+    /// May be used to send a Unix signal to one or all processes of a session. As arguments it
+    /// takes the session ID, either the string "leader" or "all" and a signal number.
+    fn kill_session(&self, session_id: &str, who: &str, signal_number: i32) -> zbus::Result<()>;
+
+    /// # KillUser()
+    /// ## METHOD
+    /// May be used to send a Unix signal to all processes of a user.
+    fn kill_user(&self, uid: u32, signal_number: i32) -> zbus::Result<()>;
+
+    /// # ListInhibitors()
+    /// ## METHOD
+    /// Lists all currently active inhibitor locks. It returns an array of structures consisting
+    /// of what, who, why, mode, uid (user ID), and pid (process ID).
+    #[allow(clippy::type_complexity)]
+    fn list_inhibitors(&self) -> zbus::Result<Vec<(String, String, String, String, u32, u32)>>;
+
+    /// # ListSeats()
+    /// ## METHOD
+    /// Returns an array with all currently available seats. The structure in the array consists
+    /// of the seat ID and the seat object path.
+    fn list_seats(&self) -> zbus::Result<Vec<(String, zbus::zvariant::OwnedObjectPath)>>;
+
+    /// # ListSessions()
+    /// ## METHOD
+    /// Returns an array of all current sessions. The structures in the array consist of the
+    /// following fields: session id, user id, user name, seat id, session object path. If a
+    /// session does not have a seat attached, the seat id field will be an empty string.
+    #[allow(clippy::type_complexity)]
+    fn list_sessions(
+        &self,
+    ) -> zbus::Result<Vec<(String, u32, String, String, zbus::zvariant::OwnedObjectPath)>>;
+
+    /// # ListUsers()
+    /// ## METHOD
+    /// Returns an array of all currently logged in users. The structures in the array consist
+    /// of the following fields: user id, user name, user object path.
+    fn list_users(&self) -> zbus::Result<Vec<(u32, String, zbus::zvariant::OwnedObjectPath)>>;
+
+    /// # LockSession()
+    /// ## METHOD
+    /// Asks the session with the specified ID to activate the screen lock.
+    fn lock_session(&self, session_id: &str) -> zbus::Result<()>;
+
+    /// ## METHOD
+    /// Powers off the system, see Halt().
+    fn power_off(&self, interactive: bool) -> zbus::Result<()>;
+
+    /// # Reboot()
+    /// ## METHOD
+    /// Reboots the system, see Halt().
+    fn reboot(&self, interactive: bool) -> zbus::Result<()>;
+
+    /// # ScheduleShutdown()
+    /// ## METHOD
+    /// Schedules a shutdown operation type at a specified time, given in microseconds since the
+    /// epoch. The type may be one of "poweroff", "dry-poweroff", "reboot", "dry-reboot", "halt",
+    /// "dry-halt" and "kexec". The "dry-" variants do everything except actually shutting down.
+    fn schedule_shutdown(&self, type_: &str, usec: u64) -> zbus::Result<()>;
+
+    /// # SetUserLinger()
+    /// ## METHOD
+    /// Enables or disables user lingering. If enabled, the user service manager of the user is
+    /// started at boot and kept around after the user logs out, so that user services keep
+    /// running. The interactive argument controls whether the user may be asked for
+    /// authentication.
+    fn set_user_linger(&self, uid: u32, enable: bool, interactive: bool) -> zbus::Result<()>;
+
+    /// ## METHOD
+    /// Suspends the system, see Halt().
+    fn suspend(&self, interactive: bool) -> zbus::Result<()>;
+
+    /// # SuspendThenHibernate()
+    /// ## METHOD
+    /// Suspends the system and hibernates it after a delay, see Halt().
+    fn suspend_then_hibernate(&self, interactive: bool) -> zbus::Result<()>;
+
+    /// # TerminateSeat()
+    /// ## METHOD
+    /// Forcibly terminates all sessions on the seat with the specified ID.
+    fn terminate_seat(&self, seat_id: &str) -> zbus::Result<()>;
+
+    /// # TerminateSession()
+    /// ## METHOD
+    /// Forcibly terminates the session with the specified ID, killing all of its processes.
+    fn terminate_session(&self, session_id: &str) -> zbus::Result<()>;
+
+    /// # TerminateUser()
+    /// ## METHOD
+    /// Forcibly terminates all sessions of the user, killing all of their processes.
+    fn terminate_user(&self, uid: u32) -> zbus::Result<()>;
+
+    /// # UnlockSession()
+    /// ## METHOD
+    /// Asks the session with the specified ID to deactivate the screen lock.
+    fn unlock_session(&self, session_id: &str) -> zbus::Result<()>;
+
+    /// # PrepareForShutdown
+    /// ## SIGNAL
+    /// Sent right before (with the argument true) and after (with the argument false) the system
+    /// goes down for reboot/poweroff.
+    #[zbus(signal)]
+    fn prepare_for_shutdown(&self, start: bool) -> zbus::Result<()>;
+
+    /// # PrepareForSleep
+    /// ## SIGNAL
+    /// Sent right before (with the argument true) and after (with the argument false) the system
+    /// goes to sleep.
+    #[zbus(signal)]
+    fn prepare_for_sleep(&self, start: bool) -> zbus::Result<()>;
+
+    /// # ScheduledShutdown
+    /// ## PROPERTY
+    /// The scheduled shutdown type and time in microseconds since the epoch. The type is empty
+    /// and the time 0 if no shutdown is scheduled.
+    #[zbus(property)]
+    fn scheduled_shutdown(&self) -> zbus::Result<(String, u64)>;
+}
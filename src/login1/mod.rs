@@ -0,0 +1,8 @@
+//! Proxies and types for systemd-logind, the login manager, at `org.freedesktop.login1`.
+//! See "man org.freedesktop.login1".
+pub mod inhibitor;
+pub mod loginctl;
+pub mod manager;
+pub mod seat;
+pub mod session;
+pub mod user;
@@ -0,0 +1,66 @@
+//! # D-Bus interface proxy for: `org.freedesktop.login1.Seat`
+//!
+//! Seat objects live at "/org/freedesktop/login1/seat/<escaped id>", so the path of the seat has
+//! to be given when building the proxy. Use GetSeat() on the manager to find it.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+use zbus::proxy;
+#[proxy(
+    interface = "org.freedesktop.login1.Seat",
+    default_service = "org.freedesktop.login1"
+)]
+pub trait Seat {
+    /// # ActivateSession()
+    /// ## METHOD
+    /// Brings the session with the specified ID to the foreground.
+    fn activate_session(&self, session_id: &str) -> zbus::Result<()>;
+
+    /// # SwitchTo()
+    /// ## METHOD
+    /// Switches to the session on the specified virtual terminal.
+    fn switch_to(&self, vtnr: u32) -> zbus::Result<()>;
+
+    /// # Terminate()
+    /// ## METHOD
+    /// Forcibly terminates all sessions on the seat, see TerminateSeat() on the manager.
+    fn terminate(&self) -> zbus::Result<()>;
+
+    /// # ActiveSession
+    /// ## PROPERTY
+    /// The ID and object path of the session currently in the foreground of the seat.
+    #[zbus(property)]
+    fn active_session(&self) -> zbus::Result<(String, zbus::zvariant::OwnedObjectPath)>;
+
+    /// # CanGraphical
+    /// ## PROPERTY
+    /// Whether the seat is suitable for graphical sessions.
+    #[zbus(property)]
+    fn can_graphical(&self) -> zbus::Result<bool>;
+
+    /// # CanTTY
+    /// ## PROPERTY
+    /// Whether the seat is suitable for text logins.
+    #[zbus(property, name = "CanTTY")]
+    fn can_tty(&self) -> zbus::Result<bool>;
+
+    /// # Id
+    /// ## PROPERTY
+    /// The seat ID.
+    #[zbus(property)]
+    fn id(&self) -> zbus::Result<String>;
+
+    /// # IdleHint
+    /// ## PROPERTY
+    /// Whether all sessions on the seat are idle.
+    #[zbus(property)]
+    fn idle_hint(&self) -> zbus::Result<bool>;
+
+    /// # Sessions
+    /// ## PROPERTY
+    /// The IDs and object paths of all sessions on the seat.
+    #[zbus(property)]
+    fn sessions(&self) -> zbus::Result<Vec<(String, zbus::zvariant::OwnedObjectPath)>>;
+}
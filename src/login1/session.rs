@@ -0,0 +1,143 @@
+//! # D-Bus interface proxy for: `org.freedesktop.login1.Session`
+//!
+//! Session objects live at "/org/freedesktop/login1/session/<escaped id>", so the path of the
+//! session has to be given when building the proxy. Use GetSession() on the manager to find it.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+use zbus::proxy;
+#[proxy(
+    interface = "org.freedesktop.login1.Session",
+    default_service = "org.freedesktop.login1"
+)]
+pub trait Session {
+    /// # Activate()
+    /// ## METHOD
+    /// Brings the session to the foreground if one is running on the seat.
+    fn activate(&self) -> zbus::Result<()>;
+
+    /// # Kill()
+    /// ## METHOD
+    /// Sends a Unix signal to the processes of the session, see KillSession() on the manager.
+    fn kill(&self, who: &str, signal_number: i32) -> zbus::Result<()>;
+
+    /// # Lock()
+    /// ## METHOD
+    /// Asks the session to activate the screen lock.
+    fn lock(&self) -> zbus::Result<()>;
+
+    /// # Terminate()
+    /// ## METHOD
+    /// Forcibly terminates the session, see TerminateSession() on the manager.
+    fn terminate(&self) -> zbus::Result<()>;
+
+    /// # Unlock()
+    /// ## METHOD
+    /// Asks the session to deactivate the screen lock.
+    fn unlock(&self) -> zbus::Result<()>;
+
+    /// # Active
+    /// ## PROPERTY
+    /// Whether the session is currently in the foreground of its seat.
+    #[zbus(property)]
+    fn active(&self) -> zbus::Result<bool>;
+
+    /// # Class
+    /// ## PROPERTY
+    /// The session class, one of "user", "greeter", "lock-screen", "background" and others.
+    #[zbus(property)]
+    fn class(&self) -> zbus::Result<String>;
+
+    /// # Display
+    /// ## PROPERTY
+    /// The X11 display name, if this is a graphical login.
+    #[zbus(property)]
+    fn display(&self) -> zbus::Result<String>;
+
+    /// # Id
+    /// ## PROPERTY
+    /// The session ID.
+    #[zbus(property)]
+    fn id(&self) -> zbus::Result<String>;
+
+    /// # IdleHint
+    /// ## PROPERTY
+    /// Whether the session is idle.
+    #[zbus(property)]
+    fn idle_hint(&self) -> zbus::Result<bool>;
+
+    /// # Leader
+    /// ## PROPERTY
+    /// The process ID of the session leader.
+    #[zbus(property)]
+    fn leader(&self) -> zbus::Result<u32>;
+
+    /// # LockedHint
+    /// ## PROPERTY
+    /// Whether the session is locked.
+    #[zbus(property)]
+    fn locked_hint(&self) -> zbus::Result<bool>;
+
+    /// # Name
+    /// ## PROPERTY
+    /// The user name of the session owner.
+    #[zbus(property)]
+    fn name(&self) -> zbus::Result<String>;
+
+    /// # Remote
+    /// ## PROPERTY
+    /// Whether the session is remote, e.g. an SSH login.
+    #[zbus(property)]
+    fn remote(&self) -> zbus::Result<bool>;
+
+    /// # RemoteHost
+    /// ## PROPERTY
+    /// The remote host name, if this is a remote session and it is known.
+    #[zbus(property)]
+    fn remote_host(&self) -> zbus::Result<String>;
+
+    /// # Scope
+    /// ## PROPERTY
+    /// The scope unit the processes of the session are in.
+    #[zbus(property)]
+    fn scope(&self) -> zbus::Result<String>;
+
+    /// # Seat
+    /// ## PROPERTY
+    /// The seat ID and seat object path of the seat the session is on. The ID is empty if the
+    /// session is not attached to a seat.
+    #[zbus(property)]
+    fn seat(&self) -> zbus::Result<(String, zbus::zvariant::OwnedObjectPath)>;
+
+    /// # Service
+    /// ## PROPERTY
+    /// The PAM service that registered the session, e.g. "sshd" or "login".
+    #[zbus(property)]
+    fn service(&self) -> zbus::Result<String>;
+
+    /// # State
+    /// ## PROPERTY
+    /// The session state, one of "online", "active" and "closing".
+    #[zbus(property)]
+    fn state(&self) -> zbus::Result<String>;
+
+    /// # TTY
+    /// ## PROPERTY
+    /// The kernel TTY path of the session, if it has one.
+    #[zbus(property, name = "TTY")]
+    fn tty(&self) -> zbus::Result<String>;
+
+    /// # Type
+    /// ## PROPERTY
+    /// The session type, one of "unspecified", "tty", "x11", "wayland", "mir" and "web".
+    #[zbus(property, name = "Type")]
+    fn session_type(&self) -> zbus::Result<String>;
+
+    /// # User
+    /// ## PROPERTY
+    /// The user ID and user object path of the session owner.
+    #[zbus(property)]
+    fn user(&self) -> zbus::Result<(u32, zbus::zvariant::OwnedObjectPath)>;
+}
@@ -0,0 +1,91 @@
+//! # D-Bus interface proxy for: `org.freedesktop.login1.User`
+//!
+//! User objects live at "/org/freedesktop/login1/user/_<uid>", so the path of the user has to be
+//! given when building the proxy. Use GetUser() on the manager to find it.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+use zbus::proxy;
+#[proxy(
+    interface = "org.freedesktop.login1.User",
+    default_service = "org.freedesktop.login1"
+)]
+pub trait User {
+    /// # Kill()
+    /// ## METHOD
+    /// Sends a Unix signal to all processes of the user, see KillUser() on the manager.
+    fn kill(&self, signal_number: i32) -> zbus::Result<()>;
+
+    /// # Terminate()
+    /// ## METHOD
+    /// Forcibly terminates all sessions of the user, see TerminateUser() on the manager.
+    fn terminate(&self) -> zbus::Result<()>;
+
+    /// # Display
+    /// ## PROPERTY
+    /// The ID and object path of the primary graphical session of the user, if there is one.
+    #[zbus(property)]
+    fn display(&self) -> zbus::Result<(String, zbus::zvariant::OwnedObjectPath)>;
+
+    /// # GID
+    /// ## PROPERTY
+    /// The primary group ID of the user.
+    #[zbus(property, name = "GID")]
+    fn gid(&self) -> zbus::Result<u32>;
+
+    /// # IdleHint
+    /// ## PROPERTY
+    /// Whether all sessions of the user are idle.
+    #[zbus(property)]
+    fn idle_hint(&self) -> zbus::Result<bool>;
+
+    /// # Linger
+    /// ## PROPERTY
+    /// Whether lingering is enabled for the user, see SetUserLinger() on the manager.
+    #[zbus(property)]
+    fn linger(&self) -> zbus::Result<bool>;
+
+    /// # Name
+    /// ## PROPERTY
+    /// The user name.
+    #[zbus(property)]
+    fn name(&self) -> zbus::Result<String>;
+
+    /// # RuntimePath
+    /// ## PROPERTY
+    /// The runtime directory of the user, i.e. $XDG_RUNTIME_DIR.
+    #[zbus(property)]
+    fn runtime_path(&self) -> zbus::Result<String>;
+
+    /// # Service
+    /// ## PROPERTY
+    /// The unit name of the user service manager, e.g. "user@1000.service".
+    #[zbus(property)]
+    fn service(&self) -> zbus::Result<String>;
+
+    /// # Sessions
+    /// ## PROPERTY
+    /// The IDs and object paths of all sessions of the user.
+    #[zbus(property)]
+    fn sessions(&self) -> zbus::Result<Vec<(String, zbus::zvariant::OwnedObjectPath)>>;
+
+    /// # Slice
+    /// ## PROPERTY
+    /// The slice unit of the user, e.g. "user-1000.slice".
+    #[zbus(property)]
+    fn slice(&self) -> zbus::Result<String>;
+
+    /// # State
+    /// ## PROPERTY
+    /// The user state, one of "offline", "lingering", "online", "active" and "closing".
+    #[zbus(property)]
+    fn state(&self) -> zbus::Result<String>;
+
+    /// # UID
+    /// ## PROPERTY
+    /// The user ID.
+    #[zbus(property, name = "UID")]
+    fn uid(&self) -> zbus::Result<u32>;
+}
@@ -199,7 +199,10 @@ pub trait Manager {
     /// Returns the job object path for a specific job, identified by its id.
     fn get_job(&self, id: u32) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
 
-    /// GetJobAfter method
+    /// # GetJobAfter()
+    /// ## METHOD
+    /// Returns the jobs that are waiting for the job with the given id, like GetAfter() on the job
+    /// object.
     #[allow(clippy::type_complexity)]
     fn get_job_after(
         &self,
@@ -215,7 +218,10 @@ pub trait Manager {
         )>,
     >;
 
-    /// GetJobBefore method
+    /// # GetJobBefore()
+    /// ## METHOD
+    /// Returns the jobs the job with the given id is waiting for, like GetBefore() on the job
+    /// object.
     #[allow(clippy::type_complexity)]
     fn get_job_before(
         &self,
@@ -0,0 +1,55 @@
+//! # D-Bus interface proxy for: `org.freedesktop.systemd1.Mount`
+//!
+//! Mount units implement this interface in addition to org.freedesktop.systemd1.Unit, on the
+//! same object. Like for the unit proxy, the path of the unit has to be given when building the
+//! proxy.
+//!
+//! Only the members describing what is mounted where are included here, see
+//! "man org.freedesktop.systemd1" and "man systemd.mount" for the full list.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+use zbus::proxy;
+#[proxy(
+    interface = "org.freedesktop.systemd1.Mount",
+    default_service = "org.freedesktop.systemd1"
+)]
+pub trait Mount {
+    /// # Options
+    /// ## PROPERTY
+    /// The mount options, as passed to mount(8) with -o.
+    #[zbus(property)]
+    fn options(&self) -> zbus::Result<String>;
+
+    /// # Result
+    /// ## PROPERTY
+    /// Why the mount last stopped, e.g. "success", "exit-code" or "timeout".
+    #[zbus(property)]
+    fn result(&self) -> zbus::Result<String>;
+
+    /// # TimeoutUSec
+    /// ## PROPERTY
+    /// How long mounting and unmounting may take in microseconds, see TimeoutSec=.
+    #[zbus(property, name = "TimeoutUSec")]
+    fn timeout_usec(&self) -> zbus::Result<u64>;
+
+    /// # Type
+    /// ## PROPERTY
+    /// The file system type, e.g. "ext4", empty if it is detected automatically.
+    #[zbus(property, name = "Type")]
+    fn fs_type(&self) -> zbus::Result<String>;
+
+    /// # What
+    /// ## PROPERTY
+    /// What is mounted, e.g. a device node, a file or a network share.
+    #[zbus(property)]
+    fn what(&self) -> zbus::Result<String>;
+
+    /// # Where
+    /// ## PROPERTY
+    /// The mount point, which the name of the unit is derived from.
+    #[zbus(property, name = "Where")]
+    fn where_(&self) -> zbus::Result<String>;
+}
@@ -0,0 +1,264 @@
+//! # D-Bus interface proxy for: `org.freedesktop.systemd1.Service`
+//!
+//! Service units implement this interface in addition to org.freedesktop.systemd1.Unit, on the
+//! same object. Like for the unit proxy, the path of the unit has to be given when building the
+//! proxy.
+//!
+//! Only the members describing how the service runs and how it is sandboxed are included here,
+//! see "man org.freedesktop.systemd1" and "man systemd.exec" for the full list.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+use zbus::proxy;
+#[proxy(
+    interface = "org.freedesktop.systemd1.Service",
+    default_service = "org.freedesktop.systemd1"
+)]
+pub trait Service {
+    /// # AmbientCapabilities
+    /// ## PROPERTY
+    /// The ambient capabilities the processes are started with, as a bitmask of capability
+    /// numbers.
+    #[zbus(property)]
+    fn ambient_capabilities(&self) -> zbus::Result<u64>;
+
+    /// # CapabilityBoundingSet
+    /// ## PROPERTY
+    /// The capability bounding set of the processes, as a bitmask of capability numbers.
+    #[zbus(property)]
+    fn capability_bounding_set(&self) -> zbus::Result<u64>;
+
+    /// # CPUUsageNSec
+    /// ## PROPERTY
+    /// The CPU time consumed by the processes in nanoseconds, or u64::MAX if CPU accounting is
+    /// disabled.
+    #[zbus(property, name = "CPUUsageNSec")]
+    fn cpu_usage_nsec(&self) -> zbus::Result<u64>;
+
+    /// # DevicePolicy
+    /// ## PROPERTY
+    /// The policy for access to device nodes: "auto", "closed" or "strict".
+    #[zbus(property)]
+    fn device_policy(&self) -> zbus::Result<String>;
+
+    /// # DynamicUser
+    /// ## PROPERTY
+    /// Whether a user and group are allocated dynamically while the service runs.
+    #[zbus(property)]
+    fn dynamic_user(&self) -> zbus::Result<bool>;
+
+    /// # ExecMainCode
+    /// ## PROPERTY
+    /// How the main process last exited, as a CLD_* code of waitid(2), e.g. 1 for a regular exit
+    /// or 2 if it was killed by a signal. 0 if it has not exited yet.
+    #[zbus(property)]
+    fn exec_main_code(&self) -> zbus::Result<i32>;
+
+    /// # ExecMainExitTimestampMonotonic
+    /// ## PROPERTY
+    /// When the main process last exited, in microseconds of CLOCK_MONOTONIC.
+    #[zbus(property)]
+    fn exec_main_exit_timestamp_monotonic(&self) -> zbus::Result<u64>;
+
+    /// # ExecMainStartTimestampMonotonic
+    /// ## PROPERTY
+    /// When the main process was last started, in microseconds of CLOCK_MONOTONIC.
+    #[zbus(property)]
+    fn exec_main_start_timestamp_monotonic(&self) -> zbus::Result<u64>;
+
+    /// # ExecMainStatus
+    /// ## PROPERTY
+    /// The exit status of the main process if it exited, or the signal that killed it.
+    #[zbus(property)]
+    fn exec_main_status(&self) -> zbus::Result<i32>;
+
+    /// # IPAddressDeny
+    /// ## PROPERTY
+    /// The IP address prefixes the processes may not communicate with, as address family,
+    /// address bytes and prefix length.
+    #[zbus(property, name = "IPAddressDeny")]
+    fn ip_address_deny(&self) -> zbus::Result<Vec<(i32, Vec<u8>, u32)>>;
+
+    /// # LockPersonality
+    /// ## PROPERTY
+    /// Whether changing the execution domain with personality(2) is prevented.
+    #[zbus(property)]
+    fn lock_personality(&self) -> zbus::Result<bool>;
+
+    /// # MainPID
+    /// ## PROPERTY
+    /// The PID of the main process of the service, or 0 if there is none.
+    #[zbus(property, name = "MainPID")]
+    fn main_pid(&self) -> zbus::Result<u32>;
+
+    /// # MemoryDenyWriteExecute
+    /// ## PROPERTY
+    /// Whether creating memory mappings that are both writable and executable is prevented.
+    #[zbus(property)]
+    fn memory_deny_write_execute(&self) -> zbus::Result<bool>;
+
+    /// # MemoryPeak
+    /// ## PROPERTY
+    /// The most memory the processes used at once in bytes, or u64::MAX if memory accounting is
+    /// disabled. Only available since systemd 255.
+    #[zbus(property)]
+    fn memory_peak(&self) -> zbus::Result<u64>;
+
+    /// # NoNewPrivileges
+    /// ## PROPERTY
+    /// Whether the processes and their children can never gain new privileges through execve().
+    #[zbus(property)]
+    fn no_new_privileges(&self) -> zbus::Result<bool>;
+
+    /// # PrivateDevices
+    /// ## PROPERTY
+    /// Whether the processes see a minimal /dev/ without physical devices.
+    #[zbus(property)]
+    fn private_devices(&self) -> zbus::Result<bool>;
+
+    /// # PrivateNetwork
+    /// ## PROPERTY
+    /// Whether the processes run in a network namespace with only a loopback device.
+    #[zbus(property)]
+    fn private_network(&self) -> zbus::Result<bool>;
+
+    /// # PrivateTmp
+    /// ## PROPERTY
+    /// Whether the processes get their own /tmp/ and /var/tmp/.
+    #[zbus(property)]
+    fn private_tmp(&self) -> zbus::Result<bool>;
+
+    /// # PrivateUsers
+    /// ## PROPERTY
+    /// Whether the processes run in a user namespace with only root and the service user mapped.
+    #[zbus(property)]
+    fn private_users(&self) -> zbus::Result<bool>;
+
+    /// # ProcSubset
+    /// ## PROPERTY
+    /// "all", or "pid" if only the process directories of /proc/ are accessible.
+    #[zbus(property)]
+    fn proc_subset(&self) -> zbus::Result<String>;
+
+    /// # ProtectClock
+    /// ## PROPERTY
+    /// Whether changing the system clock is prevented.
+    #[zbus(property)]
+    fn protect_clock(&self) -> zbus::Result<bool>;
+
+    /// # ProtectControlGroups
+    /// ## PROPERTY
+    /// Whether the control group hierarchy is read-only.
+    #[zbus(property)]
+    fn protect_control_groups(&self) -> zbus::Result<bool>;
+
+    /// # ProtectHome
+    /// ## PROPERTY
+    /// "no", "yes", "read-only" or "tmpfs", see ProtectHome= in "man systemd.exec".
+    #[zbus(property)]
+    fn protect_home(&self) -> zbus::Result<String>;
+
+    /// # ProtectHostname
+    /// ## PROPERTY
+    /// Whether changing the hostname is prevented.
+    #[zbus(property)]
+    fn protect_hostname(&self) -> zbus::Result<bool>;
+
+    /// # ProtectKernelLogs
+    /// ## PROPERTY
+    /// Whether access to the kernel log ring buffer is prevented.
+    #[zbus(property)]
+    fn protect_kernel_logs(&self) -> zbus::Result<bool>;
+
+    /// # ProtectKernelModules
+    /// ## PROPERTY
+    /// Whether loading kernel modules is prevented.
+    #[zbus(property)]
+    fn protect_kernel_modules(&self) -> zbus::Result<bool>;
+
+    /// # ProtectKernelTunables
+    /// ## PROPERTY
+    /// Whether the kernel variables in /proc/sys/ and /sys/ are read-only.
+    #[zbus(property)]
+    fn protect_kernel_tunables(&self) -> zbus::Result<bool>;
+
+    /// # ProtectProc
+    /// ## PROPERTY
+    /// "default", "invisible", "noaccess" or "ptraceable", see ProtectProc= in "man systemd.exec".
+    #[zbus(property)]
+    fn protect_proc(&self) -> zbus::Result<String>;
+
+    /// # ProtectSystem
+    /// ## PROPERTY
+    /// "no", "yes", "full" or "strict", see ProtectSystem= in "man systemd.exec".
+    #[zbus(property)]
+    fn protect_system(&self) -> zbus::Result<String>;
+
+    /// # RemoveIPC
+    /// ## PROPERTY
+    /// Whether the IPC objects of the service user are removed when the service stops.
+    #[zbus(property, name = "RemoveIPC")]
+    fn remove_ipc(&self) -> zbus::Result<bool>;
+
+    /// # Restart
+    /// ## PROPERTY
+    /// The restart policy, i.e. Restart= in the unit file.
+    #[zbus(property)]
+    fn restart(&self) -> zbus::Result<String>;
+
+    /// # RestrictAddressFamilies
+    /// ## PROPERTY
+    /// Whether the list is an allow list, and the socket address families in it, e.g. "AF_UNIX".
+    #[zbus(property)]
+    fn restrict_address_families(&self) -> zbus::Result<(bool, Vec<String>)>;
+
+    /// # RestrictNamespaces
+    /// ## PROPERTY
+    /// The namespace types the processes may create, as a bitmask of CLONE_NEW* flags.
+    #[zbus(property)]
+    fn restrict_namespaces(&self) -> zbus::Result<u64>;
+
+    /// # RestrictRealtime
+    /// ## PROPERTY
+    /// Whether realtime scheduling is prevented.
+    #[zbus(property)]
+    fn restrict_realtime(&self) -> zbus::Result<bool>;
+
+    /// # RestrictSUIDSGID
+    /// ## PROPERTY
+    /// Whether creating set-user-ID and set-group-ID files is prevented.
+    #[zbus(property, name = "RestrictSUIDSGID")]
+    fn restrict_suid_sgid(&self) -> zbus::Result<bool>;
+
+    /// # Result
+    /// ## PROPERTY
+    /// Why the service last stopped, e.g. "success", "exit-code" or "timeout".
+    #[zbus(property)]
+    fn result(&self) -> zbus::Result<String>;
+
+    /// # SystemCallArchitectures
+    /// ## PROPERTY
+    /// The architectures system calls may be made for, e.g. "native".
+    #[zbus(property)]
+    fn system_call_architectures(&self) -> zbus::Result<Vec<String>>;
+
+    /// # SystemCallFilter
+    /// ## PROPERTY
+    /// Whether the list is an allow list, and the system calls in it.
+    #[zbus(property)]
+    fn system_call_filter(&self) -> zbus::Result<(bool, Vec<String>)>;
+
+    /// # Type
+    /// ## PROPERTY
+    /// The service type, i.e. Type= in the unit file.
+    #[zbus(property, name = "Type")]
+    fn service_type(&self) -> zbus::Result<String>;
+
+    /// # User
+    /// ## PROPERTY
+    /// The user the processes run as, empty for root.
+    #[zbus(property)]
+    fn user(&self) -> zbus::Result<String>;
+}
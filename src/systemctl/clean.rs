@@ -0,0 +1,89 @@
+use std::fmt::Display;
+
+use bitflags::bitflags;
+
+// NOTE: These docs are from the man page of systemctl, under clean
+
+bitflags! {
+    /// The kinds of resources `systemctl clean` may remove for a unit.
+    #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+    pub struct CleanMask: u8 {
+        /// The runtime directory, RuntimeDirectory=
+        const RUNTIME = 1 << 0;
+        /// The state directory, StateDirectory=
+        const STATE = 1 << 1;
+        /// The cache directory, CacheDirectory=
+        const CACHE = 1 << 2;
+        /// The logs directory, LogsDirectory=
+        const LOGS = 1 << 3;
+        /// The configuration directory, ConfigurationDirectory=
+        const CONFIGURATION = 1 << 4;
+        /// The file descriptor store of the unit, FileDescriptorStoreMax=
+        const FDSTORE = 1 << 5;
+    }
+}
+
+const NAMES: [(CleanMask, &str); 6] = [
+    (CleanMask::RUNTIME, "runtime"),
+    (CleanMask::STATE, "state"),
+    (CleanMask::CACHE, "cache"),
+    (CleanMask::LOGS, "logs"),
+    (CleanMask::CONFIGURATION, "configuration"),
+    (CleanMask::FDSTORE, "fdstore"),
+];
+
+impl CleanMask {
+    /// The names of the resource types in the mask, as expected by CleanUnit().
+    pub fn names(&self) -> Vec<&'static str> {
+        NAMES
+            .into_iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| name)
+            .collect()
+    }
+
+    /// Builds the mask from resource type names, as found in the CanClean property of a unit.
+    /// Unknown names are ignored.
+    pub fn from_names<S: AsRef<str>>(names: &[S]) -> Self {
+        names
+            .iter()
+            .filter_map(|name| {
+                NAMES
+                    .into_iter()
+                    .find(|(_, known)| *known == name.as_ref())
+                    .map(|(flag, _)| flag)
+            })
+            .collect()
+    }
+}
+
+impl Display for CleanMask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.names().join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clean_mask_round_trips_through_names() {
+        let mask = CleanMask::CACHE | CleanMask::LOGS;
+
+        assert_eq!(mask.names(), vec!["cache", "logs"]);
+        assert_eq!(CleanMask::from_names(&mask.names()), mask);
+        assert_eq!(
+            CleanMask::all().to_string(),
+            "runtime,state,cache,logs,configuration,fdstore"
+        );
+    }
+
+    #[test]
+    fn ignores_unknown_names() {
+        assert_eq!(
+            CleanMask::from_names(&["state", "something-new"]),
+            CleanMask::STATE
+        );
+    }
+}
@@ -1,3 +1,4 @@
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum ConnectionLevel {
     /// Create a Connection to the session/user message bus.
     UserLevel,
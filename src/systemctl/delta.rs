@@ -0,0 +1,357 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::systemctl::lookup_paths::{LookupPaths, Resolved};
+
+// NOTE: These docs are from the man page of systemd-delta, under --type
+
+/// How a file in the unit search path changes a file further down the search path.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DeltaType {
+    /// Masked files, i.e. symlinks to /dev/null or empty files
+    Masked,
+    /// Overridden files that do not differ in content
+    Equivalent,
+    /// Files that are redirected to another file, i.e. symlinks
+    Redirected,
+    /// Overridden files that differ in content
+    Overridden,
+    /// Files that are extended by drop-ins in a ".d" directory
+    Extended,
+}
+
+impl Display for DeltaType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            DeltaType::Masked => "masked",
+            DeltaType::Equivalent => "equivalent",
+            DeltaType::Redirected => "redirected",
+            DeltaType::Overridden => "overridden",
+            DeltaType::Extended => "extended",
+        };
+        f.write_str(value)
+    }
+}
+
+/// A single line of the `systemd-delta` output, e.g.
+/// "[OVERRIDDEN] /etc/systemd/system/foo.service → /usr/lib/systemd/system/foo.service".
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Delta {
+    pub delta_type: DeltaType,
+    /// The name of the file relative to its directory in the search path, e.g. "foo.service" or
+    /// "foo.service.d/override.conf"
+    pub name: String,
+    /// The file that takes effect
+    pub path: PathBuf,
+    /// The file it overrides or extends, if there is one
+    pub original: Option<PathBuf>,
+    /// A unified diff from the original to the overriding file, for files that differ in content
+    pub diff: Option<String>,
+}
+
+/// Compares all unit files and drop-ins in the search path with the ones further down that they
+/// hide or extend, like `systemd-delta` does for the unit directories. Directories that are the
+/// same as an earlier one in the search path are only looked at once.
+pub(crate) fn delta(lookup_paths: &LookupPaths) -> Vec<Delta> {
+    let mut units: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    let mut drop_ins: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+
+    for dir in lookup_paths.unique_dirs() {
+        let Ok(entries) = fs::read_dir(&dir.path) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let path = entry.path();
+
+            if let Some(unit) = name.strip_suffix(".d").filter(|_| path.is_dir()) {
+                for file in conf_files(&path) {
+                    let file_name = file.file_name().unwrap_or_default().to_string_lossy();
+                    drop_ins
+                        .entry(format!("{unit}.d/{file_name}"))
+                        .or_default()
+                        .push(file);
+                }
+            } else if !path.is_dir() && name.contains('.') {
+                units.entry(name).or_default().push(path);
+            }
+        }
+    }
+
+    let mut deltas = Vec::new();
+
+    for (name, paths) in &units {
+        if let Some(delta) = compare(lookup_paths, name, paths) {
+            deltas.push(delta);
+        }
+    }
+
+    for (name, paths) in &drop_ins {
+        if let Some(delta) = compare(lookup_paths, name, paths) {
+            deltas.push(delta);
+            continue;
+        }
+
+        // Drop-ins that do not hide another drop-in extend the unit, if there is one
+        let unit = name
+            .split_once(".d/")
+            .map_or(name.as_str(), |(unit, _)| unit);
+        deltas.push(Delta {
+            delta_type: DeltaType::Extended,
+            name: name.clone(),
+            path: paths[0].clone(),
+            original: units.get(unit).and_then(|paths| paths.first().cloned()),
+            diff: None,
+        });
+    }
+
+    deltas
+}
+
+/// Compares the highest priority file with the next one of the same name. Returns None for
+/// files that do not hide anything, including masks without a file underneath.
+fn compare(lookup_paths: &LookupPaths, name: &str, paths: &[PathBuf]) -> Option<Delta> {
+    let path = paths.first()?;
+    let original = paths.get(1);
+
+    let delta = |delta_type, diff| Delta {
+        delta_type,
+        name: name.to_string(),
+        path: path.clone(),
+        original: original.cloned(),
+        diff,
+    };
+
+    let original = original?;
+    if lookup_paths.resolve_link(path) == Resolved::Masked {
+        return Some(delta(DeltaType::Masked, None));
+    }
+
+    let old = read_resolved(lookup_paths, original);
+    let new = read_resolved(lookup_paths, path);
+    let diff = (old != new).then(|| {
+        unified_diff(
+            &old,
+            &new,
+            &original.to_string_lossy(),
+            &path.to_string_lossy(),
+        )
+    });
+
+    Some(match (path.is_symlink(), diff) {
+        (true, diff) => delta(DeltaType::Redirected, diff),
+        (false, None) => delta(DeltaType::Equivalent, None),
+        (false, diff) => delta(DeltaType::Overridden, diff),
+    })
+}
+
+fn read_resolved(lookup_paths: &LookupPaths, path: &Path) -> String {
+    match lookup_paths.resolve_link(path) {
+        Resolved::File(file) => fs::read_to_string(file).unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// The ".conf" files in a drop-in directory, sorted by name.
+fn conf_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "conf")
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// The number of unchanged lines shown around each change in a diff.
+const DIFF_CONTEXT: usize = 3;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Edit {
+    Keep,
+    Delete,
+    Insert,
+}
+
+/// A unified diff between two texts, in the format of `diff -u`.
+pub(crate) fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let edits = edit_script(&old, &new);
+
+    let mut diff = format!("--- {old_label}\n+++ {new_label}\n");
+
+    // Positions in the edit script, and in the old and new lines, of each edit
+    let mut positions = Vec::with_capacity(edits.len());
+    let (mut old_line, mut new_line) = (0, 0);
+    for edit in &edits {
+        positions.push((old_line, new_line));
+        match edit {
+            Edit::Keep => {
+                old_line += 1;
+                new_line += 1;
+            }
+            Edit::Delete => old_line += 1,
+            Edit::Insert => new_line += 1,
+        }
+    }
+
+    let changes: Vec<usize> = (0..edits.len())
+        .filter(|&i| edits[i] != Edit::Keep)
+        .collect();
+
+    let mut i = 0;
+    while i < changes.len() {
+        let start = changes[i].saturating_sub(DIFF_CONTEXT);
+        let mut end = changes[i];
+        while i < changes.len() && changes[i] <= end + 2 * DIFF_CONTEXT {
+            end = changes[i];
+            i += 1;
+        }
+        let end = (end + DIFF_CONTEXT + 1).min(edits.len());
+
+        let hunk = &edits[start..end];
+        let (old_start, new_start) = positions[start];
+        let old_count = hunk.iter().filter(|edit| **edit != Edit::Insert).count();
+        let new_count = hunk.iter().filter(|edit| **edit != Edit::Delete).count();
+
+        diff.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_count),
+            hunk_range(new_start, new_count)
+        ));
+
+        for (edit, (old_line, new_line)) in hunk.iter().zip(&positions[start..end]) {
+            let (prefix, line) = match edit {
+                Edit::Keep => (' ', old[*old_line]),
+                Edit::Delete => ('-', old[*old_line]),
+                Edit::Insert => ('+', new[*new_line]),
+            };
+            diff.push(prefix);
+            diff.push_str(line);
+            diff.push('\n');
+        }
+    }
+
+    diff
+}
+
+fn hunk_range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{start},0"),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{count}", start + 1),
+    }
+}
+
+/// The shortest edit script between two lists of lines, from their longest common subsequence.
+/// Unit files are short, so the quadratic table is fine.
+fn edit_script(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::with_capacity(old.len() + new.len());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            edits.push(Edit::Keep);
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lengths[i + 1][j] >= lengths[i][j + 1]) {
+            edits.push(Edit::Delete);
+            i += 1;
+        } else {
+            edits.push(Edit::Insert);
+            j += 1;
+        }
+    }
+
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemctl::test_root::TestRoot;
+
+    #[test]
+    fn diffs_changed_lines_with_context() {
+        let old = "[Unit]\nDescription=Foo\n\n[Service]\nExecStart=/bin/foo\n";
+        let new = "[Unit]\nDescription=Foo\n\n[Service]\nExecStart=/bin/foo --verbose\n";
+
+        assert_eq!(
+            unified_diff(old, new, "a", "b"),
+            "--- a\n+++ b\n@@ -2,4 +2,4 @@\n Description=Foo\n \n [Service]\n\
+             -ExecStart=/bin/foo\n+ExecStart=/bin/foo --verbose\n"
+        );
+        assert_eq!(unified_diff(old, old, "a", "b"), "--- a\n+++ b\n");
+    }
+
+    #[test]
+    fn reports_masked_overridden_equivalent_and_extended_files() {
+        let root = TestRoot::new("delta");
+        for unit in ["same", "changed", "masked", "extended"] {
+            root.unit(&format!("{unit}.service"), "[Service]\n");
+        }
+        root.file("etc/systemd/system/same.service", "[Service]\n")
+            .file(
+                "etc/systemd/system/changed.service",
+                "[Service]\nType=oneshot\n",
+            )
+            .file("etc/systemd/system/masked.service", "")
+            // Masking a unit that has no other unit file hides nothing
+            .file("etc/systemd/system/nothing.service", "")
+            .file(
+                "run/systemd/system/extended.service.d/10-limits.conf",
+                "[Service]\n",
+            )
+            // A merged /usr, where vendor units must not show up as overriding themselves
+            .link("lib", "usr/lib");
+
+        let deltas = delta(&LookupPaths::system(&root.0));
+
+        let types: Vec<(&str, DeltaType)> = deltas
+            .iter()
+            .map(|delta| (delta.name.as_str(), delta.delta_type))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("changed.service", DeltaType::Overridden),
+                ("masked.service", DeltaType::Masked),
+                ("same.service", DeltaType::Equivalent),
+                ("extended.service.d/10-limits.conf", DeltaType::Extended),
+            ]
+        );
+        assert!(
+            deltas[0]
+                .diff
+                .as_deref()
+                .is_some_and(|diff| diff.contains("+Type=oneshot"))
+        );
+        assert_eq!(
+            deltas[3].original,
+            Some(root.0.join("usr/lib/systemd/system/extended.service"))
+        );
+    }
+}
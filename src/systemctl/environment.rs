@@ -0,0 +1,152 @@
+use std::{collections::BTreeMap, ffi::OsString};
+
+use crate::{
+    ManagerProxy,
+    errors::SystemdError,
+    systemctl::{uncached_manager_proxy, unit_query::glob_match},
+};
+
+/// Environment variable names are made of letters, digits and underscores, and do not start with
+/// a digit, like systemd accepts them.
+pub fn is_valid_env_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Values may contain anything but control characters, except for tabs and newlines.
+pub fn is_valid_env_value(value: &str) -> bool {
+    !value
+        .chars()
+        .any(|c| c.is_control() && c != '\t' && c != '\n')
+}
+
+/// An assignment has the form "NAME=value", with a valid name and value.
+pub fn is_valid_env_assignment(assignment: &str) -> bool {
+    assignment
+        .split_once('=')
+        .is_some_and(|(name, value)| is_valid_env_name(name) && is_valid_env_value(value))
+}
+
+pub(crate) fn check_assignments(assignments: &[&str]) -> Result<(), SystemdError> {
+    match assignments.iter().find(|a| !is_valid_env_assignment(a)) {
+        Some(invalid) => Err(SystemdError::InvalidEnvironmentAssignment(
+            invalid.to_string(),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// UnsetEnvironment() takes plain names, or assignments to only unset a variable if it has that
+/// exact value.
+pub(crate) fn check_unset_names(names: &[&str]) -> Result<(), SystemdError> {
+    match names
+        .iter()
+        .find(|name| !is_valid_env_name(name) && !is_valid_env_assignment(name))
+    {
+        Some(invalid) => Err(SystemdError::InvalidEnvironmentName(invalid.to_string())),
+        None => Ok(()),
+    }
+}
+
+/// Parses "NAME=value" assignments into a map, skipping anything that is not an assignment.
+pub(crate) fn parse_environment(assignments: Vec<String>) -> BTreeMap<String, String> {
+    assignments
+        .into_iter()
+        .filter_map(|assignment| {
+            let (name, value) = assignment.split_once('=')?;
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Picks the variables to import from the given environment, as `systemctl import-environment`
+/// does. Names may be globs. Names that are not set are skipped, but invalid names are an error.
+/// Variables that are not valid UTF-8 cannot be sent to the manager, so they are skipped too.
+pub(crate) fn select_for_import(
+    names: &[&str],
+    environment: impl IntoIterator<Item = (OsString, OsString)>,
+) -> Result<Vec<String>, SystemdError> {
+    for name in names {
+        let is_glob = name.contains(['*', '?', '[']);
+        if !is_glob && !is_valid_env_name(name) {
+            return Err(SystemdError::InvalidEnvironmentName(name.to_string()));
+        }
+    }
+
+    Ok(environment
+        .into_iter()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+        .filter(|(name, _)| names.iter().any(|pattern| glob_match(pattern, name)))
+        .map(|(name, value)| format!("{name}={value}"))
+        .filter(|assignment| is_valid_env_assignment(assignment))
+        .collect())
+}
+
+/// The Environment property does not emit PropertiesChanged, so it is always read fresh.
+pub(crate) async fn current_environment(
+    proxy: &ManagerProxy<'_>,
+) -> Result<BTreeMap<String, String>, SystemdError> {
+    let uncached = uncached_manager_proxy(proxy).await?;
+
+    Ok(parse_environment(uncached.environment().await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::ffi::OsStringExt;
+
+    use super::*;
+
+    #[test]
+    fn validates_names_and_assignments() {
+        assert!(is_valid_env_name("PATH"));
+        assert!(is_valid_env_name("_private_1"));
+        assert!(!is_valid_env_name("1ST"));
+        assert!(!is_valid_env_name("WITH-DASH"));
+        assert!(!is_valid_env_name(""));
+
+        assert!(is_valid_env_assignment("LANG=en_US.UTF-8"));
+        assert!(is_valid_env_assignment("EMPTY="));
+        assert!(!is_valid_env_assignment("NO_EQUALS"));
+        assert!(!is_valid_env_assignment("BELL=\u{7}"));
+
+        assert!(check_unset_names(&["PATH", "LANG=C"]).is_ok());
+        assert!(check_unset_names(&["NOT VALID"]).is_err());
+    }
+
+    #[test]
+    fn parses_environment_into_map() {
+        let environment = parse_environment(vec![
+            "LANG=C.UTF-8".to_string(),
+            "PATH=/usr/bin:/bin".to_string(),
+            "EQUALS=a=b".to_string(),
+        ]);
+
+        assert_eq!(environment["LANG"], "C.UTF-8");
+        assert_eq!(environment["EQUALS"], "a=b");
+        assert_eq!(environment.len(), 3);
+    }
+
+    #[test]
+    fn selects_variables_to_import_by_glob() {
+        let environment = vec![
+            ("XDG_RUNTIME_DIR".into(), "/run/user/1000".into()),
+            ("XDG_SESSION_ID".into(), "3".into()),
+            (
+                "XDG_DATA_HOME".into(),
+                OsString::from_vec(b"/home/\xff".to_vec()),
+            ),
+            ("HOME".into(), "/home/user".into()),
+        ];
+
+        let selected =
+            select_for_import(&["XDG_*", "MISSING"], environment).expect("The names are valid");
+
+        assert_eq!(
+            selected,
+            vec!["XDG_RUNTIME_DIR=/run/user/1000", "XDG_SESSION_ID=3"]
+        );
+        assert!(select_for_import(&["NOT-VALID"], Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+use std::{fmt::Display, thread, time::Duration};
+
+use async_io::Timer;
+use futures_lite::{StreamExt, future};
+
+use crate::{ManagerProxy, UnitProxy, errors::SystemdError};
+
+/// How long to wait for the freezer to settle unless the builder sets another timeout. This matches
+/// the default start and stop timeout of systemd, DefaultTimeoutStartSec=.
+pub(crate) const DEFAULT_FREEZER_TIMEOUT: Duration = Duration::from_secs(90);
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum FreezerState {
+    /// The processes of the unit are running normally
+    Running,
+    /// The processes of the unit are being frozen
+    Freezing,
+    /// All processes of the unit are frozen
+    Frozen,
+    /// The processes of the unit are being thawed
+    Thawing,
+    Other(String),
+}
+
+impl From<String> for FreezerState {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "running" => FreezerState::Running,
+            "freezing" => FreezerState::Freezing,
+            "frozen" => FreezerState::Frozen,
+            "thawing" => FreezerState::Thawing,
+            _ => FreezerState::Other(value),
+        }
+    }
+}
+
+impl Display for FreezerState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            FreezerState::Running => "running",
+            FreezerState::Freezing => "freezing",
+            FreezerState::Frozen => "frozen",
+            FreezerState::Thawing => "thawing",
+            FreezerState::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+/// Waits for the FreezerState property of the unit to reach the target state.
+pub(crate) async fn wait_for_freezer_state(
+    unit: &UnitProxy<'_>,
+    target: FreezerState,
+    timeout: Duration,
+) -> Result<(), SystemdError> {
+    let mut changes = unit.receive_freezer_state_changed().await;
+
+    let wait = async {
+        loop {
+            if FreezerState::from(unit.freezer_state().await?) == target {
+                return Ok(());
+            }
+
+            if changes.next().await.is_none() {
+                return Err(SystemdError::SignalStreamClosed);
+            }
+        }
+    };
+
+    let timer = async {
+        Timer::after(timeout).await;
+        Err(SystemdError::Timeout)
+    };
+
+    future::or(wait, timer).await
+}
+
+/// Freezes the unit and waits until all of its processes are frozen.
+pub(crate) async fn freeze(
+    manager_proxy: &ManagerProxy<'_>,
+    unit: &UnitProxy<'_>,
+    name: &str,
+    timeout: Duration,
+) -> Result<(), SystemdError> {
+    manager_proxy.freeze_unit(name).await?;
+    wait_for_freezer_state(unit, FreezerState::Frozen, timeout).await
+}
+
+/// Thaws the unit and waits until all of its processes are running again.
+pub(crate) async fn thaw(
+    manager_proxy: &ManagerProxy<'_>,
+    unit: &UnitProxy<'_>,
+    name: &str,
+    timeout: Duration,
+) -> Result<(), SystemdError> {
+    manager_proxy.thaw_unit(name).await?;
+    wait_for_freezer_state(unit, FreezerState::Running, timeout).await
+}
+
+/// Keeps a unit frozen for as long as it is alive, and thaws the unit again when dropped. This
+/// makes sure a workload is not left frozen when the code that froze it returns early or panics.
+///
+/// Dropping the guard does not block: the ThawUnit() call is sent from a separate thread, and any
+/// error is ignored. Use [`FreezeGuard::thaw`] to find out whether thawing worked.
+pub struct FreezeGuard<'a> {
+    manager_proxy: Option<ManagerProxy<'a>>,
+    unit: UnitProxy<'a>,
+    name: String,
+    timeout: Duration,
+}
+
+impl<'a> FreezeGuard<'a> {
+    pub(crate) fn new(
+        manager_proxy: ManagerProxy<'a>,
+        unit: UnitProxy<'a>,
+        name: &str,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            manager_proxy: Some(manager_proxy),
+            unit,
+            name: name.to_string(),
+            timeout,
+        }
+    }
+
+    /// The name of the frozen unit.
+    pub fn unit_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Thaws the unit and waits for it to be running again.
+    pub async fn thaw(mut self) -> Result<(), SystemdError> {
+        let Some(manager_proxy) = self.manager_proxy.take() else {
+            return Ok(());
+        };
+
+        thaw(&manager_proxy, &self.unit, &self.name, self.timeout).await
+    }
+
+    /// Like thaw(), but blocks the current thread instead.
+    pub fn thaw_blocking(self) -> Result<(), SystemdError> {
+        async_io::block_on(self.thaw())
+    }
+}
+
+impl Drop for FreezeGuard<'_> {
+    fn drop(&mut self) {
+        let Some(manager_proxy) = self.manager_proxy.take() else {
+            return;
+        };
+
+        // Drop cannot wait for the reply, which may block forever inside an executor. The proxy
+        // borrows from the guard, so a new one is made on the same connection for the thread.
+        let connection = manager_proxy.inner().connection().clone();
+        let name = std::mem::take(&mut self.name);
+        thread::spawn(move || {
+            let _ = async_io::block_on(async {
+                ManagerProxy::new(&connection).await?.thaw_unit(&name).await
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freezer_state_round_trips_through_strings() {
+        for raw in ["running", "freezing", "frozen", "thawing"] {
+            let state: FreezerState = raw.to_string().into();
+            assert!(!matches!(state, FreezerState::Other(_)));
+            assert_eq!(state.to_string(), raw);
+        }
+    }
+}
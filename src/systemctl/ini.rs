@@ -0,0 +1,179 @@
+//! A parser for the ini-like syntax of unit files, see "man systemd.syntax".
+
+/// A single "Key=Value" assignment.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct IniEntry {
+    pub key: String,
+    pub value: String,
+    /// The line the assignment starts on, counting from 1
+    pub line: usize,
+}
+
+/// A "[Section]" along with the assignments in it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct IniSection {
+    pub name: String,
+    /// The line of the section header, counting from 1
+    pub line: usize,
+    pub entries: Vec<IniEntry>,
+}
+
+/// A parsed unit file. Sections are kept in file order, and a section that appears more than once
+/// is kept more than once, like systemd reads them.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Ini {
+    pub sections: Vec<IniSection>,
+    /// Lines that are neither a section header, an assignment nor a comment, and assignments
+    /// before the first section header. systemd ignores these with a warning.
+    pub invalid_lines: Vec<(usize, String)>,
+}
+
+impl Ini {
+    /// Parses the contents of a unit file. Lines ending in a backslash are continued on the next
+    /// line, with the backslash replaced by a space. Comment lines start with "#" or ";".
+    pub fn parse(contents: &str) -> Self {
+        let mut ini = Ini::default();
+        let mut lines = contents.lines().enumerate();
+
+        while let Some((index, line)) = lines.next() {
+            let line_number = index + 1;
+            let mut line = line.trim().to_string();
+
+            if line.is_empty() || line.starts_with(['#', ';']) {
+                continue;
+            }
+
+            while line.ends_with('\\') {
+                line.pop();
+                line.push(' ');
+
+                // Comment lines within a continuation are skipped without ending it
+                let Some((_, next)) = lines
+                    .by_ref()
+                    .find(|(_, next)| !next.trim().starts_with(['#', ';']))
+                else {
+                    break;
+                };
+                line.push_str(next.trim());
+            }
+            let line = line.trim();
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                ini.sections.push(IniSection {
+                    name: name.to_string(),
+                    line: line_number,
+                    entries: Vec::new(),
+                });
+                continue;
+            }
+
+            match (line.split_once('='), ini.sections.last_mut()) {
+                (Some((key, value)), Some(section)) => section.entries.push(IniEntry {
+                    key: key.trim().to_string(),
+                    value: value.trim().to_string(),
+                    line: line_number,
+                }),
+                _ => ini.invalid_lines.push((line_number, line.to_string())),
+            }
+        }
+
+        ini
+    }
+
+    /// All assignments to the key in all sections with that name, in file order.
+    pub fn entries<'a>(
+        &'a self,
+        section: &'a str,
+        key: &'a str,
+    ) -> impl Iterator<Item = &'a IniEntry> + 'a {
+        self.sections
+            .iter()
+            .filter(move |s| s.name == section)
+            .flat_map(|s| s.entries.iter())
+            .filter(move |entry| entry.key == key)
+    }
+
+    /// The last value assigned to the key, which is the one that counts for single value
+    /// settings.
+    pub fn value<'a>(&'a self, section: &'a str, key: &'a str) -> Option<&'a str> {
+        self.entries(section, key)
+            .last()
+            .map(|entry| entry.value.as_str())
+    }
+
+    /// The values of a list setting, split on whitespace. Assigning the empty string resets the
+    /// list, dropping everything assigned before.
+    pub fn list(&self, section: &str, key: &str) -> Vec<String> {
+        let mut values = Vec::new();
+
+        for entry in self.entries(section, key) {
+            if entry.value.is_empty() {
+                values.clear();
+            } else {
+                values.extend(entry.value.split_whitespace().map(ToString::to_string));
+            }
+        }
+
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_comments_within_continuations() {
+        let ini = Ini::parse(
+            "[Service]\n\
+             ExecStart=/usr/bin/foo \\\n\
+             # --verbose \\\n\
+             ; --quiet\n\
+             --bar\n\
+             Type=oneshot\n",
+        );
+
+        assert_eq!(
+            ini.value("Service", "ExecStart"),
+            Some("/usr/bin/foo  --bar")
+        );
+        assert_eq!(ini.value("Service", "Type"), Some("oneshot"));
+        assert!(ini.invalid_lines.is_empty());
+    }
+
+    #[test]
+    fn parses_sections_and_continuations() {
+        let ini = Ini::parse(
+            "# comment\n\
+             [Unit]\n\
+             Description=Test\n\
+             \n\
+             [Service]\n\
+             ExecStart=/usr/bin/foo \\\n\
+             \x20 --bar\n\
+             orphan line\n\
+             [Install]\n\
+             WantedBy=multi-user.target\n\
+             WantedBy=\n\
+             WantedBy=graphical.target default.target\n",
+        );
+
+        assert_eq!(ini.sections.len(), 3);
+        assert_eq!(ini.value("Unit", "Description"), Some("Test"));
+        assert_eq!(
+            ini.value("Service", "ExecStart"),
+            Some("/usr/bin/foo  --bar")
+        );
+        assert_eq!(ini.invalid_lines, vec![(8, "orphan line".to_string())]);
+        assert_eq!(
+            ini.list("Install", "WantedBy"),
+            vec!["graphical.target", "default.target"]
+        );
+        assert_eq!(
+            ini.entries("Install", "WantedBy")
+                .map(|e| e.line)
+                .collect::<Vec<_>>(),
+            vec![10, 11, 12]
+        );
+    }
+}
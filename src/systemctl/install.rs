@@ -0,0 +1,129 @@
+use crate::systemctl::{ini::Ini, unit_name::UnitName};
+
+// NOTE: These docs are from the man page of systemd.unit, under [Install] Section Options
+
+/// The [Install] section of a unit file, which describes what enabling the unit does.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct InstallInfo {
+    /// Units that get a symlink to this unit in their .wants/ directory
+    pub wanted_by: Vec<String>,
+    /// Units that get a symlink to this unit in their .requires/ directory
+    pub required_by: Vec<String>,
+    /// Units that get a symlink to this unit in their .upholds/ directory
+    pub upheld_by: Vec<String>,
+    /// Additional names the unit is installed under, as symlinks to it
+    pub alias: Vec<String>,
+    /// Units that are enabled or disabled along with this one
+    pub also: Vec<String>,
+    /// The instance used when a template is enabled without one
+    pub default_instance: Option<String>,
+}
+
+impl InstallInfo {
+    /// Reads the [Install] section of the unit file, expanding the specifiers that make sense
+    /// there for the given unit name.
+    pub fn from_ini(ini: &Ini, name: &UnitName) -> Self {
+        let list = |key: &str| {
+            ini.list("Install", key)
+                .iter()
+                .map(|value| expand_specifiers(value, name))
+                .collect()
+        };
+
+        Self {
+            wanted_by: list("WantedBy"),
+            required_by: list("RequiredBy"),
+            upheld_by: list("UpheldBy"),
+            alias: list("Alias"),
+            also: list("Also"),
+            default_instance: ini
+                .value("Install", "DefaultInstance")
+                .filter(|value| !value.is_empty())
+                .map(|value| expand_specifiers(value, name)),
+        }
+    }
+
+    /// Whether enabling the unit creates any symlinks of its own. Units without any are
+    /// "static", or "indirect" if they only list other units in Also=.
+    pub fn has_links(&self) -> bool {
+        !(self.wanted_by.is_empty()
+            && self.required_by.is_empty()
+            && self.upheld_by.is_empty()
+            && self.alias.is_empty())
+    }
+
+    /// The dependency directories and the units they belong to, e.g.
+    /// ("multi-user.target.wants", ...) for WantedBy=multi-user.target.
+    pub fn dependency_dirs(&self) -> Vec<String> {
+        let wants = self.wanted_by.iter().map(|unit| format!("{unit}.wants"));
+        let requires = self
+            .required_by
+            .iter()
+            .map(|unit| format!("{unit}.requires"));
+        let upholds = self.upheld_by.iter().map(|unit| format!("{unit}.upholds"));
+
+        wants.chain(requires).chain(upholds).collect()
+    }
+}
+
+/// Expands the unit name specifiers, see "man systemd.unit" under Specifiers. Only the ones
+/// derived from the unit name are supported, others are left as they are.
+pub(crate) fn expand_specifiers(value: &str, name: &UnitName) -> String {
+    let mut expanded = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            expanded.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => expanded.push_str(name.as_str()),
+            Some('N') => expanded.push_str(
+                name.as_str()
+                    .strip_suffix(name.unit_type().suffix())
+                    .and_then(|stem| stem.strip_suffix('.'))
+                    .unwrap_or(name.as_str()),
+            ),
+            Some('p') => expanded.push_str(name.prefix()),
+            Some('i') => expanded.push_str(name.instance().unwrap_or_default()),
+            Some('%') => expanded.push('%'),
+            Some(other) => {
+                expanded.push('%');
+                expanded.push(other);
+            }
+            None => expanded.push('%'),
+        }
+    }
+
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_install_section_with_specifiers() {
+        let ini = Ini::parse(
+            "[Install]\n\
+             WantedBy=multi-user.target\n\
+             RequiredBy=%p-helper.target\n\
+             Alias=%N-alias.service\n\
+             DefaultInstance=tty1\n",
+        );
+        let name = UnitName::new("getty@tty2.service").expect("This is a valid unit name");
+
+        let install = InstallInfo::from_ini(&ini, &name);
+
+        assert_eq!(install.required_by, vec!["getty-helper.target"]);
+        assert_eq!(install.alias, vec!["getty@tty2-alias.service"]);
+        assert_eq!(install.default_instance.as_deref(), Some("tty1"));
+        assert_eq!(
+            install.dependency_dirs(),
+            vec!["multi-user.target.wants", "getty-helper.target.requires"]
+        );
+        assert!(install.has_links());
+    }
+}
@@ -1,15 +1,107 @@
+use std::fmt::Display;
+
 use zbus::zvariant::OwnedObjectPath;
 
+// NOTE: These docs are all from the man page of org.freedesktop.systemd1
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum JobType {
+    /// Start the unit
+    Start,
+    /// Check that the unit is active, without starting it
+    VerifyActive,
+    /// Stop the unit
+    Stop,
+    /// Reload the unit, which fails if it is not running
+    Reload,
+    /// Reload the unit if it supports it, otherwise start it
+    ReloadOrStart,
+    /// Restart the unit, starting it if it is not running
+    Restart,
+    /// Restart the unit only if it is running
+    TryRestart,
+    /// Reload the unit only if it is running
+    TryReload,
+    /// Do nothing, used to order against other jobs
+    Nop,
+    Other(String),
+}
+
+impl From<String> for JobType {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "start" => JobType::Start,
+            "verify-active" => JobType::VerifyActive,
+            "stop" => JobType::Stop,
+            "reload" => JobType::Reload,
+            "reload-or-start" => JobType::ReloadOrStart,
+            "restart" => JobType::Restart,
+            "try-restart" => JobType::TryRestart,
+            "try-reload" => JobType::TryReload,
+            "nop" => JobType::Nop,
+            _ => JobType::Other(value),
+        }
+    }
+}
+
+impl Display for JobType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            JobType::Start => "start",
+            JobType::VerifyActive => "verify-active",
+            JobType::Stop => "stop",
+            JobType::Reload => "reload",
+            JobType::ReloadOrStart => "reload-or-start",
+            JobType::Restart => "restart",
+            JobType::TryRestart => "try-restart",
+            JobType::TryReload => "try-reload",
+            JobType::Nop => "nop",
+            JobType::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum JobState {
+    /// The job is queued but has not begun to execute yet
+    Waiting,
+    /// The job is currently being executed
+    Running,
+    Other(String),
+}
+
+impl From<String> for JobState {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "waiting" => JobState::Waiting,
+            "running" => JobState::Running,
+            _ => JobState::Other(value),
+        }
+    }
+}
+
+impl Display for JobState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            JobState::Waiting => "waiting",
+            JobState::Running => "running",
+            JobState::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Job {
     /// The numeric job id
     pub id: u32,
     /// The primary unit name for this job
     pub unit_name: String,
-    /// The job type as string
-    pub job_type: String,
-    /// The job state as string
-    pub job_state: String,
+    /// The job type
+    pub job_type: JobType,
+    /// The job state
+    pub job_state: JobState,
     /// The job object path
     pub job_path: OwnedObjectPath,
     /// The unit object path
@@ -39,10 +131,127 @@ impl
         Job {
             id: value.0,
             unit_name: value.1,
-            job_type: value.2,
-            job_state: value.3,
+            job_type: value.2.into(),
+            job_state: value.3.into(),
             job_path: value.4,
             unit_path: value.5,
         }
     }
 }
+
+/// The result of EnqueueUnitJob(): the job that was asked for, along with every other job that
+/// was pulled into the same transaction, e.g. start jobs for dependencies or stop jobs for
+/// conflicting units.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EnqueuedTransaction {
+    /// The job for the unit that was asked for
+    pub job: Job,
+    /// All jobs that were added to the queue as part of the transaction
+    pub affected: Vec<Job>,
+}
+
+type EnqueuedJob = (u32, OwnedObjectPath, String, OwnedObjectPath, String);
+
+/// EnqueueUnitJob() does not report job states. All of the jobs were just installed by the
+/// transaction, so they are waiting.
+fn enqueued_job(value: EnqueuedJob) -> Job {
+    Job {
+        id: value.0,
+        job_path: value.1,
+        unit_name: value.2,
+        unit_path: value.3,
+        job_type: value.4.into(),
+        job_state: JobState::Waiting,
+    }
+}
+
+impl
+    From<(
+        u32,
+        OwnedObjectPath,
+        String,
+        OwnedObjectPath,
+        String,
+        Vec<EnqueuedJob>,
+    )> for EnqueuedTransaction
+{
+    fn from(
+        value: (
+            u32,
+            OwnedObjectPath,
+            String,
+            OwnedObjectPath,
+            String,
+            Vec<EnqueuedJob>,
+        ),
+    ) -> Self {
+        Self {
+            job: enqueued_job((value.0, value.1, value.2, value.3, value.4)),
+            affected: value.5.into_iter().map(enqueued_job).collect(),
+        }
+    }
+}
+
+/// The jobs a job is ordered against, which explains why a job is not making progress.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct JobDependencies {
+    /// The jobs that have to complete before this job can be executed, what
+    /// `systemctl list-jobs --before` shows (GetJobBefore())
+    pub waiting_for: Vec<Job>,
+    /// The jobs that are waiting for this job to complete, what `systemctl list-jobs --after`
+    /// shows (GetJobAfter())
+    pub blocking: Vec<Job>,
+}
+
+type RawJob = (
+    u32,
+    String,
+    String,
+    String,
+    OwnedObjectPath,
+    OwnedObjectPath,
+);
+
+impl JobDependencies {
+    /// From the replies of GetJobAfter() and GetJobBefore() for the same job.
+    pub(crate) fn new(after: Vec<RawJob>, before: Vec<RawJob>) -> Self {
+        Self {
+            waiting_for: before.into_iter().map(Into::into).collect(),
+            blocking: after.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw_job(id: u32, unit_name: &str, state: &str) -> RawJob {
+        let path = |path: String| OwnedObjectPath::try_from(path).expect("Valid object path");
+        (
+            id,
+            unit_name.to_string(),
+            "start".to_string(),
+            state.to_string(),
+            path(format!("/org/freedesktop/systemd1/job/{id}")),
+            path("/org/freedesktop/systemd1/unit/x".to_string()),
+        )
+    }
+
+    #[test]
+    fn jobs_queued_behind_a_job_are_blocked_by_it() {
+        // b.service is ordered After=a.service, so its start job 2 waits for job 1 of a.service.
+        // GetJobAfter(1) returns job 2, and GetJobBefore(2) returns job 1.
+        let first = JobDependencies::new(vec![raw_job(2, "b.service", "waiting")], Vec::new());
+        let second = JobDependencies::new(Vec::new(), vec![raw_job(1, "a.service", "running")]);
+
+        assert!(first.waiting_for.is_empty());
+        assert_eq!(first.blocking.len(), 1);
+        assert_eq!(first.blocking[0].unit_name, "b.service");
+        assert_eq!(first.blocking[0].job_state, JobState::Waiting);
+
+        assert!(second.blocking.is_empty());
+        assert_eq!(second.waiting_for.len(), 1);
+        assert_eq!(second.waiting_for[0].id, 1);
+    }
+}
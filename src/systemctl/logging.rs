@@ -0,0 +1,186 @@
+use std::fmt::Display;
+
+// NOTE: These docs are mostly from the man pages of systemd and systemd-analyze
+
+/// The maximum log level of the manager, as set with `systemctl log-level`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LogLevel {
+    Emergency,
+    Alert,
+    Critical,
+    Error,
+    Warning,
+    Notice,
+    Info,
+    Debug,
+    Other(String),
+}
+
+impl From<String> for LogLevel {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "emerg" | "0" => LogLevel::Emergency,
+            "alert" | "1" => LogLevel::Alert,
+            "crit" | "2" => LogLevel::Critical,
+            "err" | "3" => LogLevel::Error,
+            "warning" | "4" => LogLevel::Warning,
+            "notice" | "5" => LogLevel::Notice,
+            "info" | "6" => LogLevel::Info,
+            "debug" | "7" => LogLevel::Debug,
+            _ => LogLevel::Other(value),
+        }
+    }
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            LogLevel::Emergency => "emerg",
+            LogLevel::Alert => "alert",
+            LogLevel::Critical => "crit",
+            LogLevel::Error => "err",
+            LogLevel::Warning => "warning",
+            LogLevel::Notice => "notice",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+/// Where the manager sends its log messages, as set with `systemd-analyze log-target`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LogTarget {
+    /// Log to the console
+    Console,
+    /// Log to the kernel log buffer
+    Kmsg,
+    /// Log to the journal
+    Journal,
+    /// Log to the journal, falling back to the kernel log buffer
+    JournalOrKmsg,
+    /// Log to syslog
+    Syslog,
+    /// Log to syslog, falling back to the kernel log buffer
+    SyslogOrKmsg,
+    /// Pick a target automatically, based on what is available
+    Auto,
+    /// Disable log output
+    Null,
+    Other(String),
+}
+
+impl From<String> for LogTarget {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "console" => LogTarget::Console,
+            "kmsg" => LogTarget::Kmsg,
+            "journal" => LogTarget::Journal,
+            "journal-or-kmsg" => LogTarget::JournalOrKmsg,
+            "syslog" => LogTarget::Syslog,
+            "syslog-or-kmsg" => LogTarget::SyslogOrKmsg,
+            "auto" => LogTarget::Auto,
+            "null" => LogTarget::Null,
+            _ => LogTarget::Other(value),
+        }
+    }
+}
+
+impl Display for LogTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            LogTarget::Console => "console",
+            LogTarget::Kmsg => "kmsg",
+            LogTarget::Journal => "journal",
+            LogTarget::JournalOrKmsg => "journal-or-kmsg",
+            LogTarget::Syslog => "syslog",
+            LogTarget::SyslogOrKmsg => "syslog-or-kmsg",
+            LogTarget::Auto => "auto",
+            LogTarget::Null => "null",
+            LogTarget::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+/// Whether the manager shows status messages on the console, i.e. ShowStatus= in
+/// systemd-system.conf.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ShowStatus {
+    /// Always show status messages
+    Yes,
+    /// Never show status messages
+    No,
+    /// Show status messages only after a unit failed, or a boot took too long
+    Auto,
+    /// Only show error messages
+    Error,
+    Other(String),
+}
+
+impl From<String> for ShowStatus {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "yes" => ShowStatus::Yes,
+            "no" => ShowStatus::No,
+            "auto" => ShowStatus::Auto,
+            "error" => ShowStatus::Error,
+            _ => ShowStatus::Other(value),
+        }
+    }
+}
+
+/// The ShowStatus property only tells whether status messages are currently shown.
+impl From<bool> for ShowStatus {
+    fn from(value: bool) -> Self {
+        if value {
+            ShowStatus::Yes
+        } else {
+            ShowStatus::No
+        }
+    }
+}
+
+impl Display for ShowStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            ShowStatus::Yes => "yes",
+            ShowStatus::No => "no",
+            ShowStatus::Auto => "auto",
+            ShowStatus::Error => "error",
+            ShowStatus::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_level_accepts_names_and_numbers() {
+        assert_eq!(LogLevel::from("debug".to_string()), LogLevel::Debug);
+        assert_eq!(LogLevel::from("3".to_string()), LogLevel::Error);
+        assert_eq!(LogLevel::Error.to_string(), "err");
+    }
+
+    #[test]
+    fn log_target_round_trips_through_strings() {
+        for raw in [
+            "console",
+            "kmsg",
+            "journal",
+            "journal-or-kmsg",
+            "syslog",
+            "syslog-or-kmsg",
+            "auto",
+            "null",
+        ] {
+            let target: LogTarget = raw.to_string().into();
+            assert!(!matches!(target, LogTarget::Other(_)));
+            assert_eq!(target.to_string(), raw);
+        }
+    }
+}
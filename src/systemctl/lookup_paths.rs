@@ -0,0 +1,619 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    env, fs,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+};
+
+use crate::systemctl::{connection_level::ConnectionLevel, unit_name::UnitName};
+
+// NOTE: The search paths are from the man page of systemd.unit, under Unit File Load Path
+
+/// What a directory in the unit search path is used for. Directories of the same kind are
+/// searched in the order they are listed.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum UnitDirKind {
+    /// Settings changed persistently through the API, e.g. with `systemctl set-property`
+    PersistentControl,
+    /// Settings changed through the API until the next reboot
+    RuntimeControl,
+    /// Transient units created through the API, e.g. with `systemd-run`
+    Transient,
+    /// Units written by generators
+    Generator,
+    /// Persistent local configuration, i.e. /etc/systemd/system/
+    PersistentConfig,
+    /// Local configuration that lasts until the next reboot, i.e. /run/systemd/system/
+    RuntimeConfig,
+    /// Units shipped with installed packages, i.e. /usr/lib/systemd/system/
+    Vendor,
+    /// Directories set through $SYSTEMD_UNIT_PATH
+    Custom,
+}
+
+/// A single directory of the unit search path.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UnitDir {
+    pub path: PathBuf,
+    pub kind: UnitDirKind,
+}
+
+/// What a path in the unit search path points to, after following symlinks.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum Resolved {
+    File(PathBuf),
+    /// A symlink to /dev/null, or an empty file
+    Masked,
+    /// A dangling symlink, or a symlink loop
+    Missing,
+}
+
+/// Where a unit was found in the search path, along with everything else that configures it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UnitFileLookup {
+    /// The name that was looked up
+    pub name: UnitName,
+    /// The entry in the search path the unit was found at, which may be a symlink
+    pub path: PathBuf,
+    /// The kind of directory the entry is in
+    pub dir_kind: UnitDirKind,
+    /// The unit file the entry points to. None if the unit is masked or the symlink is dangling
+    pub fragment: Option<PathBuf>,
+    /// Whether the unit is masked, i.e. the entry is a symlink to /dev/null or an empty file
+    pub masked: bool,
+    /// Whether the entry is the template, because there is no unit file for the instance
+    pub from_template: bool,
+    /// The other names the unit file is known by, through symlinks in the search path
+    pub aliases: Vec<String>,
+    /// The drop-in directories that exist for the unit, most specific first
+    pub drop_in_dirs: Vec<PathBuf>,
+    /// The drop-in files that are applied, in the order they are applied
+    pub drop_ins: Vec<PathBuf>,
+    /// Unit files with the same name further down the search path, hidden by this one
+    pub overridden: Vec<PathBuf>,
+}
+
+/// A unit file that hides other unit files with the same name, like `systemd-delta` reports.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OverriddenUnitFile {
+    pub name: String,
+    /// The unit file that is used
+    pub overriding: PathBuf,
+    /// The unit files that are hidden by it, highest priority first
+    pub overridden: Vec<PathBuf>,
+}
+
+/// The unit search path of a service manager, and the logic to find unit files in it. All paths
+/// are below the root directory, which is "/" unless unit files of another system are looked up.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LookupPaths {
+    root: PathBuf,
+    dirs: Vec<UnitDir>,
+}
+
+impl LookupPaths {
+    /// The search path of the system manager below the root directory. When the root is "/",
+    /// $SYSTEMD_UNIT_PATH is honored like systemd does: it replaces the search path, unless it
+    /// ends in ":", in which case the regular search path is appended.
+    pub fn system(root: &Path) -> Self {
+        use UnitDirKind::*;
+
+        let dirs = vec![
+            (PersistentControl, "etc/systemd/system.control"),
+            (RuntimeControl, "run/systemd/system.control"),
+            (Transient, "run/systemd/transient"),
+            (Generator, "run/systemd/generator.early"),
+            (PersistentConfig, "etc/systemd/system"),
+            (PersistentConfig, "etc/systemd/system.attached"),
+            (RuntimeConfig, "run/systemd/system"),
+            (RuntimeConfig, "run/systemd/system.attached"),
+            (Generator, "run/systemd/generator"),
+            (Vendor, "usr/local/lib/systemd/system"),
+            (Vendor, "usr/lib/systemd/system"),
+            (Vendor, "lib/systemd/system"),
+            (Generator, "run/systemd/generator.late"),
+        ];
+
+        let dirs = dirs
+            .into_iter()
+            .map(|(kind, path)| UnitDir {
+                path: root.join(path),
+                kind,
+            })
+            .collect();
+
+        Self::with_unit_path_override(root, dirs, |name| env::var(name).ok())
+    }
+
+    /// The search path of the service manager of the current user, based on $HOME and the XDG
+    /// base directory variables.
+    pub fn user() -> Self {
+        let env = |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+        let dirs = user_dirs(&env);
+        Self::with_unit_path_override(Path::new("/"), dirs, env)
+    }
+
+    /// The search path shared by the service managers of all users below the root directory,
+    /// which is what `systemctl --global` manages.
+    pub fn global(root: &Path) -> Self {
+        use UnitDirKind::*;
+
+        let dirs = [
+            (PersistentConfig, "etc/systemd/user"),
+            (RuntimeConfig, "run/systemd/user"),
+            (Vendor, "usr/local/lib/systemd/user"),
+            (Vendor, "usr/lib/systemd/user"),
+        ];
+
+        Self {
+            root: root.to_path_buf(),
+            dirs: dirs
+                .into_iter()
+                .map(|(kind, path)| UnitDir {
+                    path: root.join(path),
+                    kind,
+                })
+                .collect(),
+        }
+    }
+
+    /// The search path for the given connection level, as the service manager on this system
+    /// uses it.
+    pub fn for_connection_level(connection_level: &ConnectionLevel) -> Self {
+        match connection_level {
+            ConnectionLevel::UserLevel => Self::user(),
+            ConnectionLevel::SystemLevel => Self::system(Path::new("/")),
+        }
+    }
+
+    fn with_unit_path_override(
+        root: &Path,
+        dirs: Vec<UnitDir>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Self {
+        let root = root.to_path_buf();
+        let Some(unit_path) = env("SYSTEMD_UNIT_PATH").filter(|_| root == Path::new("/")) else {
+            return Self { root, dirs };
+        };
+
+        let mut custom: Vec<UnitDir> = unit_path
+            .split(':')
+            .filter(|path| !path.is_empty())
+            .map(|path| UnitDir {
+                path: PathBuf::from(path),
+                kind: UnitDirKind::Custom,
+            })
+            .collect();
+
+        if unit_path.ends_with(':') {
+            custom.extend(dirs);
+        }
+
+        Self { root, dirs: custom }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// All directories of the search path, highest priority first.
+    pub fn dirs(&self) -> &[UnitDir] {
+        &self.dirs
+    }
+
+    /// The directories of the search path, without those that are the same directory as an
+    /// earlier one, e.g. /lib/systemd/system/ when /lib is a symlink to /usr/lib.
+    pub(crate) fn unique_dirs(&self) -> Vec<&UnitDir> {
+        let mut seen = HashSet::new();
+
+        self.dirs
+            .iter()
+            .filter(|dir| match fs::metadata(&dir.path) {
+                Ok(metadata) => seen.insert((metadata.dev(), metadata.ino())),
+                Err(_) => true,
+            })
+            .collect()
+    }
+
+    /// The directory `systemctl enable` creates symlinks in, i.e. /etc/systemd/system/ for the
+    /// system manager, or the runtime equivalent in /run/.
+    pub fn config_dir(&self, runtime: bool) -> Option<&Path> {
+        let kind = if runtime {
+            UnitDirKind::RuntimeConfig
+        } else {
+            UnitDirKind::PersistentConfig
+        };
+
+        self.dirs
+            .iter()
+            .find(|dir| dir.kind == kind)
+            .map(|dir| dir.path.as_path())
+    }
+
+    /// The kind of directory the path is in, if it is in the search path.
+    pub fn dir_kind_of(&self, path: &Path) -> Option<UnitDirKind> {
+        self.dirs
+            .iter()
+            .find(|dir| path.parent() == Some(dir.path.as_path()))
+            .map(|dir| dir.kind)
+    }
+
+    /// Finds the unit file for the unit, like the service manager does when loading it. For an
+    /// instance without a unit file of its own, the template is used.
+    pub fn resolve(&self, name: &UnitName) -> Option<UnitFileLookup> {
+        let (entries, from_template) = match self.entries_named(name.as_str()) {
+            entries if !entries.is_empty() => (entries, false),
+            _ => (self.entries_named(name.template()?.as_str()), true),
+        };
+        let (path, overridden) = entries.split_first()?;
+
+        let resolved = self.resolve_link(path);
+        let fragment = match &resolved {
+            Resolved::File(file) => Some(file.clone()),
+            _ => None,
+        };
+
+        let aliases = match &fragment {
+            Some(fragment) => self.aliases_of(fragment, path),
+            None => Vec::new(),
+        };
+        let drop_in_dirs = self.drop_in_dirs(name);
+
+        Some(UnitFileLookup {
+            name: name.clone(),
+            dir_kind: self.dir_kind_of(path).unwrap_or(UnitDirKind::Custom),
+            masked: resolved == Resolved::Masked,
+            drop_ins: drop_ins(&drop_in_dirs),
+            drop_in_dirs,
+            path: path.clone(),
+            fragment,
+            from_template,
+            aliases,
+            overridden: overridden.to_vec(),
+        })
+    }
+
+    /// All unit names in the search path, with the entry that is used for each.
+    pub fn unit_files(&self) -> BTreeMap<String, PathBuf> {
+        let mut files = BTreeMap::new();
+
+        for dir in self.unique_dirs() {
+            for (name, path) in unit_entries(&dir.path) {
+                files.entry(name).or_insert(path);
+            }
+        }
+
+        files
+    }
+
+    /// Every unit file that hides unit files with the same name further down the search path,
+    /// e.g. a copy in /etc/ of a unit file shipped in /usr/lib/.
+    pub fn overridden_unit_files(&self) -> Vec<OverriddenUnitFile> {
+        let mut files: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+
+        for dir in self.unique_dirs() {
+            for (name, path) in unit_entries(&dir.path) {
+                files.entry(name).or_default().push(path);
+            }
+        }
+
+        files
+            .into_iter()
+            .filter(|(_, paths)| paths.len() > 1)
+            .map(|(name, mut paths)| OverriddenUnitFile {
+                name,
+                overriding: paths.remove(0),
+                overridden: paths,
+            })
+            .collect()
+    }
+
+    /// The drop-in directories for the unit that exist, in the order of the search path. Within
+    /// each directory of the search path, the most specific comes first: the unit itself, its
+    /// template, the prefixes of its name split on dashes (e.g. "foo-.service.d" for
+    /// "foo-bar.service") and its type (e.g. "service.d"). Like in systemd, a drop-in in /etc/
+    /// therefore hides one with the same name for the template in /usr/lib/.
+    pub fn drop_in_dirs(&self, name: &UnitName) -> Vec<PathBuf> {
+        let suffix = name.unit_type().suffix();
+        let mut dir_names = vec![format!("{name}.d")];
+
+        if let Some(template) = name.template() {
+            dir_names.push(format!("{template}.d"));
+        }
+
+        let prefix = name.prefix();
+        let mut dashes: Vec<usize> = prefix.match_indices('-').map(|(i, _)| i).collect();
+        dashes.reverse();
+        for dash in dashes {
+            dir_names.push(format!("{}-.{suffix}.d", &prefix[..dash]));
+        }
+        dir_names.push(format!("{suffix}.d"));
+
+        self.unique_dirs()
+            .into_iter()
+            .flat_map(|dir| dir_names.iter().map(|dir_name| dir.path.join(dir_name)))
+            .filter(|path| path.is_dir())
+            .collect()
+    }
+
+    /// Follows symlinks, resolving absolute symlink targets below the root directory.
+    pub(crate) fn resolve_link(&self, path: &Path) -> Resolved {
+        let mut current = path.to_path_buf();
+
+        for _ in 0..32 {
+            let Ok(target) = fs::read_link(&current) else {
+                return match fs::metadata(&current) {
+                    Ok(metadata) if metadata.len() == 0 => Resolved::Masked,
+                    Ok(_) => Resolved::File(current),
+                    Err(_) => Resolved::Missing,
+                };
+            };
+
+            if target == Path::new("/dev/null") {
+                return Resolved::Masked;
+            }
+
+            current = match target.strip_prefix("/") {
+                Ok(relative) => self.root.join(relative),
+                Err(_) => current
+                    .parent()
+                    .map_or_else(|| target.clone(), |parent| parent.join(&target)),
+            };
+        }
+
+        Resolved::Missing
+    }
+
+    /// The path as seen from the system below the root directory.
+    pub(crate) fn path_in_root(&self, path: &Path) -> PathBuf {
+        Path::new("/").join(path.strip_prefix(&self.root).unwrap_or(path))
+    }
+
+    fn entries_named(&self, name: &str) -> Vec<PathBuf> {
+        self.unique_dirs()
+            .into_iter()
+            .map(|dir| dir.path.join(name))
+            .filter(|path| path.symlink_metadata().is_ok())
+            .collect()
+    }
+
+    fn aliases_of(&self, fragment: &Path, entry: &Path) -> Vec<String> {
+        let mut aliases: Vec<String> = self
+            .unit_files()
+            .into_iter()
+            .filter(|(_, path)| path != entry)
+            .filter(|(_, path)| self.resolve_link(path) == Resolved::File(fragment.to_path_buf()))
+            .map(|(name, _)| name)
+            .collect();
+
+        let entry_name = entry.file_name();
+        if let Some(fragment_name) = fragment
+            .file_name()
+            .filter(|name| Some(*name) != entry_name)
+        {
+            let fragment_name = fragment_name.to_string_lossy().into_owned();
+            if !aliases.contains(&fragment_name) {
+                aliases.push(fragment_name);
+            }
+        }
+
+        aliases.sort();
+        aliases
+    }
+}
+
+fn user_dirs(env: &impl Fn(&str) -> Option<String>) -> Vec<UnitDir> {
+    use UnitDirKind::*;
+
+    let home = env("HOME").unwrap_or_default();
+    let config_home = env("XDG_CONFIG_HOME").unwrap_or_else(|| format!("{home}/.config"));
+    let data_home = env("XDG_DATA_HOME").unwrap_or_else(|| format!("{home}/.local/share"));
+    let config_dirs = env("XDG_CONFIG_DIRS").unwrap_or_else(|| "/etc/xdg".to_string());
+    let data_dirs =
+        env("XDG_DATA_DIRS").unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    let runtime_dir = env("XDG_RUNTIME_DIR");
+
+    let mut dirs: Vec<(UnitDirKind, String)> = vec![(
+        PersistentControl,
+        format!("{config_home}/systemd/user.control"),
+    )];
+
+    if let Some(runtime_dir) = &runtime_dir {
+        dirs.extend([
+            (
+                RuntimeControl,
+                format!("{runtime_dir}/systemd/user.control"),
+            ),
+            (Transient, format!("{runtime_dir}/systemd/transient")),
+            (Generator, format!("{runtime_dir}/systemd/generator.early")),
+        ]);
+    }
+
+    dirs.push((PersistentConfig, format!("{config_home}/systemd/user")));
+    dirs.extend(
+        config_dirs
+            .split(':')
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| (PersistentConfig, format!("{dir}/systemd/user"))),
+    );
+    dirs.push((PersistentConfig, "/etc/systemd/user".to_string()));
+
+    if let Some(runtime_dir) = &runtime_dir {
+        dirs.push((RuntimeConfig, format!("{runtime_dir}/systemd/user")));
+    }
+    dirs.push((RuntimeConfig, "/run/systemd/user".to_string()));
+
+    if let Some(runtime_dir) = &runtime_dir {
+        dirs.push((Generator, format!("{runtime_dir}/systemd/generator")));
+    }
+
+    dirs.push((Vendor, format!("{data_home}/systemd/user")));
+    dirs.extend(
+        data_dirs
+            .split(':')
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| (Vendor, format!("{dir}/systemd/user"))),
+    );
+    dirs.push((Vendor, "/usr/local/lib/systemd/user".to_string()));
+    dirs.push((Vendor, "/usr/lib/systemd/user".to_string()));
+
+    if let Some(runtime_dir) = &runtime_dir {
+        dirs.push((Generator, format!("{runtime_dir}/systemd/generator.late")));
+    }
+
+    dirs.into_iter()
+        .map(|(kind, path)| UnitDir {
+            path: PathBuf::from(path),
+            kind,
+        })
+        .collect()
+}
+
+/// The unit files and symlinks directly in a directory of the search path, by name.
+fn unit_entries(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| !entry.path().is_dir() || entry.path().is_symlink())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            UnitName::new(name.as_str()).ok()?;
+            Some((name, entry.path()))
+        })
+        .collect()
+}
+
+/// The ".conf" files in the drop-in directories, sorted by file name. A file hides files with
+/// the same name in later directories, which is how a drop-in in /etc/ replaces one in /usr/lib/.
+fn drop_ins(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files: BTreeMap<String, PathBuf> = BTreeMap::new();
+
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.ends_with(".conf") {
+                files.entry(name).or_insert_with(|| entry.path());
+            }
+        }
+    }
+
+    files.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemctl::test_root::TestRoot;
+
+    #[test]
+    fn builds_user_search_path_from_xdg_variables() {
+        let env = |name: &str| match name {
+            "HOME" => Some("/home/user".to_string()),
+            "XDG_RUNTIME_DIR" => Some("/run/user/1000".to_string()),
+            "XDG_DATA_DIRS" => Some("/opt/share".to_string()),
+            _ => None,
+        };
+
+        let dirs: Vec<PathBuf> = user_dirs(&env).into_iter().map(|dir| dir.path).collect();
+
+        let position = |path: &str| {
+            dirs.iter()
+                .position(|dir| dir == Path::new(path))
+                .unwrap_or_else(|| panic!("{path} should be in the search path"))
+        };
+        assert!(position("/home/user/.config/systemd/user") < position("/etc/systemd/user"));
+        assert!(position("/run/user/1000/systemd/user") < position("/run/systemd/user"));
+        assert!(position("/opt/share/systemd/user") < position("/usr/lib/systemd/user"));
+        assert_eq!(
+            dirs.last().map(PathBuf::as_path),
+            Some(Path::new("/run/user/1000/systemd/generator.late"))
+        );
+    }
+
+    #[test]
+    fn unit_path_variable_overrides_search_path() {
+        let dirs = || vec![];
+        let env = |value: &'static str| move |_: &str| Some(value.to_string());
+
+        let replaced = LookupPaths::with_unit_path_override(Path::new("/"), dirs(), env("/a:/b"));
+        assert_eq!(replaced.dirs().len(), 2);
+
+        let appended = LookupPaths::with_unit_path_override(
+            Path::new("/"),
+            vec![UnitDir {
+                path: PathBuf::from("/usr/lib/systemd/system"),
+                kind: UnitDirKind::Vendor,
+            }],
+            env("/a:"),
+        );
+        assert_eq!(appended.dirs().len(), 2);
+        assert_eq!(appended.dirs()[1].kind, UnitDirKind::Vendor);
+    }
+
+    #[test]
+    fn resolves_fragments_aliases_templates_and_drop_ins() {
+        let root = TestRoot::new("resolve");
+        root.file("usr/lib/systemd/system/foo-bar@.service", "[Service]\n")
+            .file("etc/systemd/system/other.service", "[Service]\n")
+            .file("usr/lib/systemd/system/other.service", "[Service]\n")
+            .link(
+                "etc/systemd/system/alias.service",
+                "/usr/lib/systemd/system/foo-bar@.service",
+            )
+            .file("usr/lib/systemd/system/foo-bar@.service.d/10-a.conf", "")
+            .file("etc/systemd/system/foo-.service.d/10-a.conf", "")
+            .file("run/systemd/system/service.d/20-b.conf", "");
+        let lookup = LookupPaths::system(&root.0);
+
+        let name = UnitName::new("foo-bar@x.service").expect("This is a valid unit name");
+        let unit = lookup.resolve(&name).expect("The template should be found");
+
+        assert!(unit.from_template);
+        assert_eq!(unit.dir_kind, UnitDirKind::Vendor);
+        assert_eq!(
+            unit.fragment,
+            Some(root.0.join("usr/lib/systemd/system/foo-bar@.service"))
+        );
+        assert_eq!(unit.aliases, vec!["alias.service"]);
+        assert_eq!(unit.drop_in_dirs.len(), 3);
+        assert_eq!(
+            unit.drop_ins,
+            vec![
+                root.0.join("etc/systemd/system/foo-.service.d/10-a.conf"),
+                root.0.join("run/systemd/system/service.d/20-b.conf"),
+            ]
+        );
+
+        let overridden = lookup.overridden_unit_files();
+        assert_eq!(overridden.len(), 1);
+        assert_eq!(overridden[0].name, "other.service");
+        assert_eq!(
+            overridden[0].overriding,
+            root.0.join("etc/systemd/system/other.service")
+        );
+    }
+
+    #[test]
+    fn skips_directories_that_are_symlinked_into_usr() {
+        let root = TestRoot::new("merged-usr");
+        root.file("usr/lib/systemd/system/foo.service", "[Service]\n")
+            .file("usr/lib/systemd/system/foo.service.d/10-a.conf", "")
+            .link("lib", "usr/lib");
+        let lookup = LookupPaths::system(&root.0);
+
+        let name = UnitName::new("foo.service").expect("This is a valid unit name");
+        let unit = lookup.resolve(&name).expect("The unit should be found");
+
+        assert!(unit.overridden.is_empty());
+        assert_eq!(unit.drop_ins.len(), 1);
+        assert!(lookup.overridden_unit_files().is_empty());
+        assert_eq!(lookup.unique_dirs().len(), lookup.dirs().len() - 1);
+    }
+}
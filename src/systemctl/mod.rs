@@ -1,7 +1,76 @@
 //! Main service in this crate
+pub mod clean;
 pub mod connection_level;
+pub mod delta;
+pub mod environment;
+pub mod freezer;
+pub mod ini;
+pub mod install;
 pub mod job;
+pub mod logging;
+pub mod lookup_paths;
+pub mod offline;
+pub mod power;
+pub mod preset;
+pub mod process;
+pub mod reset_failed;
+pub mod run;
+pub mod security;
+pub mod system_state;
 pub mod systemctl_async;
 pub mod systemctl_blocking;
+#[cfg(test)]
+pub(crate) mod test_root;
+pub mod time;
+pub mod transient;
 pub mod unit;
+pub mod unit_config;
 pub mod unit_file;
+pub mod unit_name;
+pub mod unit_query;
+pub mod verify;
+pub mod watchdog;
+
+use zbus::proxy::CacheProperties;
+
+use crate::{ManagerProxy, errors::SystemdError};
+
+/// A manager proxy that reads properties fresh rather than from the property cache. Some
+/// properties do not emit PropertiesChanged, or are changed at runtime by other clients, so a
+/// cached value may be stale.
+pub(crate) async fn uncached_manager_proxy(
+    proxy: &ManagerProxy<'_>,
+) -> Result<ManagerProxy<'static>, SystemdError> {
+    Ok(ManagerProxy::builder(proxy.inner().connection())
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await?)
+}
+
+/// Asks the manager to send out signals, which it only does once at least one client has
+/// subscribed. The manager refuses a second Subscribe() from the same connection, which is taken
+/// as success. Returns whether this call created the subscription, only then should it be
+/// dropped again with [`unsubscribe_if_subscribed`].
+pub(crate) async fn subscribe(proxy: &ManagerProxy<'_>) -> Result<bool, SystemdError> {
+    match proxy.subscribe().await {
+        Ok(()) => Ok(true),
+        Err(zbus::Error::MethodError(name, _, _))
+            if name.as_str() == "org.freedesktop.systemd1.AlreadySubscribed" =>
+        {
+            Ok(false)
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Drops the subscription if [`subscribe`] created it, so a subscription that someone else set
+/// up on the same connection is left alone.
+pub(crate) async fn unsubscribe_if_subscribed(
+    proxy: &ManagerProxy<'_>,
+    subscribed: bool,
+) -> Result<(), SystemdError> {
+    if subscribed {
+        proxy.unsubscribe().await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,757 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Display,
+    fs, io,
+    os::unix::fs::symlink,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    errors::SystemdError,
+    systemctl::{
+        connection_level::ConnectionLevel,
+        delta::{self, Delta},
+        ini::Ini,
+        install::InstallInfo,
+        lookup_paths::{LookupPaths, Resolved},
+        preset::{PresetAction, Presets},
+        security::{SandboxSettings, SecurityReport},
+        unit::{UnitChangeKind, UnitEnablementChange, UnitEnablementResponse},
+        unit_config::{self, service::ServiceUnitConfig},
+        unit_file::{EnablementStatus, UnitFile, UnitFileQuery},
+        unit_name::{UnitName, UnitType},
+        unit_query::glob_match,
+    },
+};
+
+/// Manages unit files below an alternate root directory without talking to systemd, like
+/// `systemctl --root=` does. This is meant for preparing OS images, where no service manager is
+/// running. Symlinks are created as they would be on the running system, i.e. pointing to
+/// absolute paths without the root prefix.
+///
+/// At the user level, the unit files for all users are managed, like `systemctl --global` does.
+///
+/// All paths returned are paths on the host, i.e. below the root directory.
+pub struct OfflineSystemCtl {
+    root: PathBuf,
+    connection_level: ConnectionLevel,
+    lookup_paths: LookupPaths,
+}
+
+impl OfflineSystemCtl {
+    pub(crate) fn new(root: &Path, connection_level: ConnectionLevel) -> Self {
+        let lookup_paths = match connection_level {
+            ConnectionLevel::UserLevel => LookupPaths::global(root),
+            ConnectionLevel::SystemLevel => LookupPaths::system(root),
+        };
+
+        Self {
+            root: root.to_path_buf(),
+            connection_level,
+            lookup_paths,
+        }
+    }
+
+    /// The root directory the unit files are managed in.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The unit search path below the root directory.
+    pub fn lookup_paths(&self) -> &LookupPaths {
+        &self.lookup_paths
+    }
+
+    /// Writes a unit file, e.g. a [`ServiceUnitConfig`], to /etc/systemd/system/ below the root
+    /// directory, or /run/systemd/system/ if runtime is set. Returns the path written.
+    ///
+    /// [`ServiceUnitConfig`]: crate::ServiceUnitConfig
+    pub fn write_unit_file(
+        &self,
+        name: &str,
+        contents: &impl Display,
+        runtime: bool,
+    ) -> Result<PathBuf, SystemdError> {
+        unit_config::write_unit_file(&self.lookup_paths, name, &contents.to_string(), runtime)
+    }
+
+    /// Assesses how well the service is sandboxed, based on its unit file and drop-ins below the
+    /// root directory, like `systemd-analyze security --offline=yes --root=` does. Settings that
+    /// cannot be parsed are treated as not set.
+    pub fn security_report(&self, name: &str) -> Result<SecurityReport, SystemdError> {
+        let unit_name = UnitName::new(name)?;
+        if unit_name.unit_type() != UnitType::Service {
+            return Err(SystemdError::NotAService(name.to_string()));
+        }
+
+        let lookup = self
+            .lookup_paths
+            .resolve(&unit_name)
+            .ok_or_else(|| SystemdError::UnitFileNotFound(name.to_string()))?;
+        let Some(fragment) = &lookup.fragment else {
+            return Err(SystemdError::UnitFileMasked(name.to_string()));
+        };
+
+        let mut contents = fs::read_to_string(fragment)?;
+        for drop_in in &lookup.drop_ins {
+            contents.push('\n');
+            contents.push_str(&fs::read_to_string(drop_in)?);
+        }
+
+        let (config, _) = ServiceUnitConfig::read(&Ini::parse(&contents));
+        let settings = SandboxSettings::from_exec_settings(&config.service.exec);
+
+        Ok(SecurityReport::new(name, &settings))
+    }
+
+    /// Lists the unit files and drop-ins below the root directory that mask, override, redirect
+    /// or extend other unit files, like `systemd-delta --root=` does for the unit directories.
+    pub fn delta(&self) -> Vec<Delta> {
+        delta::delta(&self.lookup_paths)
+    }
+
+    /// Enables one or more units, by creating the symlinks described in the [Install] section of
+    /// their unit files in /etc/, or /run/ if runtime_only is set. Units listed in Also= are
+    /// enabled as well, and templates are enabled with their DefaultInstance=. Existing symlinks
+    /// pointing elsewhere are only replaced if force is set.
+    pub fn enable_units(
+        &self,
+        names: &[&str],
+        runtime_only: bool,
+        force: bool,
+    ) -> Result<UnitEnablementResponse, SystemdError> {
+        let mut changes = Vec::new();
+        let mut visited = HashSet::new();
+
+        for name in names {
+            self.enable(name, runtime_only, force, &mut changes, &mut visited)?;
+        }
+
+        Ok(UnitEnablementResponse::AdditionalContext(changes))
+    }
+
+    /// Disables one or more units, by removing all symlinks to them from /etc/, or /run/ if
+    /// runtime_only is set. Units listed in Also= are disabled as well. Masks are left alone.
+    pub fn disable_units(
+        &self,
+        names: &[&str],
+        runtime_only: bool,
+    ) -> Result<UnitEnablementResponse, SystemdError> {
+        let mut targets = HashSet::new();
+        for name in names {
+            self.collect_disable_targets(name, &mut targets)?;
+        }
+
+        let mut changes = Vec::new();
+        for link in self.links_in(&self.config_dir(runtime_only)) {
+            let Ok(destination) = fs::read_link(&link) else {
+                continue;
+            };
+            if destination == Path::new("/dev/null") {
+                continue;
+            }
+
+            if link_refers_to(&link, &destination, &targets) {
+                fs::remove_file(&link)?;
+                changes.push(unlink_change(&link));
+            }
+        }
+
+        Ok(UnitEnablementResponse::AdditionalContext(changes))
+    }
+
+    /// Masks one or more units by symlinking them to /dev/null in /etc/, or /run/ if runtime_only
+    /// is set, so that they cannot be started. Existing symlinks are only replaced if force is
+    /// set, and unit files are never replaced.
+    pub fn mask_units(
+        &self,
+        names: &[&str],
+        runtime_only: bool,
+        force: bool,
+    ) -> Result<UnitEnablementResponse, SystemdError> {
+        let mut changes = Vec::new();
+
+        for name in names {
+            let name = UnitName::new(*name)?;
+            let link = self.config_dir(runtime_only).join(name.as_str());
+            create_symlink(&link, Path::new("/dev/null"), force, &mut changes)?;
+        }
+
+        Ok(UnitEnablementResponse::AdditionalContext(changes))
+    }
+
+    /// Unmasks one or more units, by removing their symlinks to /dev/null from /etc/, or /run/ if
+    /// runtime_only is set.
+    pub fn unmask_units(
+        &self,
+        names: &[&str],
+        runtime_only: bool,
+    ) -> Result<UnitEnablementResponse, SystemdError> {
+        let mut changes = Vec::new();
+
+        for name in names {
+            let name = UnitName::new(*name)?;
+            let link = self.config_dir(runtime_only).join(name.as_str());
+
+            if fs::read_link(&link).is_ok_and(|target| target == Path::new("/dev/null")) {
+                fs::remove_file(&link)?;
+                changes.push(unlink_change(&link));
+            }
+        }
+
+        Ok(UnitEnablementResponse::AdditionalContext(changes))
+    }
+
+    /// Returns the enablement state of a unit file, like `systemctl --root= is-enabled`.
+    pub fn get_unit_file_state(&self, name: &str) -> Result<EnablementStatus, SystemdError> {
+        let name = UnitName::new(name)?;
+        let Some(path) = self.find_fragment(&name) else {
+            return Err(SystemdError::UnitFileNotFound(name.to_string()));
+        };
+
+        let is_runtime = path.starts_with(self.config_dir(true));
+        let file = match self.resolve(&path) {
+            Resolved::File(file) => file,
+            Resolved::Masked if is_runtime => return Ok(EnablementStatus::MaskedRuntime),
+            Resolved::Masked => return Ok(EnablementStatus::Masked),
+            Resolved::Missing => return Ok(EnablementStatus::Bad),
+        };
+
+        let in_config = is_runtime || path.starts_with(self.config_dir(false));
+        if in_config && path.is_symlink() {
+            if file.file_name() != path.file_name() {
+                return Ok(EnablementStatus::Alias);
+            }
+            if !self.unit_dirs().iter().any(|dir| file.starts_with(dir)) {
+                return Ok(if is_runtime {
+                    EnablementStatus::LinkedRuntime
+                } else {
+                    EnablementStatus::Linked
+                });
+            }
+        }
+
+        let install = self.install_info(&file, &name)?;
+
+        if self.is_enabled_in(&self.config_dir(false), &name) {
+            return Ok(EnablementStatus::Enabled);
+        }
+        if self.is_enabled_in(&self.config_dir(true), &name) {
+            return Ok(EnablementStatus::EnabledRuntime);
+        }
+
+        Ok(match (install.has_links(), install.also.is_empty()) {
+            (false, true) => EnablementStatus::Static,
+            (false, false) => EnablementStatus::Indirect,
+            (true, _) => EnablementStatus::Disabled,
+        })
+    }
+
+    /// Lists all unit files in the unit search path along with their enablement state and preset
+    /// policy. Unit files in earlier directories of the search path hide those with the same name
+    /// in later ones.
+    pub fn list_unit_files(&self) -> Result<Vec<UnitFile>, SystemdError> {
+        let presets = self.presets();
+        let mut paths: BTreeMap<String, PathBuf> = BTreeMap::new();
+
+        for dir in self.unit_dirs() {
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            };
+
+            for entry in entries {
+                let entry = entry?;
+                let file_name = entry.file_name().to_string_lossy().into_owned();
+                if UnitName::new(file_name.as_str()).is_ok() && !entry.path().is_dir() {
+                    paths.entry(file_name).or_insert_with(|| entry.path());
+                }
+            }
+        }
+
+        // A unit file that cannot be read or parsed is listed as bad, like systemctl does
+        Ok(paths
+            .into_iter()
+            .map(|(name, path)| {
+                let state = self
+                    .get_unit_file_state(&name)
+                    .unwrap_or(EnablementStatus::Bad);
+                UnitFile::from((path.to_string_lossy().into_owned(), state.to_string()))
+                    .with_preset(&presets)
+            })
+            .collect())
+    }
+
+    /// Lists the unit files matching the query, like `systemctl --root= list-unit-files` does
+    /// with its --state=, --type= and pattern arguments.
+    pub fn list_unit_files_by_query(
+        &self,
+        query: &UnitFileQuery,
+    ) -> Result<Vec<UnitFile>, SystemdError> {
+        let states = query.states();
+        let patterns = query.patterns();
+
+        Ok(self
+            .list_unit_files()?
+            .into_iter()
+            .filter(|unit_file| {
+                states.is_empty() || states.contains(&unit_file.enablement_status.to_string())
+            })
+            .filter(|unit_file| {
+                patterns.is_empty()
+                    || patterns
+                        .iter()
+                        .any(|pattern| glob_match(pattern, unit_file.file_name()))
+            })
+            .filter(|unit_file| query.matches(unit_file))
+            .collect())
+    }
+
+    /// The preset policy below the root directory.
+    pub fn presets(&self) -> Presets {
+        Presets::load(&self.connection_level, &self.root)
+    }
+
+    /// Enables or disables one or more units as the preset policy says, like
+    /// `systemctl --root= preset`. Templates are enabled with the instances listed in the
+    /// preset rule, if any.
+    pub fn preset_units(
+        &self,
+        names: &[&str],
+        runtime_only: bool,
+        force: bool,
+    ) -> Result<UnitEnablementResponse, SystemdError> {
+        let presets = self.presets();
+        let mut changes = Vec::new();
+
+        for name in names {
+            let to_enable = match presets.rule_for(name) {
+                Some(rule) if rule.action == PresetAction::Disable => {
+                    changes.extend(self.disable_units(&[name], runtime_only)?.changes());
+                    continue;
+                }
+                Some(rule) if rule.action == PresetAction::Ignore => continue,
+                Some(rule) if !rule.instances.is_empty() => {
+                    let template = UnitName::new(*name)?;
+                    rule.instances
+                        .iter()
+                        .map(|instance| Ok(template.with_instance(instance)?.to_string()))
+                        .collect::<Result<Vec<_>, SystemdError>>()?
+                }
+                _ => vec![name.to_string()],
+            };
+
+            let to_enable: Vec<&str> = to_enable.iter().map(String::as_str).collect();
+            changes.extend(
+                self.enable_units(&to_enable, runtime_only, force)?
+                    .changes(),
+            );
+        }
+
+        Ok(UnitEnablementResponse::AdditionalContext(changes))
+    }
+
+    /// Applies the preset policy to all unit files that can be enabled, like
+    /// `systemctl --root= preset-all`.
+    pub fn preset_all(
+        &self,
+        runtime_only: bool,
+        force: bool,
+    ) -> Result<UnitEnablementResponse, SystemdError> {
+        let names: Vec<String> = self
+            .list_unit_files()?
+            .into_iter()
+            .filter(|unit_file| {
+                matches!(
+                    unit_file.enablement_status,
+                    EnablementStatus::Enabled
+                        | EnablementStatus::Disabled
+                        | EnablementStatus::Indirect
+                )
+            })
+            .map(|unit_file| unit_file.file_name().to_string())
+            .collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+        self.preset_units(&names, runtime_only, force)
+    }
+
+    fn enable(
+        &self,
+        name: &str,
+        runtime_only: bool,
+        force: bool,
+        changes: &mut Vec<UnitEnablementChange>,
+        visited: &mut HashSet<String>,
+    ) -> Result<(), SystemdError> {
+        if !visited.insert(name.to_string()) {
+            return Ok(());
+        }
+
+        let mut name = UnitName::new(name)?;
+        let Some(path) = self.find_fragment(&name) else {
+            return Err(SystemdError::UnitFileNotFound(name.to_string()));
+        };
+        let file = match self.resolve(&path) {
+            Resolved::File(file) => file,
+            Resolved::Masked => return Err(SystemdError::UnitFileMasked(name.to_string())),
+            Resolved::Missing => return Err(SystemdError::UnitFileNotFound(name.to_string())),
+        };
+
+        let mut install = self.install_info(&file, &name)?;
+        if name.is_template() {
+            if let Some(instance) = &install.default_instance {
+                name = name.with_instance(instance)?;
+                install = self.install_info(&file, &name)?;
+            } else {
+                install.wanted_by.clear();
+                install.required_by.clear();
+                install.upheld_by.clear();
+            }
+        }
+
+        let destination = self.path_in_root(&path);
+        let config_dir = self.config_dir(runtime_only);
+
+        for dir in install.dependency_dirs() {
+            let link = config_dir.join(dir).join(name.as_str());
+            create_symlink(&link, &destination, force, changes)?;
+        }
+
+        for alias in &install.alias {
+            let alias = UnitName::new(alias.as_str())?;
+            if alias.unit_type() != name.unit_type() {
+                return Err(SystemdError::InvalidUnitName(alias.to_string()));
+            }
+            create_symlink(
+                &config_dir.join(alias.as_str()),
+                &destination,
+                force,
+                changes,
+            )?;
+        }
+
+        for also in &install.also {
+            self.enable(also, runtime_only, force, changes, visited)?;
+        }
+
+        Ok(())
+    }
+
+    /// The names whose symlinks are removed when disabling the unit: its own name, its aliases
+    /// and, recursively, the units listed in Also=.
+    fn collect_disable_targets(
+        &self,
+        name: &str,
+        targets: &mut HashSet<String>,
+    ) -> Result<(), SystemdError> {
+        let name = UnitName::new(name)?;
+        if !targets.insert(name.to_string()) {
+            return Ok(());
+        }
+
+        let Some(Resolved::File(file)) = self.find_fragment(&name).map(|p| self.resolve(&p)) else {
+            return Ok(());
+        };
+
+        let install = self.install_info(&file, &name)?;
+        targets.extend(install.alias);
+        for also in &install.also {
+            self.collect_disable_targets(also, targets)?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether the config directory has a dependency symlink for the unit, or for an instance of
+    /// it if it is a template, or an alias pointing to it.
+    fn is_enabled_in(&self, config_dir: &Path, name: &UnitName) -> bool {
+        self.links_in(config_dir).iter().any(|link| {
+            let Some(link_name) = link.file_name().and_then(|n| n.to_str()) else {
+                return false;
+            };
+
+            if link.parent() != Some(config_dir) {
+                return link_name == name.as_str()
+                    || UnitName::new(link_name)
+                        .ok()
+                        .and_then(|n| n.template())
+                        .is_some_and(|template| &template == name);
+            }
+
+            link_name != name.as_str()
+                && fs::read_link(link)
+                    .is_ok_and(|target| target.file_name() == Some(name.as_str().as_ref()))
+        })
+    }
+
+    /// All symlinks directly in the config directory, or in its .wants/, .requires/ and
+    /// .upholds/ directories.
+    fn links_in(&self, config_dir: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(config_dir) else {
+            return Vec::new();
+        };
+
+        let mut links = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_symlink() {
+                links.push(path);
+            } else if path.is_dir()
+                && [".wants", ".requires", ".upholds"]
+                    .iter()
+                    .any(|suffix| path.to_string_lossy().ends_with(suffix))
+            {
+                let Ok(children) = fs::read_dir(&path) else {
+                    continue;
+                };
+                links.extend(
+                    children
+                        .flatten()
+                        .map(|child| child.path())
+                        .filter(|child| child.is_symlink()),
+                );
+            }
+        }
+
+        links
+    }
+
+    fn install_info(&self, file: &Path, name: &UnitName) -> Result<InstallInfo, SystemdError> {
+        let contents = fs::read_to_string(file)?;
+        Ok(InstallInfo::from_ini(&Ini::parse(&contents), name))
+    }
+
+    /// Looks up the unit in the search path, falling back to the template for instances.
+    fn find_fragment(&self, name: &UnitName) -> Option<PathBuf> {
+        self.lookup_paths.resolve(name).map(|lookup| lookup.path)
+    }
+
+    fn resolve(&self, path: &Path) -> Resolved {
+        self.lookup_paths.resolve_link(path)
+    }
+
+    fn path_in_root(&self, path: &Path) -> PathBuf {
+        self.lookup_paths.path_in_root(path)
+    }
+
+    fn unit_dirs(&self) -> Vec<PathBuf> {
+        self.lookup_paths
+            .dirs()
+            .iter()
+            .map(|dir| dir.path.clone())
+            .collect()
+    }
+
+    fn config_dir(&self, runtime_only: bool) -> PathBuf {
+        self.lookup_paths
+            .config_dir(runtime_only)
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| self.root.clone())
+    }
+}
+
+fn link_refers_to(link: &Path, destination: &Path, names: &HashSet<String>) -> bool {
+    let link_name = link
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    let destination_name = destination
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+
+    names.contains(link_name)
+        || names.contains(destination_name)
+        || UnitName::new(link_name)
+            .ok()
+            .and_then(|name| name.template())
+            .is_some_and(|template| names.contains(template.as_str()))
+}
+
+fn create_symlink(
+    link: &Path,
+    destination: &Path,
+    force: bool,
+    changes: &mut Vec<UnitEnablementChange>,
+) -> Result<(), SystemdError> {
+    if let Ok(metadata) = link.symlink_metadata() {
+        if !metadata.is_symlink() {
+            return Err(SystemdError::UnitFileExists(link.display().to_string()));
+        }
+        if fs::read_link(link)? == destination {
+            return Ok(());
+        }
+        if !force {
+            return Err(SystemdError::UnitFileExists(link.display().to_string()));
+        }
+
+        fs::remove_file(link)?;
+        changes.push(unlink_change(link));
+    }
+
+    if let Some(parent) = link.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    symlink(destination, link)?;
+    changes.push(UnitEnablementChange {
+        unit_change_kind: UnitChangeKind::Symlink,
+        filename: link.display().to_string(),
+        destination: destination.display().to_string(),
+    });
+
+    Ok(())
+}
+
+fn unlink_change(link: &Path) -> UnitEnablementChange {
+    UnitEnablementChange {
+        unit_change_kind: UnitChangeKind::Unlink,
+        filename: link.display().to_string(),
+        destination: String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemctl::test_root::TestRoot;
+
+    #[test]
+    fn enables_and_disables_units() {
+        let root = TestRoot::new("enable");
+        root.unit(
+            "foo.service",
+            "[Service]\nExecStart=/bin/true\n[Install]\nWantedBy=multi-user.target\nAlias=bar.service\n",
+        );
+        let systemctl = root.systemctl();
+
+        assert_eq!(
+            systemctl.get_unit_file_state("foo.service").ok(),
+            Some(EnablementStatus::Disabled)
+        );
+
+        let changes = systemctl
+            .enable_units(&["foo.service"], false, false)
+            .expect("Should be able to enable the unit")
+            .changes();
+        assert_eq!(changes.len(), 2);
+
+        let link = root
+            .0
+            .join("etc/systemd/system/multi-user.target.wants/foo.service");
+        assert_eq!(
+            fs::read_link(&link).ok(),
+            Some(PathBuf::from("/usr/lib/systemd/system/foo.service"))
+        );
+        assert_eq!(
+            systemctl.get_unit_file_state("foo.service").ok(),
+            Some(EnablementStatus::Enabled)
+        );
+        assert_eq!(
+            systemctl.get_unit_file_state("bar.service").ok(),
+            Some(EnablementStatus::Alias)
+        );
+
+        let changes = systemctl
+            .disable_units(&["foo.service"], false)
+            .expect("Should be able to disable the unit")
+            .changes();
+        assert_eq!(changes.len(), 2);
+        assert!(!link.is_symlink());
+        assert_eq!(
+            systemctl.get_unit_file_state("foo.service").ok(),
+            Some(EnablementStatus::Disabled)
+        );
+    }
+
+    #[test]
+    fn enables_templates_with_default_instance() {
+        let root = TestRoot::new("template");
+        root.unit(
+            "getty@.service",
+            "[Install]\nWantedBy=getty.target\nDefaultInstance=tty1\n",
+        );
+        let systemctl = root.systemctl();
+
+        systemctl
+            .enable_units(&["getty@.service"], false, false)
+            .expect("Should be able to enable the template");
+
+        assert!(
+            root.0
+                .join("etc/systemd/system/getty.target.wants/getty@tty1.service")
+                .is_symlink()
+        );
+        assert_eq!(
+            systemctl.get_unit_file_state("getty@.service").ok(),
+            Some(EnablementStatus::Enabled)
+        );
+    }
+
+    #[test]
+    fn masks_and_unmasks_units() {
+        let root = TestRoot::new("mask");
+        root.unit("foo.service", "[Service]\nExecStart=/bin/true\n");
+        let systemctl = root.systemctl();
+
+        assert_eq!(
+            systemctl.get_unit_file_state("foo.service").ok(),
+            Some(EnablementStatus::Static)
+        );
+
+        systemctl
+            .mask_units(&["foo.service"], true, false)
+            .expect("Should be able to mask the unit");
+        assert_eq!(
+            systemctl.get_unit_file_state("foo.service").ok(),
+            Some(EnablementStatus::MaskedRuntime)
+        );
+        assert!(matches!(
+            systemctl.enable_units(&["foo.service"], false, false),
+            Err(SystemdError::UnitFileMasked(_))
+        ));
+
+        systemctl
+            .unmask_units(&["foo.service"], true)
+            .expect("Should be able to unmask the unit");
+        assert_eq!(
+            systemctl.get_unit_file_state("foo.service").ok(),
+            Some(EnablementStatus::Static)
+        );
+    }
+
+    #[test]
+    fn applies_presets() {
+        let root = TestRoot::new("preset");
+        root.unit("foo.service", "[Install]\nWantedBy=multi-user.target\n")
+            .unit("bar.service", "[Install]\nWantedBy=multi-user.target\n");
+        fs::create_dir_all(root.0.join("usr/lib/systemd/system-preset"))
+            .expect("Should be able to create the preset dir");
+        fs::write(
+            root.0.join("usr/lib/systemd/system-preset/90-test.preset"),
+            "enable foo.service\ndisable *\n",
+        )
+        .expect("Should be able to write the preset file");
+        let systemctl = root.systemctl();
+
+        systemctl
+            .preset_all(false, false)
+            .expect("Should be able to apply presets");
+
+        let files = systemctl
+            .list_unit_files()
+            .expect("Should be able to list unit files");
+        let states: Vec<(&str, String)> = files
+            .iter()
+            .map(|file| (file.file_name(), file.enablement_status.to_string()))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                ("bar.service", "disabled".to_string()),
+                ("foo.service", "enabled".to_string())
+            ]
+        );
+    }
+}
@@ -0,0 +1,344 @@
+use std::{
+    fmt::Display,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use zbus::Connection;
+
+use crate::{
+    ManagerProxy,
+    errors::SystemdError,
+    login1::{
+        inhibitor::{InhibitWhat, Inhibitor},
+        manager::ManagerProxy as LoginManagerProxy,
+    },
+};
+
+/// A system wide power state change, as requested by `systemctl reboot`, `systemctl suspend` etc.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PowerAction {
+    /// Shut down and reboot the system
+    Reboot,
+    /// Shut down and power off the system
+    PowerOff,
+    /// Shut down and halt the system, without powering it off
+    Halt,
+    /// Shut down and reboot the system through kexec
+    Kexec,
+    /// Suspend the system to RAM
+    Suspend,
+    /// Hibernate the system to disk
+    Hibernate,
+    /// Suspend the system to both RAM and disk
+    HybridSleep,
+    /// Suspend the system, and hibernate it after a delay
+    SuspendThenHibernate,
+}
+
+impl PowerAction {
+    /// The kind of inhibitor lock that holds back this action.
+    pub fn inhibited_by(&self) -> InhibitWhat {
+        match self {
+            PowerAction::Reboot
+            | PowerAction::PowerOff
+            | PowerAction::Halt
+            | PowerAction::Kexec => InhibitWhat::Shutdown,
+            PowerAction::Suspend
+            | PowerAction::Hibernate
+            | PowerAction::HybridSleep
+            | PowerAction::SuspendThenHibernate => InhibitWhat::Sleep,
+        }
+    }
+
+    /// Whether this action shuts the system down, as opposed to putting it to sleep. Only these
+    /// actions can be scheduled, or forced through the service manager.
+    pub fn is_shutdown(&self) -> bool {
+        self.inhibited_by() == InhibitWhat::Shutdown
+    }
+
+    fn from_shutdown_type(value: &str) -> Option<Self> {
+        match value {
+            "reboot" => Some(PowerAction::Reboot),
+            "poweroff" => Some(PowerAction::PowerOff),
+            "halt" => Some(PowerAction::Halt),
+            "kexec" => Some(PowerAction::Kexec),
+            _ => None,
+        }
+    }
+}
+
+impl Display for PowerAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            PowerAction::Reboot => "reboot",
+            PowerAction::PowerOff => "poweroff",
+            PowerAction::Halt => "halt",
+            PowerAction::Kexec => "kexec",
+            PowerAction::Suspend => "suspend",
+            PowerAction::Hibernate => "hibernate",
+            PowerAction::HybridSleep => "hybrid-sleep",
+            PowerAction::SuspendThenHibernate => "suspend-then-hibernate",
+        };
+        f.write_str(value)
+    }
+}
+
+/// Whether a power action may be carried out, as returned by CanReboot() and friends.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum PowerCapability {
+    /// The action is supported and the caller may carry it out without authentication
+    Yes,
+    /// The action is supported, but the caller is not allowed to carry it out
+    No,
+    /// The action is supported, but only after authorization
+    Challenge,
+    /// The action is not supported by the hardware, kernel or drivers
+    NotApplicable,
+    Other(String),
+}
+
+impl PowerCapability {
+    /// Whether the action may be requested at all, possibly after authorization.
+    pub fn is_available(&self) -> bool {
+        matches!(self, PowerCapability::Yes | PowerCapability::Challenge)
+    }
+}
+
+impl From<String> for PowerCapability {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "yes" => PowerCapability::Yes,
+            "no" => PowerCapability::No,
+            "challenge" => PowerCapability::Challenge,
+            "na" => PowerCapability::NotApplicable,
+            _ => PowerCapability::Other(value),
+        }
+    }
+}
+
+impl Display for PowerCapability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            PowerCapability::Yes => "yes",
+            PowerCapability::No => "no",
+            PowerCapability::Challenge => "challenge",
+            PowerCapability::NotApplicable => "na",
+            PowerCapability::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+/// A shutdown that logind was asked to carry out at a later point in time.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ScheduledShutdown {
+    /// The action to carry out
+    pub action: PowerAction,
+    /// Whether this is a dry run, which does everything except actually shutting down
+    pub dry_run: bool,
+    /// When the action is carried out
+    pub at: SystemTime,
+}
+
+impl ScheduledShutdown {
+    fn from_property(value: (String, u64)) -> Option<Self> {
+        let (dry_run, kind) = match value.0.strip_prefix("dry-") {
+            Some(kind) => (true, kind),
+            None => (false, value.0.as_str()),
+        };
+
+        Some(Self {
+            action: PowerAction::from_shutdown_type(kind)?,
+            dry_run,
+            at: UNIX_EPOCH + Duration::from_micros(value.1),
+        })
+    }
+
+    fn shutdown_type(&self) -> String {
+        if self.dry_run {
+            format!("dry-{}", self.action)
+        } else {
+            self.action.to_string()
+        }
+    }
+}
+
+/// logind only lives on the system bus. The connection of the service manager is reused when it
+/// is a system bus connection already.
+pub(crate) async fn login_manager_proxy(
+    connection: Option<&Connection>,
+) -> Result<LoginManagerProxy<'static>, SystemdError> {
+    let connection = match connection {
+        Some(connection) => connection.clone(),
+        None => Connection::system().await?,
+    };
+
+    Ok(LoginManagerProxy::new(&connection).await?)
+}
+
+pub(crate) async fn capability(
+    login: &LoginManagerProxy<'_>,
+    action: PowerAction,
+) -> Result<PowerCapability, SystemdError> {
+    let capability = match action {
+        PowerAction::Reboot => login.can_reboot().await?,
+        PowerAction::PowerOff => login.can_power_off().await?,
+        PowerAction::Halt => login.can_halt().await?,
+        PowerAction::Kexec => match login.can_reboot_to_kexec().await {
+            Err(err) if is_unknown_method(&err) => kexec_capability(),
+            capability => capability?,
+        },
+        PowerAction::Suspend => login.can_suspend().await?,
+        PowerAction::Hibernate => login.can_hibernate().await?,
+        PowerAction::HybridSleep => login.can_hybrid_sleep().await?,
+        PowerAction::SuspendThenHibernate => login.can_suspend_then_hibernate().await?,
+    };
+
+    Ok(capability.into())
+}
+
+/// Without CanRebootToKexec(), kexec is available once a kernel has been loaded for it, which is
+/// what `systemctl kexec` relies on as well.
+fn kexec_capability() -> String {
+    let loaded = std::fs::read_to_string("/sys/kernel/kexec_loaded")
+        .is_ok_and(|loaded| loaded.trim() == "1");
+
+    if loaded { "yes" } else { "na" }.to_string()
+}
+
+fn is_unknown_method(error: &zbus::Error) -> bool {
+    matches!(
+        error,
+        zbus::Error::MethodError(name, _, _)
+            if name.as_str() == "org.freedesktop.DBus.Error.UnknownMethod"
+    )
+}
+
+pub(crate) async fn list_inhibitors(
+    login: &LoginManagerProxy<'_>,
+) -> Result<Vec<Inhibitor>, SystemdError> {
+    Ok(login
+        .list_inhibitors()
+        .await?
+        .into_iter()
+        .map(Inhibitor::from)
+        .collect())
+}
+
+/// The inhibitor locks that would refuse the action. Delay locks are left out, since they only
+/// hold the action back for a short while.
+pub(crate) async fn blocking_inhibitors(
+    login: &LoginManagerProxy<'_>,
+    action: PowerAction,
+) -> Result<Vec<Inhibitor>, SystemdError> {
+    let what = action.inhibited_by();
+
+    Ok(list_inhibitors(login)
+        .await?
+        .into_iter()
+        .filter(|inhibitor| inhibitor.is_blocking() && inhibitor.inhibits(&what))
+        .collect())
+}
+
+/// Asks logind to carry out the action, after making sure it is available and no inhibitor lock
+/// refuses it. Kexec is not offered by logind, so it goes through the service manager like
+/// `systemctl kexec` does, by starting kexec.target.
+pub(crate) async fn request(
+    login: &LoginManagerProxy<'_>,
+    manager: &ManagerProxy<'_>,
+    action: PowerAction,
+) -> Result<(), SystemdError> {
+    let inhibitors = blocking_inhibitors(login, action).await?;
+    if !inhibitors.is_empty() {
+        return Err(SystemdError::PowerActionInhibited {
+            action: action.to_string(),
+            inhibitors: inhibitors.iter().map(Inhibitor::description).collect(),
+        });
+    }
+
+    let capability = capability(login, action).await?;
+    if !capability.is_available() {
+        return Err(SystemdError::PowerActionUnavailable {
+            action: action.to_string(),
+            capability: capability.to_string(),
+        });
+    }
+
+    // With "challenge", logind only carries out the action after polkit asked for authorization,
+    // which it only does for interactive calls.
+    let interactive = capability == PowerCapability::Challenge;
+    match action {
+        PowerAction::Reboot => login.reboot(interactive).await?,
+        PowerAction::PowerOff => login.power_off(interactive).await?,
+        PowerAction::Halt => login.halt(interactive).await?,
+        PowerAction::Kexec => {
+            manager
+                .start_unit("kexec.target", "replace-irreversibly")
+                .await?;
+        }
+        PowerAction::Suspend => login.suspend(interactive).await?,
+        PowerAction::Hibernate => login.hibernate(interactive).await?,
+        PowerAction::HybridSleep => login.hybrid_sleep(interactive).await?,
+        PowerAction::SuspendThenHibernate => login.suspend_then_hibernate(interactive).await?,
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn schedule(
+    login: &LoginManagerProxy<'_>,
+    shutdown: &ScheduledShutdown,
+) -> Result<(), SystemdError> {
+    if !shutdown.action.is_shutdown() {
+        return Err(SystemdError::PowerActionUnsupported(
+            shutdown.action.to_string(),
+        ));
+    }
+
+    let usec = shutdown
+        .at
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros() as u64;
+
+    Ok(login
+        .schedule_shutdown(&shutdown.shutdown_type(), usec)
+        .await?)
+}
+
+pub(crate) async fn scheduled(
+    login: &LoginManagerProxy<'_>,
+) -> Result<Option<ScheduledShutdown>, SystemdError> {
+    Ok(ScheduledShutdown::from_property(
+        login.scheduled_shutdown().await?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_scheduled_shutdown_property() {
+        let scheduled =
+            ScheduledShutdown::from_property(("dry-reboot".to_string(), 1_700_000_000_000_000))
+                .expect("A dry reboot is a valid shutdown type");
+
+        assert_eq!(scheduled.action, PowerAction::Reboot);
+        assert!(scheduled.dry_run);
+        assert_eq!(
+            scheduled.at,
+            UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+        );
+        assert_eq!(scheduled.shutdown_type(), "dry-reboot");
+
+        assert_eq!(ScheduledShutdown::from_property((String::new(), 0)), None);
+    }
+
+    #[test]
+    fn maps_actions_to_inhibitor_locks() {
+        assert_eq!(PowerAction::Kexec.inhibited_by(), InhibitWhat::Shutdown);
+        assert_eq!(PowerAction::HybridSleep.inhibited_by(), InhibitWhat::Sleep);
+        assert!(!PowerAction::Suspend.is_shutdown());
+    }
+}
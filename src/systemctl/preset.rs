@@ -0,0 +1,191 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::systemctl::{connection_level::ConnectionLevel, unit_query::glob_match};
+
+// NOTE: These docs are from the man page of systemd.preset
+
+const SYSTEM_PRESET_DIRS: [&str; 4] = [
+    "etc/systemd/system-preset",
+    "run/systemd/system-preset",
+    "usr/local/lib/systemd/system-preset",
+    "usr/lib/systemd/system-preset",
+];
+
+const USER_PRESET_DIRS: [&str; 4] = [
+    "etc/systemd/user-preset",
+    "run/systemd/user-preset",
+    "usr/local/lib/systemd/user-preset",
+    "usr/lib/systemd/user-preset",
+];
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PresetAction {
+    /// The unit should be enabled by `systemctl preset`
+    Enable,
+    /// The unit should be disabled by `systemctl preset`
+    Disable,
+    /// The unit is left as it is by `systemctl preset`
+    Ignore,
+}
+
+impl Display for PresetAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            PresetAction::Enable => "enabled",
+            PresetAction::Disable => "disabled",
+            PresetAction::Ignore => "ignored",
+        };
+        f.write_str(value)
+    }
+}
+
+/// A single line of a preset file, e.g. "enable getty@.service tty1 tty2".
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PresetRule {
+    pub action: PresetAction,
+    /// A unit name, or a shell-style glob matching unit names
+    pub pattern: String,
+    /// For templates, the instances that should be enabled. Empty means the DefaultInstance= of
+    /// the template is used.
+    pub instances: Vec<String>,
+}
+
+/// The preset policy, i.e. all rules of all preset files in the order they are applied.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Presets {
+    rules: Vec<PresetRule>,
+}
+
+impl Presets {
+    /// Loads the preset files for the system or user manager below the given root directory,
+    /// usually "/". Files in /etc/ override files with the same name in /run/ and /usr/lib/, and
+    /// all files are then applied in lexicographic order of their file names. Unreadable
+    /// directories and files are skipped.
+    pub fn load(connection_level: &ConnectionLevel, root: &Path) -> Self {
+        let dirs = match connection_level {
+            ConnectionLevel::UserLevel => USER_PRESET_DIRS,
+            ConnectionLevel::SystemLevel => SYSTEM_PRESET_DIRS,
+        };
+
+        let mut files: BTreeMap<String, PathBuf> = BTreeMap::new();
+        for dir in dirs {
+            let Ok(entries) = fs::read_dir(root.join(dir)) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let file_name = entry.file_name().to_string_lossy().into_owned();
+                if file_name.ends_with(".preset") {
+                    files.entry(file_name).or_insert_with(|| entry.path());
+                }
+            }
+        }
+
+        let rules = files
+            .values()
+            .filter_map(|path| fs::read_to_string(path).ok())
+            .flat_map(|contents| Self::parse(&contents).rules)
+            .collect();
+
+        Self { rules }
+    }
+
+    /// Parses the contents of a single preset file. Lines that are not understood are skipped,
+    /// just like systemd does.
+    pub fn parse(contents: &str) -> Self {
+        let rules = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with(['#', ';']))
+            .filter_map(|line| {
+                let mut words = line.split_whitespace();
+                let action = match words.next()? {
+                    "enable" => PresetAction::Enable,
+                    "disable" => PresetAction::Disable,
+                    "ignore" => PresetAction::Ignore,
+                    _ => return None,
+                };
+                let pattern = words.next()?.to_string();
+                let instances = words.map(ToString::to_string).collect();
+
+                Some(PresetRule {
+                    action,
+                    pattern,
+                    instances,
+                })
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    pub fn rules(&self) -> &[PresetRule] {
+        &self.rules
+    }
+
+    /// The first rule matching the unit name, if any.
+    pub fn rule_for(&self, unit_name: &str) -> Option<&PresetRule> {
+        self.rules
+            .iter()
+            .find(|rule| glob_match(&rule.pattern, unit_name))
+    }
+
+    /// The preset action for a unit. If no rule matches the unit, it is enabled, which is also the
+    /// policy when there are no preset files at all.
+    pub fn action_for(&self, unit_name: &str) -> PresetAction {
+        self.rule_for(unit_name)
+            .map_or(PresetAction::Enable, |rule| rule.action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRESET_FILE: &str = "
+# Comment
+enable dbus.service
+enable getty@.service tty1 tty2
+disable systemd-*
+; Another comment
+not-a-verb foo.service
+disable *
+";
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let presets = Presets::parse(PRESET_FILE);
+
+        assert_eq!(presets.rules().len(), 4);
+        assert_eq!(presets.action_for("dbus.service"), PresetAction::Enable);
+        assert_eq!(
+            presets.action_for("systemd-journald.service"),
+            PresetAction::Disable
+        );
+        assert_eq!(presets.action_for("cron.service"), PresetAction::Disable);
+    }
+
+    #[test]
+    fn keeps_template_instances() {
+        let presets = Presets::parse(PRESET_FILE);
+
+        let rule = presets
+            .rule_for("getty@.service")
+            .expect("Should have a rule for getty");
+
+        assert_eq!(rule.instances, vec!["tty1", "tty2"]);
+    }
+
+    #[test]
+    fn enables_everything_without_rules() {
+        assert_eq!(
+            Presets::default().action_for("anything.service"),
+            PresetAction::Enable
+        );
+    }
+}
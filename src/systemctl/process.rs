@@ -0,0 +1,226 @@
+use std::fs;
+
+use zbus::zvariant::OwnedObjectPath;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UnitProcess {
+    /// The control group the process is in, relative to the root of the cgroup hierarchy
+    pub cgroup_path: String,
+    /// The process id
+    pub pid: u32,
+    /// The command line of the process
+    pub command_line: String,
+}
+
+impl From<(String, u32, String)> for UnitProcess {
+    fn from(value: (String, u32, String)) -> Self {
+        Self {
+            cgroup_path: value.0,
+            pid: value.1,
+            command_line: value.2,
+        }
+    }
+}
+
+/// A control group along with the processes directly in it and its child control groups, similar
+/// to what `systemctl status` and `systemd-cgls` print.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct CgroupNode {
+    /// The full path of the control group
+    pub path: String,
+    /// The processes directly in this control group
+    pub processes: Vec<UnitProcess>,
+    /// The child control groups that contain processes
+    pub children: Vec<CgroupNode>,
+}
+
+impl CgroupNode {
+    /// Groups a flat list of processes into a tree, rooted at the deepest control group that
+    /// contains all of them. For the processes of a unit, this is the unit's own control group.
+    pub fn from_processes(processes: Vec<UnitProcess>) -> Self {
+        let root = common_ancestor(processes.iter().map(|p| p.cgroup_path.as_str()));
+
+        let mut tree = CgroupNode {
+            path: root,
+            ..Default::default()
+        };
+
+        for process in processes {
+            tree.insert(process);
+        }
+
+        tree
+    }
+
+    /// All processes in this control group and below it.
+    pub fn all_processes(&self) -> Vec<&UnitProcess> {
+        self.processes
+            .iter()
+            .chain(self.children.iter().flat_map(CgroupNode::all_processes))
+            .collect()
+    }
+
+    fn insert(&mut self, process: UnitProcess) {
+        if process.cgroup_path == self.path {
+            self.processes.push(process);
+            return;
+        }
+
+        let relative = process
+            .cgroup_path
+            .strip_prefix(&self.path)
+            .unwrap_or(&process.cgroup_path)
+            .trim_start_matches('/');
+        let child_name = relative.split('/').next().unwrap_or(relative);
+        let child_path = join_cgroup(&self.path, child_name);
+
+        let child = match self.children.iter().position(|c| c.path == child_path) {
+            Some(index) => &mut self.children[index],
+            None => {
+                self.children.push(CgroupNode {
+                    path: child_path,
+                    ..Default::default()
+                });
+                self.children
+                    .last_mut()
+                    .expect("A child was pushed just now")
+            }
+        };
+
+        child.insert(process);
+    }
+}
+
+/// The unit a process belongs to.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProcessOwner {
+    /// The primary unit name
+    pub unit_name: String,
+    /// The unit object path
+    pub object_path: OwnedObjectPath,
+}
+
+/// Reads the control group of a process from /proc/, as used by the manager. Only the unified
+/// (cgroup v2) hierarchy or the systemd named hierarchy is considered.
+pub(crate) fn cgroup_of_pid(pid: u32) -> Option<String> {
+    let contents = fs::read_to_string(format!("/proc/{pid}/cgroup")).ok()?;
+
+    contents.lines().find_map(|line| {
+        let mut parts = line.splitn(3, ':');
+        let (_, controllers, path) = (parts.next()?, parts.next()?, parts.next()?);
+        (controllers.is_empty() || controllers == "name=systemd").then(|| path.to_string())
+    })
+}
+
+/// Whether the manager answered that it knows no unit for the process or control group. Only
+/// then is it worth looking further, any other error is passed on.
+pub(crate) fn is_unit_not_found(error: &zbus::Error) -> bool {
+    matches!(
+        error,
+        zbus::Error::MethodError(name, _, _)
+            if name.as_str() == "org.freedesktop.systemd1.NoUnitForPID"
+                || name.as_str() == "org.freedesktop.systemd1.NoSuchUnit"
+    )
+}
+
+/// The control group itself followed by each of its parents, up to and including the root.
+pub(crate) fn cgroup_ancestors(path: &str) -> Vec<String> {
+    let mut ancestors = vec![path.to_string()];
+    let mut current = path;
+
+    while let Some((parent, _)) = current.rsplit_once('/') {
+        let parent = if parent.is_empty() { "/" } else { parent };
+        if ancestors.last().is_some_and(|last| last == parent) {
+            break;
+        }
+        ancestors.push(parent.to_string());
+        current = parent;
+    }
+
+    ancestors
+}
+
+fn join_cgroup(parent: &str, child: &str) -> String {
+    if parent.ends_with('/') {
+        format!("{parent}{child}")
+    } else {
+        format!("{parent}/{child}")
+    }
+}
+
+fn common_ancestor<'a>(mut paths: impl Iterator<Item = &'a str>) -> String {
+    let Some(first) = paths.next() else {
+        return "/".to_string();
+    };
+
+    let mut common: Vec<&str> = first.split('/').collect();
+    for path in paths {
+        let matching = common
+            .iter()
+            .zip(path.split('/'))
+            .take_while(|(a, b)| *a == b)
+            .count();
+        common.truncate(matching);
+    }
+
+    let joined = common.join("/");
+    if joined.is_empty() {
+        "/".to_string()
+    } else {
+        joined
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(cgroup_path: &str, pid: u32) -> UnitProcess {
+        (cgroup_path.to_string(), pid, format!("process {pid}")).into()
+    }
+
+    #[test]
+    fn groups_processes_into_cgroup_tree() {
+        let tree = CgroupNode::from_processes(vec![
+            process("/system.slice/foo.service", 1),
+            process("/system.slice/foo.service/worker", 2),
+            process("/system.slice/foo.service/worker/a", 3),
+            process("/system.slice/foo.service/worker", 4),
+        ]);
+
+        assert_eq!(tree.path, "/system.slice/foo.service");
+        assert_eq!(tree.processes.len(), 1);
+        assert_eq!(tree.children.len(), 1);
+
+        let worker = &tree.children[0];
+        assert_eq!(worker.path, "/system.slice/foo.service/worker");
+        assert_eq!(worker.processes.len(), 2);
+        assert_eq!(
+            worker.children[0].path,
+            "/system.slice/foo.service/worker/a"
+        );
+
+        assert_eq!(tree.all_processes().len(), 4);
+    }
+
+    #[test]
+    fn root_is_common_ancestor_when_unit_cgroup_is_empty() {
+        let tree = CgroupNode::from_processes(vec![
+            process("/system.slice/foo.service/a", 1),
+            process("/system.slice/foo.service/b", 2),
+        ]);
+
+        assert_eq!(tree.path, "/system.slice/foo.service");
+        assert!(tree.processes.is_empty());
+        assert_eq!(tree.children.len(), 2);
+    }
+
+    #[test]
+    fn lists_cgroup_ancestors() {
+        assert_eq!(
+            cgroup_ancestors("/system.slice/foo.service"),
+            vec!["/system.slice/foo.service", "/system.slice", "/"]
+        );
+        assert_eq!(cgroup_ancestors("/"), vec!["/"]);
+    }
+}
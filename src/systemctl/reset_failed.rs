@@ -0,0 +1,12 @@
+use crate::systemctl::{unit_name::UnitName, unit_query::UnitQuery};
+
+/// Selects the units `reset_failed` acts on, like the arguments of `systemctl reset-failed`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ResetFailedSelector {
+    /// Every unit, like `systemctl reset-failed` without arguments
+    All,
+    /// Only the units with these names
+    Units(Vec<UnitName>),
+    /// The units matching the query, e.g. using glob patterns
+    Query(UnitQuery),
+}
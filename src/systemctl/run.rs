@@ -0,0 +1,504 @@
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, PipeReader},
+    os::fd::OwnedFd,
+    sync::atomic::{AtomicU32, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_io::Async;
+use futures_lite::{AsyncReadExt, StreamExt, future};
+use zbus::{
+    fdo::PropertiesProxy,
+    proxy::CacheProperties,
+    zvariant::{Fd, Value},
+};
+
+use crate::{
+    ManagerProxy, ServiceProxy, UnitProxy,
+    errors::SystemdError,
+    systemctl::{
+        subscribe,
+        transient::{self, Properties, TransientUnit, exec_command},
+        uncached_manager_proxy,
+        unit::{UnitActiveState, UnitMode},
+        unit_name::{UnitName, unit_object_path},
+        unsubscribe_if_subscribed,
+    },
+};
+
+// NOTE: These docs are from the man page of systemd.exec, under $SERVICE_RESULT
+
+/// Why a service stopped, the Result property of the service.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ServiceResult {
+    /// The service ran successfully
+    Success,
+    /// The service did not fulfill the protocol of its Type=
+    Protocol,
+    /// A resource the service needed was not available
+    Resources,
+    /// An operation timed out
+    Timeout,
+    /// The main process exited with a non-zero exit status
+    ExitCode,
+    /// The main process was killed by a signal
+    Signal,
+    /// The main process dumped core
+    CoreDump,
+    /// The watchdog keep-alive ping was missed
+    Watchdog,
+    /// The service was started too often, see StartLimitBurst=
+    StartLimitHit,
+    /// A process of the service was killed by the OOM killer
+    OomKill,
+    Other(String),
+}
+
+impl From<String> for ServiceResult {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "success" => ServiceResult::Success,
+            "protocol" => ServiceResult::Protocol,
+            "resources" => ServiceResult::Resources,
+            "timeout" => ServiceResult::Timeout,
+            "exit-code" => ServiceResult::ExitCode,
+            "signal" => ServiceResult::Signal,
+            "core-dump" => ServiceResult::CoreDump,
+            "watchdog" => ServiceResult::Watchdog,
+            "start-limit-hit" => ServiceResult::StartLimitHit,
+            "oom-kill" => ServiceResult::OomKill,
+            _ => ServiceResult::Other(value),
+        }
+    }
+}
+
+impl Display for ServiceResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            ServiceResult::Success => "success",
+            ServiceResult::Protocol => "protocol",
+            ServiceResult::Resources => "resources",
+            ServiceResult::Timeout => "timeout",
+            ServiceResult::ExitCode => "exit-code",
+            ServiceResult::Signal => "signal",
+            ServiceResult::CoreDump => "core-dump",
+            ServiceResult::Watchdog => "watchdog",
+            ServiceResult::StartLimitHit => "start-limit-hit",
+            ServiceResult::OomKill => "oom-kill",
+            ServiceResult::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+/// How the main process of a service exited.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ExitStatus {
+    /// The process exited with this status
+    Exited(i32),
+    /// The process was killed by this signal
+    Killed(i32),
+    /// The process was killed by this signal and dumped core
+    Dumped(i32),
+}
+
+impl ExitStatus {
+    /// From the ExecMainCode and ExecMainStatus properties, None if the process has not exited.
+    pub(crate) fn from_code(code: i32, status: i32) -> Option<Self> {
+        // CLD_EXITED, CLD_KILLED and CLD_DUMPED from waitid(2)
+        match code {
+            1 => Some(ExitStatus::Exited(status)),
+            2 => Some(ExitStatus::Killed(status)),
+            3 => Some(ExitStatus::Dumped(status)),
+            _ => None,
+        }
+    }
+
+    /// Whether the process exited with status 0.
+    pub fn success(&self) -> bool {
+        *self == ExitStatus::Exited(0)
+    }
+}
+
+impl Display for ExitStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExitStatus::Exited(status) => write!(f, "exited with status {status}"),
+            ExitStatus::Killed(signal) => write!(f, "killed by signal {signal}"),
+            ExitStatus::Dumped(signal) => write!(f, "killed by signal {signal}, core dumped"),
+        }
+    }
+}
+
+/// The outcome of a command that ran as a transient service, like the summary
+/// `systemd-run --wait` prints.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CommandResult {
+    pub unit: UnitName,
+    pub result: ServiceResult,
+    /// None if the command never ran
+    pub exit_status: Option<ExitStatus>,
+    /// How long the main process ran
+    pub runtime: Option<Duration>,
+    /// None if CPU accounting is not available
+    pub cpu_usage: Option<Duration>,
+    /// The most memory used at once in bytes, None if memory accounting is not available
+    pub memory_peak: Option<u64>,
+}
+
+impl CommandResult {
+    /// Whether the service succeeded and the command exited with status 0.
+    pub fn success(&self) -> bool {
+        self.result == ServiceResult::Success && self.exit_status.is_some_and(|s| s.success())
+    }
+}
+
+/// The output a command wrote to its standard output and standard error, unless they were taken
+/// from the [`RunningCommand`] before waiting for it.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct CommandOutput {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+/// A command to run as a transient service with its standard input, output and error connected
+/// to the caller, like `systemd-run --wait --pipe` does. The command runs in the environment of
+/// the service manager, not of the caller, with all the sandboxing and resource control
+/// settings of a service.
+#[derive(Debug)]
+pub struct TransientCommand {
+    command: Vec<String>,
+    name: Option<String>,
+    description: Option<String>,
+    environment: Vec<String>,
+    working_directory: Option<String>,
+    stdin: Option<OwnedFd>,
+}
+
+impl TransientCommand {
+    /// Runs `command`, where the first element is the executable.
+    pub fn new(command: &[&str]) -> Self {
+        Self {
+            command: command.iter().map(ToString::to_string).collect(),
+            name: None,
+            description: None,
+            environment: Vec::new(),
+            working_directory: None,
+            stdin: None,
+        }
+    }
+
+    /// The name of the service without the suffix. A random "run-r<hex>" name is used if not
+    /// set, like systemd-run does.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Describes the service, the command line is used if not set.
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Sets an environment variable for the command, an assignment like "KEY=value".
+    pub fn with_environment(mut self, assignment: &str) -> Self {
+        self.environment.push(assignment.to_string());
+        self
+    }
+
+    /// The directory the command runs in, the root directory if not set.
+    pub fn with_working_directory(mut self, path: &str) -> Self {
+        self.working_directory = Some(path.to_string());
+        self
+    }
+
+    /// What the command reads from its standard input, e.g. a file or the reading end of a pipe.
+    /// /dev/null is used if not set.
+    pub fn with_stdin(mut self, stdin: impl Into<OwnedFd>) -> Self {
+        self.stdin = Some(stdin.into());
+        self
+    }
+
+    fn service_name(&self) -> Result<UnitName, SystemdError> {
+        let name = match &self.name {
+            Some(name) => name.clone(),
+            None => random_unit_prefix(),
+        };
+
+        UnitName::new(format!("{name}.service"))
+    }
+
+    /// The service, along with the reading ends of its standard output and standard error.
+    fn into_unit(self) -> Result<(TransientUnit, PipeReader, PipeReader), SystemdError> {
+        let name = self.service_name()?;
+        if self.command.is_empty() {
+            return Err(SystemdError::InvalidTransientUnit {
+                unit: name.to_string(),
+                reason: "no command to run",
+            });
+        }
+
+        let stdin = match self.stdin {
+            Some(stdin) => stdin,
+            None => File::open("/dev/null")?.into(),
+        };
+        let (stdout, stdout_writer) = io::pipe()?;
+        let (stderr, stderr_writer) = io::pipe()?;
+
+        let description = self.description.unwrap_or_else(|| self.command.join(" "));
+
+        let mut properties: Properties = vec![
+            ("Description", Value::from(description)),
+            ("ExecStart", exec_command(&self.command)),
+            // Keeps the unit around until its results are read, see RunningCommand::wait()
+            ("AddRef", Value::from(true)),
+            ("CollectMode", Value::from("inactive-or-failed")),
+            ("CPUAccounting", Value::from(true)),
+            ("MemoryAccounting", Value::from(true)),
+            ("StandardInputFileDescriptor", Value::from(Fd::from(stdin))),
+            (
+                "StandardOutputFileDescriptor",
+                Value::from(Fd::from(OwnedFd::from(stdout_writer))),
+            ),
+            (
+                "StandardErrorFileDescriptor",
+                Value::from(Fd::from(OwnedFd::from(stderr_writer))),
+            ),
+        ];
+        if !self.environment.is_empty() {
+            properties.push(("Environment", Value::from(self.environment)));
+        }
+        if let Some(path) = self.working_directory {
+            properties.push(("WorkingDirectory", Value::from(path)));
+        }
+
+        Ok((TransientUnit { name, properties }, stdout, stderr))
+    }
+}
+
+/// A unit name prefix that is unlikely to be taken, like the "run-r<hex>" names of systemd-run.
+fn random_unit_prefix() -> String {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_nanos() as u64)
+        .unwrap_or_default();
+    let unique = (u64::from(std::process::id()) << 32)
+        ^ nanos
+        ^ u64::from(COUNTER.fetch_add(1, Ordering::Relaxed));
+
+    format!("run-r{unique:016x}")
+}
+
+/// A command that was started as a transient service. Its output can be streamed by taking
+/// `stdout` and `stderr` and reading them while the command runs, otherwise waiting collects it.
+/// Dropping this without waiting leaves the unit loaded, and the signal subscription made to
+/// follow it in place, until the connection is closed.
+pub struct RunningCommand {
+    manager: ManagerProxy<'static>,
+    /// Whether starting the command subscribed to signals, which waiting undoes again
+    subscribed: bool,
+    unit_name: UnitName,
+    unit: UnitProxy<'static>,
+    service: ServiceProxy<'static>,
+    properties: PropertiesProxy<'static>,
+    /// The standard output of the command
+    pub stdout: Option<PipeReader>,
+    /// The standard error of the command
+    pub stderr: Option<PipeReader>,
+}
+
+impl RunningCommand {
+    /// The name of the transient service the command runs in.
+    pub fn unit_name(&self) -> &UnitName {
+        &self.unit_name
+    }
+
+    /// Waits for the command to finish and returns how it went. Output that was not taken from
+    /// `stdout` and `stderr` is discarded.
+    pub async fn wait(self) -> Result<CommandResult, SystemdError> {
+        Ok(self.finish(false).await?.0)
+    }
+
+    /// Waits for the command to finish like wait() does, along with all output that was not
+    /// taken from `stdout` and `stderr`.
+    pub async fn wait_with_output(self) -> Result<(CommandResult, CommandOutput), SystemdError> {
+        self.finish(true).await
+    }
+
+    /// Waits for the command to finish like wait() does, blocking the current thread.
+    pub fn wait_blocking(self) -> Result<CommandResult, SystemdError> {
+        async_io::block_on(self.wait())
+    }
+
+    /// Waits for the command to finish like wait_with_output() does, blocking the current thread.
+    pub fn wait_with_output_blocking(self) -> Result<(CommandResult, CommandOutput), SystemdError> {
+        async_io::block_on(self.wait_with_output())
+    }
+
+    async fn finish(mut self, keep: bool) -> Result<(CommandResult, CommandOutput), SystemdError> {
+        let read = |pipe: Option<PipeReader>| async move {
+            let mut output = Vec::new();
+            if let Some(pipe) = pipe {
+                // async-io only reads from types it knows to be safe, like files
+                let mut pipe = Async::new(File::from(OwnedFd::from(pipe)))?;
+                if keep {
+                    pipe.read_to_end(&mut output).await?;
+                } else {
+                    futures_lite::io::copy(&mut pipe, &mut futures_lite::io::sink()).await?;
+                }
+            }
+            Ok::<_, SystemdError>(output)
+        };
+
+        let stdout = read(self.stdout.take());
+        let stderr = read(self.stderr.take());
+        let (result, (stdout, stderr)) =
+            future::zip(self.wait_for_exit(), future::zip(stdout, stderr)).await;
+        let result = result?;
+
+        Ok((
+            result,
+            CommandOutput {
+                stdout: stdout?,
+                stderr: stderr?,
+            },
+        ))
+    }
+
+    async fn wait_for_exit(&self) -> Result<CommandResult, SystemdError> {
+        let result = self.wait_for_result().await;
+        let unsubscribed = unsubscribe_if_subscribed(&self.manager, self.subscribed).await;
+        let result = result?;
+        unsubscribed?;
+
+        Ok(result)
+    }
+
+    async fn wait_for_result(&self) -> Result<CommandResult, SystemdError> {
+        // Subscribed before the first check, so no change is missed however quickly the command
+        // finishes
+        let mut changes = self.properties.receive_properties_changed().await?;
+
+        loop {
+            let state = UnitActiveState::from(self.unit.active_state().await?);
+            let (job, _) = self.unit.job().await?;
+            if job == 0 && matches!(state, UnitActiveState::Inactive | UnitActiveState::Failed) {
+                break;
+            }
+
+            if changes.next().await.is_none() {
+                return Err(SystemdError::SignalStreamClosed);
+            }
+        }
+
+        let service = &self.service;
+        let started = service.exec_main_start_timestamp_monotonic().await?;
+        let exited = service.exec_main_exit_timestamp_monotonic().await?;
+        let known = |value: u64| (value != u64::MAX).then_some(value);
+
+        let result = CommandResult {
+            unit: self.unit_name.clone(),
+            result: service.result().await?.into(),
+            exit_status: ExitStatus::from_code(
+                service.exec_main_code().await?,
+                service.exec_main_status().await?,
+            ),
+            runtime: (started > 0 && exited >= started)
+                .then(|| Duration::from_micros(exited - started)),
+            cpu_usage: known(service.cpu_usage_nsec().await?).map(Duration::from_nanos),
+            // Older versions of systemd do not have this property
+            memory_peak: service.memory_peak().await.ok().and_then(known),
+        };
+
+        self.unit.unref().await?;
+        Ok(result)
+    }
+}
+
+/// Starts the command as a transient service.
+pub(crate) async fn run(
+    manager_proxy: &ManagerProxy<'_>,
+    command: TransientCommand,
+) -> Result<RunningCommand, SystemdError> {
+    let (unit, stdout, stderr) = command.into_unit()?;
+    let path = unit_object_path(unit.name.as_str());
+    let connection = manager_proxy.inner().connection();
+
+    let manager = uncached_manager_proxy(manager_proxy).await?;
+    let subscribed = subscribe(&manager).await?;
+
+    let properties = PropertiesProxy::builder(connection)
+        .destination("org.freedesktop.systemd1")?
+        .path(path.clone())?
+        .build()
+        .await?;
+    let unit_proxy = UnitProxy::builder(connection)
+        .path(path.clone())?
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await?;
+    let service = ServiceProxy::builder(connection)
+        .path(path)?
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await?;
+
+    if let Err(err) = transient::start(manager_proxy, &unit, &[], UnitMode::Fail).await {
+        // Nothing will wait for the command, so the subscription is dropped right away
+        let _ = unsubscribe_if_subscribed(&manager, subscribed).await;
+        return Err(err);
+    }
+
+    Ok(RunningCommand {
+        manager,
+        subscribed,
+        unit_name: unit.name,
+        unit: unit_proxy,
+        service,
+        properties,
+        stdout: Some(stdout),
+        stderr: Some(stderr),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_exit_statuses() {
+        assert_eq!(ExitStatus::from_code(1, 0), Some(ExitStatus::Exited(0)));
+        assert_eq!(ExitStatus::from_code(2, 9), Some(ExitStatus::Killed(9)));
+        assert_eq!(ExitStatus::from_code(0, 0), None);
+        assert!(ExitStatus::Exited(0).success());
+        assert!(!ExitStatus::Exited(1).success());
+        assert_eq!(
+            ServiceResult::from("exit-code".to_string()),
+            ServiceResult::ExitCode
+        );
+    }
+
+    #[test]
+    fn passes_standard_streams_as_file_descriptors() {
+        let command = TransientCommand::new(&["/bin/echo", "hello"]).with_name("greeting");
+        let (unit, _, _) = command.into_unit().expect("The command is valid");
+
+        assert_eq!(unit.name.as_str(), "greeting.service");
+        for name in [
+            "StandardInputFileDescriptor",
+            "StandardOutputFileDescriptor",
+            "StandardErrorFileDescriptor",
+        ] {
+            let value = unit.properties.iter().find(|(key, _)| *key == name);
+            assert!(matches!(value, Some((_, Value::Fd(_)))), "{name}");
+        }
+
+        assert!(random_unit_prefix().starts_with("run-r"));
+        assert!(TransientCommand::new(&[]).into_unit().is_err());
+    }
+}
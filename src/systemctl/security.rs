@@ -0,0 +1,956 @@
+//! An assessment of how well a service is sandboxed, modeled after `systemd-analyze security`.
+//! Each check has a weight, and a badness from 0 (fully restricted) to 10 (unrestricted). The
+//! overall exposure is the weighted average of the badness, from 0.0 to 10.0.
+
+use std::fmt::Display;
+
+use crate::{
+    ServiceProxy,
+    errors::SystemdError,
+    systemctl::unit_config::{
+        exec::{ExecSettings, ProtectHome, ProtectSystem},
+        parse_bool,
+    },
+};
+
+// NOTE: The capability numbers are from linux/capability.h
+
+const CAPABILITIES: [&str; 41] = [
+    "CAP_CHOWN",
+    "CAP_DAC_OVERRIDE",
+    "CAP_DAC_READ_SEARCH",
+    "CAP_FOWNER",
+    "CAP_FSETID",
+    "CAP_KILL",
+    "CAP_SETGID",
+    "CAP_SETUID",
+    "CAP_SETPCAP",
+    "CAP_LINUX_IMMUTABLE",
+    "CAP_NET_BIND_SERVICE",
+    "CAP_NET_BROADCAST",
+    "CAP_NET_ADMIN",
+    "CAP_NET_RAW",
+    "CAP_IPC_LOCK",
+    "CAP_IPC_OWNER",
+    "CAP_SYS_MODULE",
+    "CAP_SYS_RAWIO",
+    "CAP_SYS_CHROOT",
+    "CAP_SYS_PTRACE",
+    "CAP_SYS_PACCT",
+    "CAP_SYS_ADMIN",
+    "CAP_SYS_BOOT",
+    "CAP_SYS_NICE",
+    "CAP_SYS_RESOURCE",
+    "CAP_SYS_TIME",
+    "CAP_SYS_TTY_CONFIG",
+    "CAP_MKNOD",
+    "CAP_LEASE",
+    "CAP_AUDIT_WRITE",
+    "CAP_AUDIT_CONTROL",
+    "CAP_SETFCAP",
+    "CAP_MAC_OVERRIDE",
+    "CAP_MAC_ADMIN",
+    "CAP_SYSLOG",
+    "CAP_WAKE_ALARM",
+    "CAP_BLOCK_SUSPEND",
+    "CAP_AUDIT_READ",
+    "CAP_PERFMON",
+    "CAP_BPF",
+    "CAP_CHECKPOINT_RESTORE",
+];
+
+// NOTE: The flags are from linux/sched.h, the names from "man systemd.exec" under
+// RestrictNamespaces=
+
+const NAMESPACES: [(&str, u64); 7] = [
+    ("cgroup", 0x0200_0000),
+    ("ipc", 0x0800_0000),
+    ("net", 0x4000_0000),
+    ("mnt", 0x0002_0000),
+    ("pid", 0x2000_0000),
+    ("user", 0x1000_0000),
+    ("uts", 0x0400_0000),
+];
+
+/// Groups of capabilities that are checked together, with the weight of the check.
+const CAPABILITY_CHECKS: [(&[&str], u32); 12] = [
+    (&["CAP_SYS_ADMIN"], 1500),
+    (&["CAP_SYS_PTRACE"], 1500),
+    (&["CAP_SYS_MODULE"], 1500),
+    (&["CAP_SETUID", "CAP_SETGID", "CAP_SETPCAP"], 1500),
+    (
+        &[
+            "CAP_DAC_OVERRIDE",
+            "CAP_DAC_READ_SEARCH",
+            "CAP_FOWNER",
+            "CAP_FSETID",
+        ],
+        1500,
+    ),
+    (&["CAP_NET_ADMIN"], 1500),
+    (&["CAP_SYS_RAWIO"], 1000),
+    (&["CAP_SYS_TIME"], 1000),
+    (&["CAP_BPF", "CAP_PERFMON"], 1000),
+    (&["CAP_CHOWN", "CAP_MKNOD", "CAP_LINUX_IMMUTABLE"], 1000),
+    (&["CAP_KILL", "CAP_NET_RAW", "CAP_SYS_BOOT"], 500),
+    (
+        &["CAP_AUDIT_CONTROL", "CAP_MAC_ADMIN", "CAP_MAC_OVERRIDE"],
+        500,
+    ),
+];
+
+const ADDRESS_FAMILY_CHECKS: [(&[&str], u32); 4] = [
+    (&["AF_INET", "AF_INET6"], 1500),
+    (&["AF_UNIX"], 25),
+    (&["AF_NETLINK"], 200),
+    (&["AF_PACKET"], 1000),
+];
+
+/// The sandboxing settings of a service that the assessment is based on. They can be read from
+/// a running service, or from a parsed unit file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SandboxSettings {
+    /// The user the service runs as, None for root
+    pub user: Option<String>,
+    pub dynamic_user: bool,
+    pub protect_system: ProtectSystem,
+    pub protect_home: ProtectHome,
+    pub private_tmp: bool,
+    pub private_devices: bool,
+    pub private_network: bool,
+    pub private_users: bool,
+    pub no_new_privileges: bool,
+    pub protect_kernel_tunables: bool,
+    pub protect_kernel_modules: bool,
+    pub protect_kernel_logs: bool,
+    pub protect_control_groups: bool,
+    pub protect_clock: bool,
+    pub protect_hostname: bool,
+    /// "default", "invisible", "noaccess" or "ptraceable"
+    pub protect_proc: String,
+    /// "all" or "pid"
+    pub proc_subset: String,
+    pub restrict_realtime: bool,
+    pub restrict_suid_sgid: bool,
+    pub lock_personality: bool,
+    pub memory_deny_write_execute: bool,
+    pub remove_ipc: bool,
+    /// The capabilities that are kept, e.g. "CAP_NET_BIND_SERVICE"
+    pub capability_bounding_set: Vec<String>,
+    pub ambient_capabilities: Vec<String>,
+    /// The namespace types that may be created, e.g. "net"
+    pub allowed_namespaces: Vec<String>,
+    /// Whether the address families are an allow list, and the families, e.g. "AF_UNIX"
+    pub address_families: (bool, Vec<String>),
+    /// Whether the system calls are an allow list, and the system calls or groups
+    pub system_call_filter: (bool, Vec<String>),
+    pub system_call_architectures: Vec<String>,
+    /// "auto", "closed" or "strict"
+    pub device_policy: String,
+    /// Whether all IP traffic is denied, unless allowed with IPAddressAllow=
+    pub ip_address_deny_all: bool,
+}
+
+impl Default for SandboxSettings {
+    /// The settings of a service without any sandboxing.
+    fn default() -> Self {
+        Self {
+            user: None,
+            dynamic_user: false,
+            protect_system: ProtectSystem::No,
+            protect_home: ProtectHome::No,
+            private_tmp: false,
+            private_devices: false,
+            private_network: false,
+            private_users: false,
+            no_new_privileges: false,
+            protect_kernel_tunables: false,
+            protect_kernel_modules: false,
+            protect_kernel_logs: false,
+            protect_control_groups: false,
+            protect_clock: false,
+            protect_hostname: false,
+            protect_proc: "default".to_string(),
+            proc_subset: "all".to_string(),
+            restrict_realtime: false,
+            restrict_suid_sgid: false,
+            lock_personality: false,
+            memory_deny_write_execute: false,
+            remove_ipc: false,
+            capability_bounding_set: CAPABILITIES.iter().map(ToString::to_string).collect(),
+            ambient_capabilities: Vec::new(),
+            allowed_namespaces: NAMESPACES
+                .iter()
+                .map(|(name, _)| name.to_string())
+                .collect(),
+            address_families: (false, Vec::new()),
+            system_call_filter: (false, Vec::new()),
+            system_call_architectures: Vec::new(),
+            device_policy: "auto".to_string(),
+            ip_address_deny_all: false,
+        }
+    }
+}
+
+impl SandboxSettings {
+    /// Reads the settings from the [Service] section of a unit file, applying the defaults
+    /// systemd uses for settings that are not set.
+    pub fn from_exec_settings(exec: &ExecSettings) -> Self {
+        let mut settings = Self {
+            user: exec.user.clone().filter(|user| !is_root(user)),
+            dynamic_user: exec.dynamic_user.unwrap_or_default(),
+            protect_system: exec.protect_system.clone().unwrap_or(ProtectSystem::No),
+            protect_home: exec.protect_home.clone().unwrap_or(ProtectHome::No),
+            private_tmp: exec.private_tmp.unwrap_or_default(),
+            private_devices: exec.private_devices.unwrap_or_default(),
+            private_network: exec.private_network.unwrap_or_default(),
+            private_users: exec.private_users.unwrap_or_default(),
+            no_new_privileges: exec.no_new_privileges.unwrap_or_default(),
+            protect_kernel_tunables: exec.protect_kernel_tunables.unwrap_or_default(),
+            protect_kernel_modules: exec.protect_kernel_modules.unwrap_or_default(),
+            protect_kernel_logs: exec.protect_kernel_logs.unwrap_or_default(),
+            protect_control_groups: exec.protect_control_groups.unwrap_or_default(),
+            protect_clock: exec.protect_clock.unwrap_or_default(),
+            protect_hostname: exec.protect_hostname.unwrap_or_default(),
+            restrict_realtime: exec.restrict_realtime.unwrap_or_default(),
+            restrict_suid_sgid: exec.restrict_suid_sgid.unwrap_or_default(),
+            lock_personality: exec.lock_personality.unwrap_or_default(),
+            memory_deny_write_execute: exec.memory_deny_write_execute.unwrap_or_default(),
+            remove_ipc: exec.remove_ipc.unwrap_or_default(),
+            system_call_architectures: exec.system_call_architectures.clone(),
+            ip_address_deny_all: exec
+                .ip_address_deny
+                .iter()
+                .any(|address| matches!(address.as_str(), "any" | "0.0.0.0/0" | "::/0")),
+            ..Self::default()
+        };
+
+        if let Some(protect_proc) = &exec.protect_proc {
+            settings.protect_proc = protect_proc.clone();
+        }
+        if let Some(proc_subset) = &exec.proc_subset {
+            settings.proc_subset = proc_subset.clone();
+        }
+        if let Some(device_policy) = &exec.device_policy {
+            settings.device_policy = device_policy.clone();
+        }
+
+        // DynamicUser= implies a number of other settings, see "man systemd.exec"
+        if settings.dynamic_user {
+            settings.remove_ipc = true;
+            settings.private_tmp = true;
+            settings.restrict_suid_sgid = true;
+            settings.no_new_privileges = true;
+            if settings.protect_system == ProtectSystem::No {
+                settings.protect_system = ProtectSystem::Strict;
+            }
+            if settings.protect_home == ProtectHome::No {
+                settings.protect_home = ProtectHome::ReadOnly;
+            }
+        }
+
+        settings.capability_bounding_set = apply_capabilities(
+            &exec.capability_bounding_set,
+            settings.capability_bounding_set,
+        );
+        settings.ambient_capabilities =
+            apply_capabilities(&exec.ambient_capabilities, settings.ambient_capabilities);
+        if let Some(namespaces) = &exec.restrict_namespaces {
+            settings.allowed_namespaces = allowed_namespaces(namespaces);
+        }
+        settings.address_families = apply_filter(&exec.restrict_address_families);
+        settings.system_call_filter = apply_filter(&exec.system_call_filter);
+
+        settings
+    }
+
+    /// Reads the settings of a loaded service from the manager.
+    pub(crate) async fn from_proxy(proxy: &ServiceProxy<'_>) -> Result<Self, SystemdError> {
+        let user = proxy.user().await?;
+
+        Ok(Self {
+            user: Some(user).filter(|user| !user.is_empty() && !is_root(user)),
+            dynamic_user: proxy.dynamic_user().await?,
+            protect_system: proxy.protect_system().await?.into(),
+            protect_home: proxy.protect_home().await?.into(),
+            private_tmp: proxy.private_tmp().await?,
+            private_devices: proxy.private_devices().await?,
+            private_network: proxy.private_network().await?,
+            private_users: proxy.private_users().await?,
+            no_new_privileges: proxy.no_new_privileges().await?,
+            protect_kernel_tunables: proxy.protect_kernel_tunables().await?,
+            protect_kernel_modules: proxy.protect_kernel_modules().await?,
+            protect_kernel_logs: proxy.protect_kernel_logs().await?,
+            protect_control_groups: proxy.protect_control_groups().await?,
+            protect_clock: proxy.protect_clock().await?,
+            protect_hostname: proxy.protect_hostname().await?,
+            protect_proc: proxy.protect_proc().await?,
+            proc_subset: proxy.proc_subset().await?,
+            restrict_realtime: proxy.restrict_realtime().await?,
+            restrict_suid_sgid: proxy.restrict_suid_sgid().await?,
+            lock_personality: proxy.lock_personality().await?,
+            memory_deny_write_execute: proxy.memory_deny_write_execute().await?,
+            remove_ipc: proxy.remove_ipc().await?,
+            capability_bounding_set: capabilities_from_mask(proxy.capability_bounding_set().await?),
+            ambient_capabilities: capabilities_from_mask(proxy.ambient_capabilities().await?),
+            allowed_namespaces: namespaces_from_mask(proxy.restrict_namespaces().await?),
+            address_families: proxy.restrict_address_families().await?,
+            system_call_filter: proxy.system_call_filter().await?,
+            system_call_architectures: proxy.system_call_architectures().await?,
+            device_policy: proxy.device_policy().await?,
+            ip_address_deny_all: proxy
+                .ip_address_deny()
+                .await?
+                .iter()
+                .any(|(_, _, prefix_length)| *prefix_length == 0),
+        })
+    }
+
+    fn runs_as_root(&self) -> bool {
+        self.user.is_none() && !self.dynamic_user
+    }
+}
+
+/// How exposed a service is overall, as `systemd-analyze security` names it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum ExposureLevel {
+    Perfect,
+    Safe,
+    Ok,
+    Medium,
+    Exposed,
+    Unsafe,
+    Dangerous,
+}
+
+impl ExposureLevel {
+    /// The level for an exposure from 0.0 to 10.0.
+    pub fn from_exposure(exposure: f64) -> Self {
+        match exposure {
+            e if e >= 10.0 => ExposureLevel::Dangerous,
+            e if e >= 9.0 => ExposureLevel::Unsafe,
+            e if e >= 7.5 => ExposureLevel::Exposed,
+            e if e >= 5.0 => ExposureLevel::Medium,
+            e if e >= 1.0 => ExposureLevel::Ok,
+            e if e >= 0.1 => ExposureLevel::Safe,
+            _ => ExposureLevel::Perfect,
+        }
+    }
+}
+
+impl Display for ExposureLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            ExposureLevel::Perfect => "PERFECT",
+            ExposureLevel::Safe => "SAFE",
+            ExposureLevel::Ok => "OK",
+            ExposureLevel::Medium => "MEDIUM",
+            ExposureLevel::Exposed => "EXPOSED",
+            ExposureLevel::Unsafe => "UNSAFE",
+            ExposureLevel::Dangerous => "DANGEROUS",
+        };
+        f.write_str(value)
+    }
+}
+
+/// The outcome of a single check.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecurityFinding {
+    /// The setting that is checked, e.g. "PrivateTmp=" or "CapabilityBoundingSet=~CAP_SYS_ADMIN"
+    pub setting: String,
+    /// What the current value of the setting means
+    pub description: String,
+    /// How much the check counts towards the overall exposure
+    pub weight: u32,
+    /// From 0 if the service is fully restricted, to 10 if it is not restricted at all
+    pub badness: u8,
+}
+
+impl SecurityFinding {
+    /// Whether the service is fully restricted in this respect.
+    pub fn passed(&self) -> bool {
+        self.badness == 0
+    }
+}
+
+/// The sandboxing assessment of a service, like `systemd-analyze security` shows it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecurityReport {
+    pub unit: String,
+    /// All checks, the ones that count the most towards the exposure first
+    pub findings: Vec<SecurityFinding>,
+    /// From 0.0 for a fully sandboxed service, to 10.0 for one without any sandboxing
+    pub exposure: f64,
+    pub level: ExposureLevel,
+}
+
+impl SecurityReport {
+    /// Assesses the settings of the service.
+    pub fn new(unit: &str, settings: &SandboxSettings) -> Self {
+        let mut findings = checks(settings);
+        findings.sort_by_key(|finding| {
+            (
+                std::cmp::Reverse(finding.weight * finding.badness as u32),
+                finding.setting.clone(),
+            )
+        });
+
+        let total_weight: u64 = findings.iter().map(|f| f.weight as u64).sum();
+        let total_badness: u64 = findings
+            .iter()
+            .map(|f| f.weight as u64 * f.badness as u64)
+            .sum();
+        let exposure = if total_weight == 0 {
+            0.0
+        } else {
+            // Rounded to one decimal, the way it is displayed
+            (total_badness as f64 / total_weight as f64 * 10.0).round() / 10.0
+        };
+
+        Self {
+            unit: unit.to_string(),
+            findings,
+            exposure,
+            level: ExposureLevel::from_exposure(exposure),
+        }
+    }
+
+    /// The checks that did not pass.
+    pub fn issues(&self) -> impl Iterator<Item = &SecurityFinding> {
+        self.findings.iter().filter(|finding| !finding.passed())
+    }
+}
+
+fn checks(settings: &SandboxSettings) -> Vec<SecurityFinding> {
+    let finding = |setting: &str, weight: u32, badness: u8, description: &str| SecurityFinding {
+        setting: setting.to_string(),
+        description: description.to_string(),
+        weight,
+        badness,
+    };
+    let flag = |setting: &str, weight: u32, enabled: bool, good: &str, bad: &str| {
+        if enabled {
+            finding(setting, weight, 0, good)
+        } else {
+            finding(setting, weight, 10, bad)
+        }
+    };
+
+    let mut findings = vec![
+        match (settings.dynamic_user, settings.runs_as_root()) {
+            (true, _) => finding(
+                "User=/DynamicUser=",
+                2000,
+                0,
+                "Service runs under a transient non-root user identity",
+            ),
+            (false, false) => finding(
+                "User=/DynamicUser=",
+                2000,
+                0,
+                "Service runs under a static non-root user identity",
+            ),
+            (false, true) => finding("User=/DynamicUser=", 2000, 10, "Service runs as root user"),
+        },
+        match settings.protect_system {
+            ProtectSystem::Strict => finding(
+                "ProtectSystem=",
+                1000,
+                0,
+                "Service has strict read-only access to the OS file hierarchy",
+            ),
+            ProtectSystem::Full => finding(
+                "ProtectSystem=",
+                1000,
+                3,
+                "Service has very limited write access to the OS file hierarchy",
+            ),
+            ProtectSystem::Yes => finding(
+                "ProtectSystem=",
+                1000,
+                5,
+                "Service has limited write access to the OS file hierarchy",
+            ),
+            _ => finding(
+                "ProtectSystem=",
+                1000,
+                10,
+                "Service has full access to the OS file hierarchy",
+            ),
+        },
+        match settings.protect_home {
+            ProtectHome::Yes => finding(
+                "ProtectHome=",
+                1000,
+                0,
+                "Service has no access to home directories",
+            ),
+            ProtectHome::Tmpfs => finding(
+                "ProtectHome=",
+                1000,
+                1,
+                "Service sees empty home directories",
+            ),
+            ProtectHome::ReadOnly => finding(
+                "ProtectHome=",
+                1000,
+                5,
+                "Service has read-only access to home directories",
+            ),
+            _ => finding(
+                "ProtectHome=",
+                1000,
+                10,
+                "Service has full access to home directories",
+            ),
+        },
+        flag(
+            "PrivateTmp=",
+            1000,
+            settings.private_tmp,
+            "Service has no access to other software's temporary files",
+            "Service has access to other software's temporary files",
+        ),
+        flag(
+            "PrivateDevices=",
+            1000,
+            settings.private_devices,
+            "Service has no access to hardware devices",
+            "Service potentially has access to hardware devices",
+        ),
+        flag(
+            "PrivateNetwork=",
+            2500,
+            settings.private_network,
+            "Service has no access to the host's network",
+            "Service has access to the host's network",
+        ),
+        flag(
+            "PrivateUsers=",
+            1500,
+            settings.private_users,
+            "Service does not have access to other users",
+            "Service has access to other users",
+        ),
+        flag(
+            "NoNewPrivileges=",
+            1000,
+            settings.no_new_privileges,
+            "Service processes cannot acquire new privileges",
+            "Service processes may acquire new privileges",
+        ),
+        flag(
+            "ProtectKernelTunables=",
+            1000,
+            settings.protect_kernel_tunables,
+            "Service cannot alter kernel tunables (/proc/sys, …)",
+            "Service may alter kernel tunables",
+        ),
+        flag(
+            "ProtectKernelModules=",
+            1000,
+            settings.protect_kernel_modules,
+            "Service cannot load or read kernel modules",
+            "Service may load or read kernel modules",
+        ),
+        flag(
+            "ProtectKernelLogs=",
+            1000,
+            settings.protect_kernel_logs,
+            "Service cannot read from or write to the kernel log ring buffer",
+            "Service may read from or write to the kernel log ring buffer",
+        ),
+        flag(
+            "ProtectControlGroups=",
+            1000,
+            settings.protect_control_groups,
+            "Service cannot modify the control group file system",
+            "Service may modify the control group file system",
+        ),
+        flag(
+            "ProtectClock=",
+            1000,
+            settings.protect_clock,
+            "Service cannot write to the hardware clock or system clock",
+            "Service may write to the hardware clock or system clock",
+        ),
+        flag(
+            "ProtectHostname=",
+            50,
+            settings.protect_hostname,
+            "Service cannot change system host/domainname",
+            "Service may change system host/domainname",
+        ),
+        flag(
+            "ProtectProc=",
+            1000,
+            settings.protect_proc != "default",
+            "Service has restricted access to process tree (/proc hidepid=)",
+            "Service has full access to process tree (/proc hidepid=)",
+        ),
+        flag(
+            "ProcSubset=",
+            10,
+            settings.proc_subset == "pid",
+            "Service has no access to non-process /proc files (/proc subset=)",
+            "Service has full access to non-process /proc files (/proc subset=)",
+        ),
+        flag(
+            "RestrictRealtime=",
+            500,
+            settings.restrict_realtime,
+            "Service realtime scheduling access is restricted",
+            "Service may acquire realtime scheduling",
+        ),
+        flag(
+            "RestrictSUIDSGID=",
+            1000,
+            settings.restrict_suid_sgid,
+            "SUID/SGID file creation by service is restricted",
+            "Service may create SUID/SGID files",
+        ),
+        flag(
+            "LockPersonality=",
+            100,
+            settings.lock_personality,
+            "Service cannot change ABI personality",
+            "Service may change ABI personality",
+        ),
+        flag(
+            "MemoryDenyWriteExecute=",
+            100,
+            settings.memory_deny_write_execute,
+            "Service cannot create writable executable memory mappings",
+            "Service may create writable executable memory mappings",
+        ),
+        flag(
+            "RemoveIPC=",
+            100,
+            settings.remove_ipc || settings.runs_as_root(),
+            "Service user cannot leave SysV IPC objects around",
+            "Service user may leave SysV IPC objects around",
+        ),
+        flag(
+            "DevicePolicy=",
+            1000,
+            settings.private_devices || settings.device_policy != "auto",
+            "Service has a device access policy",
+            "Service has no device access policy",
+        ),
+        flag(
+            "IPAddressDeny=",
+            1000,
+            settings.private_network || settings.ip_address_deny_all,
+            "Service defines an IP address allow list",
+            "Service does not define an IP address allow list",
+        ),
+        flag(
+            "SystemCallArchitectures=",
+            1000,
+            settings
+                .system_call_architectures
+                .iter()
+                .any(|arch| arch == "native"),
+            "Service may execute system calls only with native ABI",
+            "Service may execute system calls with all ABIs",
+        ),
+        flag(
+            "AmbientCapabilities=",
+            500,
+            settings.ambient_capabilities.is_empty(),
+            "Service process does not receive ambient capabilities",
+            "Service process receives ambient capabilities",
+        ),
+        match &settings.system_call_filter {
+            (true, calls) if !calls.is_empty() => finding(
+                "SystemCallFilter=",
+                1000,
+                0,
+                "System calls are restricted to an allow list",
+            ),
+            (false, calls) if !calls.is_empty() => finding(
+                "SystemCallFilter=",
+                1000,
+                5,
+                "Some system calls are denied, all others are allowed",
+            ),
+            _ => finding(
+                "SystemCallFilter=",
+                1000,
+                10,
+                "Service does not filter system calls",
+            ),
+        },
+    ];
+
+    for (capabilities, weight) in CAPABILITY_CHECKS {
+        let setting = format!("CapabilityBoundingSet=~{}", capabilities.join(" "));
+        let kept = capabilities.iter().any(|cap| {
+            settings
+                .capability_bounding_set
+                .iter()
+                .any(|kept| kept == cap)
+        });
+
+        findings.push(if kept {
+            finding(&setting, weight, 10, "Service may use these capabilities")
+        } else {
+            finding(&setting, weight, 0, "Service cannot use these capabilities")
+        });
+    }
+
+    for (name, _) in NAMESPACES {
+        let weight = if name == "user" { 1500 } else { 500 };
+        let setting = format!("RestrictNamespaces=~{name}");
+        findings.push(flag(
+            &setting,
+            weight,
+            !settings
+                .allowed_namespaces
+                .iter()
+                .any(|allowed| allowed == name),
+            "Service cannot create namespaces of this type",
+            "Service may create namespaces of this type",
+        ));
+    }
+
+    let (allow_list, families) = &settings.address_families;
+    for (checked, weight) in ADDRESS_FAMILY_CHECKS {
+        let allowed = checked
+            .iter()
+            .any(|family| families.iter().any(|f| f == family) == *allow_list);
+        let setting = format!("RestrictAddressFamilies=~{}", checked.join("|"));
+        findings.push(flag(
+            &setting,
+            weight,
+            !allowed || (checked.contains(&"AF_INET") && settings.private_network),
+            "Service cannot allocate sockets of these address families",
+            "Service may allocate sockets of these address families",
+        ));
+    }
+
+    findings
+}
+
+fn is_root(user: &str) -> bool {
+    user == "root" || user == "0"
+}
+
+fn capabilities_from_mask(mask: u64) -> Vec<String> {
+    CAPABILITIES
+        .iter()
+        .enumerate()
+        .filter(|(number, _)| mask & (1 << number) != 0)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+fn namespaces_from_mask(mask: u64) -> Vec<String> {
+    NAMESPACES
+        .iter()
+        .filter(|(_, flag)| mask & flag != 0)
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// Applies the assignments to a capability set like CapabilityBoundingSet=. The first assignment
+/// replaces `unset`, later ones add to the set or, prefixed with "~", remove from it. An empty
+/// assignment resets to no capabilities and a bare "~" to all of them.
+fn apply_capabilities(assignments: &[String], unset: Vec<String>) -> Vec<String> {
+    let all = || CAPABILITIES.iter().map(ToString::to_string);
+    let mut kept: Option<Vec<String>> = None;
+
+    for assignment in assignments {
+        let (inverted, names) = match assignment.strip_prefix('~') {
+            Some(names) => (true, names),
+            None => (false, assignment.as_str()),
+        };
+        let names: Vec<&str> = names.split_whitespace().collect();
+
+        if names.is_empty() {
+            kept = Some(if inverted {
+                all().collect()
+            } else {
+                Vec::new()
+            });
+        } else if inverted {
+            kept = Some(
+                kept.unwrap_or_else(|| all().collect())
+                    .into_iter()
+                    .filter(|cap| !names.contains(&cap.as_str()))
+                    .collect(),
+            );
+        } else {
+            let kept = kept.get_or_insert_with(Vec::new);
+            for name in names {
+                if !kept.iter().any(|cap| cap == name) {
+                    kept.push(name.to_string());
+                }
+            }
+        }
+    }
+
+    kept.unwrap_or(unset)
+}
+
+/// The namespace types allowed by a RestrictNamespaces= value.
+fn allowed_namespaces(value: &str) -> Vec<String> {
+    let all = NAMESPACES.iter().map(|(name, _)| name.to_string());
+
+    if let Some(restricted) = parse_bool(value) {
+        return if restricted {
+            Vec::new()
+        } else {
+            all.collect()
+        };
+    }
+
+    match value.strip_prefix('~') {
+        Some(denied) => {
+            let denied: Vec<&str> = denied.split_whitespace().collect();
+            all.filter(|name| !denied.contains(&name.as_str()))
+                .collect()
+        }
+        None => value.split_whitespace().map(ToString::to_string).collect(),
+    }
+}
+
+/// Combines the assignments of a list setting that can be an allow list or, prefixed with "~",
+/// a deny list, like RestrictAddressFamilies= and SystemCallFilter=. The first assignment
+/// decides which kind of list it is. "none" resets it to an empty allow list.
+fn apply_filter(assignments: &[String]) -> (bool, Vec<String>) {
+    let mut allow_list = None;
+    let mut entries: Vec<String> = Vec::new();
+
+    for assignment in assignments {
+        if assignment == "none" {
+            allow_list = Some(true);
+            entries.clear();
+            continue;
+        }
+
+        let (inverted, names) = match assignment.strip_prefix('~') {
+            Some(names) => (true, names),
+            None => (false, assignment.as_str()),
+        };
+        let allow = *allow_list.get_or_insert(!inverted);
+
+        for name in names.split_whitespace() {
+            if allow != inverted {
+                entries.push(name.to_string());
+            } else {
+                entries.retain(|entry| entry != name);
+            }
+        }
+    }
+
+    (allow_list.unwrap_or(false), entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemctl::unit_config::service::ServiceUnitConfig;
+
+    #[test]
+    fn unsandboxed_services_are_unsafe() {
+        let report = SecurityReport::new("foo.service", &SandboxSettings::default());
+
+        assert!(report.exposure >= 9.0, "exposure was {}", report.exposure);
+        assert!(report.level >= ExposureLevel::Unsafe);
+        let passed: Vec<&str> = report
+            .findings
+            .iter()
+            .filter(|finding| finding.passed())
+            .map(|finding| finding.setting.as_str())
+            .collect();
+        assert_eq!(passed, vec!["AmbientCapabilities=", "RemoveIPC="]);
+    }
+
+    #[test]
+    fn assesses_parsed_unit_files() {
+        let config = ServiceUnitConfig::parse(
+            "[Service]
+             ExecStart=/usr/bin/foo
+             DynamicUser=yes
+             PrivateDevices=yes
+             PrivateNetwork=yes
+             ProtectHome=yes
+             CapabilityBoundingSet=
+             CapabilityBoundingSet=CAP_NET_BIND_SERVICE
+             RestrictNamespaces=yes
+             RestrictAddressFamilies=AF_UNIX
+             SystemCallFilter=@system-service
+             SystemCallArchitectures=native
+",
+        )
+        .expect("This is a valid unit file");
+
+        let settings = SandboxSettings::from_exec_settings(&config.service.exec);
+        let report = SecurityReport::new("foo.service", &settings);
+
+        assert_eq!(settings.protect_system, ProtectSystem::Strict);
+        assert_eq!(
+            settings.capability_bounding_set,
+            vec!["CAP_NET_BIND_SERVICE"]
+        );
+        assert!(settings.allowed_namespaces.is_empty());
+        assert!(report.exposure < 5.0, "exposure was {}", report.exposure);
+        assert!(
+            report
+                .issues()
+                .any(|finding| finding.setting == "ProtectKernelTunables=")
+        );
+        assert!(
+            !report
+                .issues()
+                .any(|finding| finding.setting.contains("CAP_SYS_ADMIN"))
+        );
+    }
+
+    #[test]
+    fn combines_filter_assignments() {
+        let assignments =
+            |values: &[&str]| values.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        assert_eq!(
+            apply_filter(&assignments(&["AF_UNIX AF_INET", "~AF_INET"])),
+            (true, vec!["AF_UNIX".to_string()])
+        );
+        assert_eq!(
+            apply_filter(&assignments(&["~@mount", "@mount"])),
+            (false, vec![])
+        );
+        assert_eq!(
+            apply_filter(&assignments(&["none", "AF_UNIX"])),
+            (true, vec!["AF_UNIX".to_string()])
+        );
+        assert_eq!(
+            apply_capabilities(&assignments(&["~CAP_SYS_ADMIN"]), Vec::new()).len(),
+            CAPABILITIES.len() - 1
+        );
+    }
+
+    #[test]
+    fn resets_capability_sets() {
+        let settings = |contents: &str| {
+            let config =
+                ServiceUnitConfig::parse(&format!("[Service]\nExecStart=/bin/foo\n{contents}"))
+                    .expect("This is a valid unit file");
+            SandboxSettings::from_exec_settings(&config.service.exec)
+        };
+
+        let empty = settings("CapabilityBoundingSet=CAP_CHOWN\nCapabilityBoundingSet=\n");
+        assert!(empty.capability_bounding_set.is_empty());
+
+        let full = settings("CapabilityBoundingSet=CAP_CHOWN\nCapabilityBoundingSet=~\n");
+        assert_eq!(full.capability_bounding_set.len(), CAPABILITIES.len());
+
+        let added = settings("CapabilityBoundingSet=\nCapabilityBoundingSet=CAP_CHOWN\n");
+        assert_eq!(added.capability_bounding_set, vec!["CAP_CHOWN"]);
+
+        let ambient =
+            settings("AmbientCapabilities=CAP_CHOWN CAP_KILL\nAmbientCapabilities=~CAP_KILL\n");
+        assert_eq!(ambient.ambient_capabilities, vec!["CAP_CHOWN"]);
+    }
+}
@@ -0,0 +1,198 @@
+use std::{fmt::Display, time::Duration};
+
+use async_io::Timer;
+use futures_lite::{StreamExt, future};
+
+use crate::{
+    ManagerProxy,
+    errors::SystemdError,
+    systemctl::{
+        subscribe, uncached_manager_proxy,
+        unit::{Unit, UnitActiveState},
+        unsubscribe_if_subscribed,
+    },
+};
+
+// NOTE: These docs are from the man page of systemctl, under is-system-running
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SystemState {
+    /// Early bootup, before basic.target is reached or the maintenance state entered.
+    Initializing,
+    /// Late bootup, before the job queue becomes idle for the first time, or one of the rescue
+    /// targets are reached.
+    Starting,
+    /// The system is fully operational.
+    Running,
+    /// The system is operational but one or more units failed.
+    Degraded,
+    /// The rescue or emergency target is active.
+    Maintenance,
+    /// The manager is shutting down.
+    Stopping,
+    /// The manager is not running. Specifically, this is the operational state if an incompatible
+    /// program is running as system manager (PID 1).
+    Offline,
+    /// The operational state could not be determined, due to lack of resources or another error
+    /// cause.
+    Unknown,
+    Other(String),
+}
+
+impl SystemState {
+    /// Whether the manager is still booting up, i.e. "initializing" or "starting". Any other state
+    /// is one that `systemctl is-system-running --wait` would return on.
+    pub fn is_booting(&self) -> bool {
+        matches!(self, SystemState::Initializing | SystemState::Starting)
+    }
+}
+
+impl From<String> for SystemState {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "initializing" => SystemState::Initializing,
+            "starting" => SystemState::Starting,
+            "running" => SystemState::Running,
+            "degraded" => SystemState::Degraded,
+            "maintenance" => SystemState::Maintenance,
+            "stopping" => SystemState::Stopping,
+            "offline" => SystemState::Offline,
+            "unknown" => SystemState::Unknown,
+            _ => SystemState::Other(value),
+        }
+    }
+}
+
+impl Display for SystemState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            SystemState::Initializing => "initializing",
+            SystemState::Starting => "starting",
+            SystemState::Running => "running",
+            SystemState::Degraded => "degraded",
+            SystemState::Maintenance => "maintenance",
+            SystemState::Stopping => "stopping",
+            SystemState::Offline => "offline",
+            SystemState::Unknown => "unknown",
+            SystemState::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SystemRunningState {
+    /// The state the manager settled in once it finished booting
+    pub state: SystemState,
+    /// The units in the failed state, only populated when the state is degraded
+    pub failed_units: Vec<Unit>,
+}
+
+/// Reads the SystemState property without going through the property cache. The manager does not
+/// emit PropertiesChanged for every state transition, so a cached value may be stale.
+pub(crate) async fn current_system_state(
+    proxy: &ManagerProxy<'_>,
+) -> Result<SystemState, SystemdError> {
+    let uncached = uncached_manager_proxy(proxy).await?;
+    Ok(uncached.system_state().await?.into())
+}
+
+/// Shared implementation of `systemctl is-system-running --wait`, used by both the async and the
+/// blocking interfaces.
+pub(crate) async fn wait_until_running(
+    proxy: &ManagerProxy<'_>,
+    timeout: Duration,
+) -> Result<SystemRunningState, SystemdError> {
+    let uncached = uncached_manager_proxy(proxy).await?;
+
+    // A subscription made here is dropped again however waiting ends, so the manager does not
+    // keep sending signals. One that already existed is left for whoever made it.
+    let subscribed = subscribe(proxy).await?;
+    let waited = wait_until_booted(proxy, &uncached, timeout).await;
+    let unsubscribed = unsubscribe_if_subscribed(proxy, subscribed).await;
+    let state = waited?;
+    unsubscribed?;
+
+    let failed_units = if let SystemState::Degraded = state {
+        proxy
+            .list_units_filtered(&[&UnitActiveState::Failed.to_string()])
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Ok(SystemRunningState {
+        state,
+        failed_units,
+    })
+}
+
+/// Waits for the manager to leave the booting states, reading the state through the uncached
+/// proxy whenever one of the signals of the subscribed proxy arrives.
+async fn wait_until_booted(
+    proxy: &ManagerProxy<'_>,
+    uncached: &ManagerProxy<'_>,
+    timeout: Duration,
+) -> Result<SystemState, SystemdError> {
+    let mut state_changed = proxy.receive_system_state_changed().await;
+    let mut startup_finished = proxy.receive_startup_finished().await?;
+
+    let wait = async {
+        loop {
+            let state: SystemState = uncached.system_state().await?.into();
+            if !state.is_booting() {
+                return Ok(state);
+            }
+
+            let woken = future::or(async { state_changed.next().await.map(|_| ()) }, async {
+                startup_finished.next().await.map(|_| ())
+            })
+            .await;
+
+            if woken.is_none() {
+                return Err(SystemdError::SignalStreamClosed);
+            }
+        }
+    };
+
+    let timer = async {
+        Timer::after(timeout).await;
+        Err(SystemdError::Timeout)
+    };
+
+    future::or(wait, timer).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn system_state_round_trips_through_strings() {
+        for raw in [
+            "initializing",
+            "starting",
+            "running",
+            "degraded",
+            "maintenance",
+            "stopping",
+            "offline",
+            "unknown",
+        ] {
+            let state: SystemState = raw.to_string().into();
+            assert!(!matches!(state, SystemState::Other(_)));
+            assert_eq!(state.to_string(), raw);
+        }
+    }
+
+    #[test]
+    fn only_initializing_and_starting_are_booting() {
+        assert!(SystemState::Initializing.is_booting());
+        assert!(SystemState::Starting.is_booting());
+        assert!(!SystemState::Running.is_booting());
+        assert!(!SystemState::Degraded.is_booting());
+    }
+}
@@ -1,24 +1,55 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Duration,
+};
+
 use zbus::{Connection, zvariant::OwnedObjectPath};
 
 use crate::{
-    ManagerProxy,
+    AutomountProxy, JobProxy, ManagerProxy, MountProxy, ServiceProxy, UnitProxy,
     errors::SystemdError,
+    login1::{inhibitor::Inhibitor, manager::ManagerProxy as LoginManagerProxy},
     systemctl::{
+        clean::CleanMask,
         connection_level::ConnectionLevel,
-        job::Job,
-        unit::{Unit, UnitEnablementResponse, UnitMode},
-        unit_file::{EnablementStatus, UnitFile},
+        delta::{self, Delta},
+        environment,
+        freezer::{self, FreezeGuard, FreezerState},
+        job::{EnqueuedTransaction, Job, JobDependencies, JobType},
+        logging::{LogLevel, LogTarget, ShowStatus},
+        lookup_paths::{LookupPaths, UnitFileLookup},
+        offline::OfflineSystemCtl,
+        power::{self, PowerAction, PowerCapability, ScheduledShutdown},
+        preset::Presets,
+        process::{self, CgroupNode, ProcessOwner, UnitProcess},
+        reset_failed::ResetFailedSelector,
+        run::{self, RunningCommand, TransientCommand},
+        security::{SandboxSettings, SecurityReport},
+        system_state::{self, SystemRunningState, SystemState},
+        transient::{self, TransientMount, TransientTimer},
+        uncached_manager_proxy,
+        unit::{Unit, UnitActiveState, UnitEnablementResponse, UnitMode},
+        unit_config,
+        unit_file::{EnablementStatus, UnitFile, UnitFileQuery},
+        unit_name::{UnitName, UnitType, unit_name_from_object_path},
+        unit_query::{UnitListMethod, UnitQuery},
+        watchdog::{self, Watchdog},
     },
 };
 
 pub struct SystemCtlBuilder {
     connection_level: ConnectionLevel,
+    freezer_timeout: Duration,
 }
 
 impl SystemCtlBuilder {
     pub fn new() -> Self {
         Self {
             connection_level: ConnectionLevel::UserLevel,
+            freezer_timeout: freezer::DEFAULT_FREEZER_TIMEOUT,
         }
     }
 
@@ -27,12 +58,27 @@ impl SystemCtlBuilder {
         self
     }
 
+    /// How long freeze() and thaw() wait for the processes of the unit to be frozen or running
+    /// again, 90 seconds by default.
+    pub fn with_freezer_timeout(mut self, timeout: Duration) -> Self {
+        self.freezer_timeout = timeout;
+        self
+    }
+
+    /// Manages the unit files below the given root directory instead of connecting to the
+    /// service manager, like `systemctl --root=` does.
+    pub fn init_offline(self, root: impl AsRef<Path>) -> OfflineSystemCtl {
+        OfflineSystemCtl::new(root.as_ref(), self.connection_level)
+    }
+
     pub async fn init<'a>(self) -> Result<SystemCtl<'a>, SystemdError> {
         let connection = self.connection_level.get_connection().await?;
         let proxy = ManagerProxy::new(&connection).await?;
         Ok(SystemCtl {
             manager_proxy: proxy,
             connection_level: self.connection_level,
+            freezer_timeout: self.freezer_timeout,
+            presets: OnceLock::new(),
         })
     }
 }
@@ -45,8 +91,9 @@ impl Default for SystemCtlBuilder {
 
 pub struct SystemCtl<'a> {
     manager_proxy: ManagerProxy<'a>,
-    #[allow(unused)]
     connection_level: ConnectionLevel,
+    freezer_timeout: Duration,
+    presets: OnceLock<Presets>,
 }
 
 impl<'a> SystemCtl<'a> {
@@ -67,17 +114,59 @@ impl<'a> SystemCtl<'a> {
     /// "ignore-dependencies", it will start a unit but ignore all its dependencies. If "ignore-requirements",
     /// it will start a unit but only ignore the requirement dependencies. It is not recommended to make use of
     /// the latter two options. On completion, this method returns the newly created job object.
+    /// In "isolate" mode, the unit is checked to allow isolation first, see isolate().
     pub async fn start_unit(
         &self,
         name: &str,
         mode: UnitMode,
     ) -> Result<OwnedObjectPath, SystemdError> {
+        self.check_allow_isolate(name, &mode).await?;
+
         Ok(self
             .get_manager_proxy()
             .start_unit(name, &mode.to_string())
             .await?)
     }
 
+    /// Starts the unit and stops all units that are not dependencies of it, equivalent to
+    /// `systemctl isolate`. This is usually done with targets, e.g. to switch to rescue.target.
+    /// Only units with AllowIsolate=yes may be isolated, which is checked before the job is
+    /// enqueued so a descriptive error can be returned.
+    pub async fn isolate(&self, target: &str) -> Result<OwnedObjectPath, SystemdError> {
+        self.start_unit(target, UnitMode::Isolate).await
+    }
+
+    /// Retrieves the name of the unit to which default.target is aliased, equivalent to
+    /// `systemctl get-default`.
+    pub async fn default_target(&self) -> Result<UnitName, SystemdError> {
+        UnitName::new(self.get_manager_proxy().get_default_target().await?)
+    }
+
+    /// Changes default.target to be an alias of the given target, equivalent to
+    /// `systemctl set-default`. If force is set, an existing default.target symlink is replaced
+    /// even if it does not point to a unit file of its own.
+    pub async fn set_default_target(
+        &self,
+        target: &UnitName,
+        force: bool,
+    ) -> Result<UnitEnablementResponse, SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .set_default_target(target.as_str(), force)
+            .await?
+            .into())
+    }
+
+    /// Makes sure the unit may be isolated if the job is enqueued in "isolate" mode. Every method
+    /// that can enqueue a start job goes through here, so the check cannot be bypassed.
+    async fn check_allow_isolate(&self, name: &str, mode: &UnitMode) -> Result<(), SystemdError> {
+        if *mode != UnitMode::Isolate || self.get_unit_proxy(name).await?.allow_isolate().await? {
+            Ok(())
+        } else {
+            Err(SystemdError::IsolateNotAllowed(name.to_string()))
+        }
+    }
+
     /// Similar to StartUnit() but stops the specified unit rather than starting it. Note that the
     /// "isolate" mode is invalid for this method.
     pub async fn stop_unit(
@@ -95,14 +184,19 @@ impl<'a> SystemCtl<'a> {
             .await?)
     }
 
-    /// RestartUnit method, takes in the mode, i.e. same as start unit, I quote:
-    /// The mode needs to be one of "replace", "fail", "isolate", "ignore-dependencies", or
-    /// "ignore-requirements". returns the object path of the restarted unit.
+    /// RestartUnit method, takes in the mode, i.e. same as start unit except that systemd only
+    /// accepts "isolate" for start jobs, so it is invalid here. The mode needs to be one of
+    /// "replace", "fail", "ignore-dependencies", or "ignore-requirements". returns the object
+    /// path of the restarted unit.
     pub async fn restart_unit(
         &self,
         name: &str,
         mode: UnitMode,
     ) -> Result<OwnedObjectPath, SystemdError> {
+        if let UnitMode::Isolate = mode {
+            return Err(SystemdError::IsolateModeUnavailable);
+        };
+
         Ok(self
             .get_manager_proxy()
             .restart_unit(name, &mode.to_string())
@@ -112,29 +206,64 @@ impl<'a> SystemCtl<'a> {
     /// ReloadUnit(), RestartUnit(), TryRestartUnit(), ReloadOrRestartUnit(), or ReloadOrTryRestartUnit() may be used to restart and/or reload a unit. These methods take similar arguments as StartUnit(). Reloading is done only if the
     /// unit is already running and fails otherwise. If a service is restarted that isn't running, it will be started unless the "Try" flavor is used in which case a service that isn't running is not affected by the restart. The
     /// "ReloadOrRestart" flavors attempt a reload if the unit supports it and use a restart otherwise.
+    /// Note that the "isolate" mode is invalid for this method.
     pub async fn reload_unit(
         &self,
         name: &str,
         mode: UnitMode,
     ) -> Result<OwnedObjectPath, SystemdError> {
+        if let UnitMode::Isolate = mode {
+            return Err(SystemdError::IsolateModeUnavailable);
+        };
+
         Ok(self
             .get_manager_proxy()
             .reload_unit(name, &mode.to_string())
             .await?)
     }
 
-    /// A combination or the reload and restart methods.
+    /// A combination or the reload and restart methods. Note that the "isolate" mode is invalid
+    /// for this method.
     pub async fn reload_or_restart_unit(
         &self,
         name: &str,
         mode: UnitMode,
     ) -> Result<OwnedObjectPath, SystemdError> {
+        if let UnitMode::Isolate = mode {
+            return Err(SystemdError::IsolateModeUnavailable);
+        };
+
         Ok(self
             .get_manager_proxy()
             .reload_or_restart_unit(name, &mode.to_string())
             .await?)
     }
 
+    /// Enqueues a job of any type for the unit, the generic form of StartUnit(), StopUnit(),
+    /// RestartUnit() and friends. Besides the types that have their own methods, this allows e.g.
+    /// verify-active jobs, which check that a unit is active without starting it. The mode is the
+    /// same as for StartUnit(), where "isolate" is only valid for start jobs. Returns the new job
+    /// together with all other jobs that were enqueued as part of the same transaction, which is
+    /// what `systemctl --show-transaction` prints.
+    pub async fn enqueue_job(
+        &self,
+        name: &str,
+        job_type: JobType,
+        mode: UnitMode,
+    ) -> Result<EnqueuedTransaction, SystemdError> {
+        if mode == UnitMode::Isolate && job_type != JobType::Start {
+            return Err(SystemdError::IsolateModeUnavailable);
+        }
+
+        self.check_allow_isolate(name, &mode).await?;
+
+        Ok(self
+            .get_manager_proxy()
+            .enqueue_unit_job(name, &job_type.to_string(), &mode.to_string())
+            .await?
+            .into())
+    }
+
     /// May be used to enable one or more units in the system (by creating symlinks to them in /etc/ or /run/). It takes a list of unit files to enable (either just file names or full
     /// absolute paths if the unit files are residing outside the usual unit search paths) and two booleans: the first controls whether the unit shall be enabled for runtime only (true, /run/), or
     /// persistently (false, /etc/). The second one controls whether symlinks pointing to other units shall be replaced if necessary. This method returns one boolean and an array of the changes made. The
@@ -230,6 +359,32 @@ impl<'a> SystemCtl<'a> {
             .into())
     }
 
+    /// MaskUnitFiles() masks unit files and UnmaskUnitFiles() unmasks them again.
+    pub async fn unmask_units(
+        &self,
+        names: &[&str],
+        runtime_only: bool,
+    ) -> Result<UnitEnablementResponse, SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .unmask_unit_files(names, runtime_only)
+            .await?
+            .into())
+    }
+
+    /// Similar to unmask units except only unmasks a single unit file
+    pub async fn unmask_unit(
+        &self,
+        name: &str,
+        runtime_only: bool,
+    ) -> Result<UnitEnablementResponse, SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .unmask_unit_files(&[name], runtime_only)
+            .await?
+            .into())
+    }
+
     /// Returns an array of all currently loaded units. Note that units may be known by multiple names at the same name, and hence there might be more unit names loaded than actual units behind them.
     pub async fn list_units(&self) -> Result<Vec<Unit>, SystemdError> {
         Ok(self
@@ -241,25 +396,216 @@ impl<'a> SystemCtl<'a> {
             .collect())
     }
 
+    /// Lists the units matching the query, like `systemctl list-units` does with its --state=,
+    /// --type=, --all and pattern arguments. The query is answered by ListUnitsByNames(),
+    /// ListUnitsByPatterns() or ListUnitsFiltered() where possible, so only the units of interest
+    /// are sent over the bus.
+    pub async fn list_units_by_query(&self, query: &UnitQuery) -> Result<Vec<Unit>, SystemdError> {
+        let proxy = self.get_manager_proxy();
+
+        let units = match query.method() {
+            UnitListMethod::All => proxy.list_units().await?,
+            UnitListMethod::Filtered { states } => {
+                let states: Vec<&str> = states.iter().map(String::as_str).collect();
+                proxy.list_units_filtered(&states).await?
+            }
+            UnitListMethod::ByPatterns { states, patterns } => {
+                let states: Vec<&str> = states.iter().map(String::as_str).collect();
+                let patterns: Vec<&str> = patterns.iter().map(String::as_str).collect();
+                proxy.list_units_by_patterns(&states, &patterns).await?
+            }
+            UnitListMethod::ByNames { names } => {
+                let names: Vec<&str> = names.iter().map(String::as_str).collect();
+                proxy.list_units_by_names(&names).await?
+            }
+        };
+
+        Ok(query.filter(units.into_iter().map(Unit::from)))
+    }
+
     /// May be used to get the unit object path for a unit name. It takes the unit name and returns
     /// the object path. If a unit has not been loaded yet by this name this method will fail.
     pub async fn get_unit(&self, name: &str) -> Result<OwnedObjectPath, SystemdError> {
         Ok(self.get_manager_proxy().get_unit(name).await?)
     }
 
+    /// Returns a proxy for the unit object, giving access to everything in
+    /// org.freedesktop.systemd1.Unit. The unit is loaded from disk if it is not loaded yet.
+    pub async fn get_unit_proxy(&self, name: &str) -> Result<UnitProxy<'static>, SystemdError> {
+        let path = self.get_manager_proxy().load_unit(name).await?;
+
+        Ok(
+            UnitProxy::builder(self.get_manager_proxy().inner().connection())
+                .path(path)?
+                .build()
+                .await?,
+        )
+    }
+
+    /// Returns the freezer state of the unit, i.e. whether its processes are currently frozen.
+    pub async fn freezer_state(&self, name: &str) -> Result<FreezerState, SystemdError> {
+        Ok(self
+            .get_unit_proxy(name)
+            .await?
+            .freezer_state()
+            .await?
+            .into())
+    }
+
+    /// Freezes all processes of the unit using the cgroup freezer, equivalent to
+    /// `systemctl freeze`. Waits until the FreezerState of the unit is "frozen". Frozen processes
+    /// are not scheduled until the unit is thawed again.
+    pub async fn freeze(&self, name: &str) -> Result<(), SystemdError> {
+        let unit = self.get_unit_proxy(name).await?;
+        freezer::freeze(self.get_manager_proxy(), &unit, name, self.freezer_timeout).await
+    }
+
+    /// Thaws the processes of a frozen unit, equivalent to `systemctl thaw`. Waits until the
+    /// FreezerState of the unit is "running" again.
+    pub async fn thaw(&self, name: &str) -> Result<(), SystemdError> {
+        let unit = self.get_unit_proxy(name).await?;
+        freezer::thaw(self.get_manager_proxy(), &unit, name, self.freezer_timeout).await
+    }
+
+    /// Freezes the unit like freeze() does, and returns a guard that thaws the unit again once it
+    /// goes out of scope. Use this to pause a workload for the duration of some maintenance.
+    pub async fn freeze_scoped(&self, name: &str) -> Result<FreezeGuard<'a>, SystemdError> {
+        let unit = self.get_unit_proxy(name).await?;
+        freezer::freeze(self.get_manager_proxy(), &unit, name, self.freezer_timeout).await?;
+
+        Ok(FreezeGuard::new(
+            self.manager_proxy.clone(),
+            unit,
+            name,
+            self.freezer_timeout,
+        ))
+    }
+
+    /// Removes the configuration, state, cache, log or runtime data of the unit, or its file
+    /// descriptor store, selected by the mask. Equivalent to `systemctl clean --what=`. Like
+    /// systemctl, this first checks the CanClean property of the unit, failing if any of the
+    /// requested resource types cannot be cleaned, and fails if the unit is not inactive, since the
+    /// resources of a running unit are in use.
+    pub async fn clean_unit(&self, name: &str, mask: CleanMask) -> Result<(), SystemdError> {
+        let unit = self.get_unit_proxy(name).await?;
+
+        let can_clean = CleanMask::from_names(&unit.can_clean().await?);
+        if !can_clean.contains(mask) {
+            return Err(SystemdError::CleanNotSupported {
+                unit: name.to_string(),
+                unsupported: mask.difference(can_clean).to_string(),
+            });
+        }
+
+        let active_state = UnitActiveState::from(unit.active_state().await?);
+        if !matches!(
+            active_state,
+            UnitActiveState::Inactive | UnitActiveState::Failed
+        ) {
+            return Err(SystemdError::UnitNotInactive {
+                unit: name.to_string(),
+                active_state: active_state.to_string(),
+            });
+        }
+
+        Ok(self
+            .get_manager_proxy()
+            .clean_unit(name, &mask.names())
+            .await?)
+    }
+
+    /// Resets the "failed" state of the selected units, equivalent to `systemctl reset-failed`.
+    /// Only units that are actually failed are reset, except for ResetFailedSelector::All, where
+    /// systemd resets every unit, including its start rate limit counter. Returns the units that
+    /// were in the failed state before they were reset, so the caller knows which failures were
+    /// cleared.
+    pub async fn reset_failed(
+        &self,
+        selector: &ResetFailedSelector,
+    ) -> Result<Vec<Unit>, SystemdError> {
+        let proxy = self.get_manager_proxy();
+        let failed = UnitActiveState::Failed.to_string();
+
+        let failed_units: Vec<Unit> = match selector {
+            ResetFailedSelector::All => {
+                let failed_units = proxy
+                    .list_units_filtered(&[&failed])
+                    .await?
+                    .into_iter()
+                    .map(Unit::from)
+                    .collect();
+                proxy.reset_failed().await?;
+                return Ok(failed_units);
+            }
+            ResetFailedSelector::Units(names) => {
+                let names: Vec<&str> = names.iter().map(UnitName::as_str).collect();
+                proxy
+                    .list_units_by_names(&names)
+                    .await?
+                    .into_iter()
+                    .map(Unit::from)
+                    .collect()
+            }
+            ResetFailedSelector::Query(query) => self.list_units_by_query(query).await?,
+        };
+
+        let failed_units: Vec<Unit> = failed_units
+            .into_iter()
+            .filter(|unit| unit.active_state == UnitActiveState::Failed)
+            .collect();
+
+        for unit in &failed_units {
+            proxy.reset_failed_unit(&unit.name).await?;
+        }
+
+        Ok(failed_units)
+    }
+
     /// Returns an array of unit names and their enablement status. Note that ListUnit() returns a list of units currently loaded into memory, while ListUnitFiles() returns a list of unit
     /// files that were found on disk. Note that while most units are read directly from a unit file with the same name, some units are not backed by files and some files (templates) cannot directly be loaded
     /// as units but need to be instantiated instead.
     pub async fn list_unit_files(&self) -> Result<Vec<UnitFile>, SystemdError> {
+        let presets = self.presets();
+
         Ok(self
             .get_manager_proxy()
             .list_unit_files()
             .await?
             .into_iter()
-            .map(Into::into)
+            .map(|unit_file| UnitFile::from(unit_file).with_preset(presets))
             .collect())
     }
 
+    /// Lists the unit files matching the query, like `systemctl list-unit-files` does with its
+    /// --state=, --type= and pattern arguments, using ListUnitFilesByPatterns(). The preset policy
+    /// for each unit file is looked up from the local preset files.
+    pub async fn list_unit_files_by_query(
+        &self,
+        query: &UnitFileQuery,
+    ) -> Result<Vec<UnitFile>, SystemdError> {
+        let states = query.states();
+        let patterns = query.patterns();
+        let states: Vec<&str> = states.iter().map(String::as_str).collect();
+        let patterns: Vec<&str> = patterns.iter().map(String::as_str).collect();
+        let presets = self.presets();
+
+        Ok(self
+            .get_manager_proxy()
+            .list_unit_files_by_patterns(&states, &patterns)
+            .await?
+            .into_iter()
+            .map(|unit_file| UnitFile::from(unit_file).with_preset(presets))
+            .filter(|unit_file| query.matches(unit_file))
+            .collect())
+    }
+
+    /// The preset policy of the manager this is connected to, read from the local preset files
+    /// the first time it is needed.
+    fn presets(&self) -> &Presets {
+        self.presets
+            .get_or_init(|| Presets::load(&self.connection_level, Path::new("/")))
+    }
+
     /// Returns the current enablement status of a specific unit file. The format of the string
     /// here is simply name.service, in other words, if you retrieved the unit files via
     /// list_unit_files, you may want to strip the prefix on the path to get the service name.
@@ -271,6 +617,71 @@ impl<'a> SystemCtl<'a> {
             .into())
     }
 
+    /// Returns the paths of all symlinks pointing to the unit file, either the persistent ones in
+    /// /etc/ or, if runtime is set, the ones in /run/. This explains why a unit file is in the
+    /// enablement state it is in, e.g. which .wants/ directories make it enabled or where it is
+    /// masked.
+    pub async fn unit_file_links(
+        &self,
+        name: &str,
+        runtime: bool,
+    ) -> Result<Vec<String>, SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .get_unit_file_links(name, runtime)
+            .await?)
+    }
+
+    /// Returns all processes of the unit, grouped by the control group they are in, like the
+    /// process tree shown by `systemctl status`. The root of the tree is the control group of the
+    /// unit itself.
+    pub async fn unit_processes(&self, name: &str) -> Result<CgroupNode, SystemdError> {
+        let processes = self
+            .get_manager_proxy()
+            .get_unit_processes(name)
+            .await?
+            .into_iter()
+            .map(UnitProcess::from)
+            .collect();
+
+        Ok(CgroupNode::from_processes(processes))
+    }
+
+    /// Finds the unit a process belongs to. GetUnitByPID() is asked first, and if the manager does
+    /// not know the process, the control group of the process (and then each of its parents) is
+    /// looked up with GetUnitByControlGroup().
+    pub async fn unit_for_pid(&self, pid: u32) -> Result<ProcessOwner, SystemdError> {
+        let proxy = self.get_manager_proxy();
+
+        let object_path = match proxy.get_unit_by_pid(pid).await {
+            Ok(object_path) => object_path,
+            Err(err) if process::is_unit_not_found(&err) => {
+                let cgroup = process::cgroup_of_pid(pid).ok_or(err)?;
+                let mut found = None;
+                for ancestor in process::cgroup_ancestors(&cgroup) {
+                    match proxy.get_unit_by_control_group(&ancestor).await {
+                        Ok(object_path) => {
+                            found = Some(object_path);
+                            break;
+                        }
+                        Err(err) if process::is_unit_not_found(&err) => continue,
+                        Err(err) => return Err(err.into()),
+                    }
+                }
+                found.ok_or(SystemdError::NoUnitForProcess(pid))?
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let unit_name =
+            unit_name_from_object_path(&object_path).ok_or(SystemdError::NoUnitForProcess(pid))?;
+
+        Ok(ProcessOwner {
+            unit_name,
+            object_path,
+        })
+    }
+
     /// Returns an array with all currently queued jobs. Returns an array consisting of structures with the following elements:
     /// •   The numeric job id
     /// •   The primary unit name for this job
@@ -288,10 +699,442 @@ impl<'a> SystemCtl<'a> {
             .collect())
     }
 
+    /// Returns a proxy for the job object with the given id, giving access to everything in
+    /// org.freedesktop.systemd1.Job.
+    pub async fn get_job_proxy(&self, id: u32) -> Result<JobProxy<'static>, SystemdError> {
+        let path = self.get_manager_proxy().get_job(id).await?;
+
+        Ok(
+            JobProxy::builder(self.get_manager_proxy().inner().connection())
+                .path(path)?
+                .build()
+                .await?,
+        )
+    }
+
+    /// Cancels a specific job identified by its numeric ID. This operation is also available in
+    /// the Cancel() method of Job objects and exists primarily to reduce the necessary round trips
+    /// to execute this operation. Note that this will not have any effect on jobs whose execution
+    /// has already begun.
+    pub async fn cancel_job(&self, id: u32) -> Result<(), SystemdError> {
+        Ok(self.get_manager_proxy().cancel_job(id).await?)
+    }
+
+    /// Flushes the job queue, removing all jobs that are still queued. Note that this does not
+    /// have any effect on jobs whose execution has already begun, it only flushes jobs that are
+    /// queued and have not yet begun execution.
+    pub async fn clear_jobs(&self) -> Result<(), SystemdError> {
+        Ok(self.get_manager_proxy().clear_jobs().await?)
+    }
+
+    /// Returns the jobs the job with the given id is waiting for, and the jobs that are waiting for
+    /// it in turn. Useful to find out why a job is stuck in the waiting state.
+    pub async fn job_dependencies(&self, id: u32) -> Result<JobDependencies, SystemdError> {
+        let proxy = self.get_manager_proxy();
+
+        Ok(JobDependencies::new(
+            proxy.get_job_after(id).await?,
+            proxy.get_job_before(id).await?,
+        ))
+    }
+
+    /// Returns whether logind allows the power action, equivalent to `loginctl can-reboot` etc.
+    pub async fn can_power_action(
+        &self,
+        action: PowerAction,
+    ) -> Result<PowerCapability, SystemdError> {
+        power::capability(&self.login_manager_proxy().await?, action).await
+    }
+
+    /// Lists all currently active inhibitor locks, equivalent to `systemd-inhibit --list`.
+    pub async fn list_inhibitors(&self) -> Result<Vec<Inhibitor>, SystemdError> {
+        power::list_inhibitors(&self.login_manager_proxy().await?).await
+    }
+
+    /// Reboots, powers off, suspends etc. the system through logind, equivalent to
+    /// `systemctl reboot` and friends. Like systemctl, this fails without doing anything if an
+    /// inhibitor lock in block mode is held for the action, listing the locks in the error, or if
+    /// logind reports the action as unavailable. Shutdown units are run as usual.
+    pub async fn power_action(&self, action: PowerAction) -> Result<(), SystemdError> {
+        power::request(
+            &self.login_manager_proxy().await?,
+            self.get_manager_proxy(),
+            action,
+        )
+        .await
+    }
+
+    /// Asks the service manager for an immediate reboot, power-off, halt or kexec, equivalent to
+    /// `systemctl reboot --force`. Inhibitor locks are ignored and no units are stopped first, so
+    /// only use this when power_action() is not an option. Sleep actions are not supported by the
+    /// service manager, and fail.
+    pub async fn force_power_action(&self, action: PowerAction) -> Result<(), SystemdError> {
+        let proxy = self.get_manager_proxy();
+
+        match action {
+            PowerAction::Reboot => proxy.reboot().await?,
+            PowerAction::PowerOff => proxy.power_off().await?,
+            PowerAction::Halt => proxy.halt().await?,
+            PowerAction::Kexec => proxy.kexec().await?,
+            _ => return Err(SystemdError::PowerActionUnsupported(action.to_string())),
+        }
+
+        Ok(())
+    }
+
+    /// Schedules a shutdown through logind, equivalent to `shutdown` with a time argument. Only
+    /// reboot, power-off, halt and kexec can be scheduled. Replaces any shutdown that was
+    /// scheduled before.
+    pub async fn schedule_shutdown(
+        &self,
+        shutdown: &ScheduledShutdown,
+    ) -> Result<(), SystemdError> {
+        power::schedule(&self.login_manager_proxy().await?, shutdown).await
+    }
+
+    /// Returns the currently scheduled shutdown, if any.
+    pub async fn scheduled_shutdown(&self) -> Result<Option<ScheduledShutdown>, SystemdError> {
+        power::scheduled(&self.login_manager_proxy().await?).await
+    }
+
+    /// Cancels the scheduled shutdown, equivalent to `shutdown -c`. Returns whether a shutdown
+    /// was scheduled.
+    pub async fn cancel_scheduled_shutdown(&self) -> Result<bool, SystemdError> {
+        Ok(self
+            .login_manager_proxy()
+            .await?
+            .cancel_scheduled_shutdown()
+            .await?)
+    }
+
+    /// Transitions to a new root directory, see SwitchRoot() on the manager. This is intended to
+    /// be used by initial RAM disks. The init binary is searched for if none is given.
+    pub async fn switch_root(
+        &self,
+        new_root: &str,
+        init: Option<&str>,
+    ) -> Result<(), SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .switch_root(new_root, init.unwrap_or_default())
+            .await?)
+    }
+
+    async fn login_manager_proxy(&self) -> Result<LoginManagerProxy<'static>, SystemdError> {
+        let connection = (self.connection_level == ConnectionLevel::SystemLevel)
+            .then(|| self.get_manager_proxy().inner().connection());
+
+        power::login_manager_proxy(connection).await
+    }
+
+    /// Returns the environment block of the manager, which is passed to all spawned processes.
+    /// Equivalent to `systemctl show-environment`.
+    pub async fn environment(&self) -> Result<BTreeMap<String, String>, SystemdError> {
+        environment::current_environment(self.get_manager_proxy()).await
+    }
+
+    /// Sets environment variables in the environment block of the manager, overriding variables
+    /// that are set already. Takes assignments of the form "NAME=value", equivalent to
+    /// `systemctl set-environment`. Nothing is changed if any of the assignments is invalid.
+    pub async fn set_env(&self, assignments: &[&str]) -> Result<(), SystemdError> {
+        environment::check_assignments(assignments)?;
+        Ok(self
+            .get_manager_proxy()
+            .set_environment(assignments)
+            .await?)
+    }
+
+    /// Unsets environment variables in the environment block of the manager, equivalent to
+    /// `systemctl unset-environment`. If an assignment of the form "NAME=value" is given, the
+    /// variable is only unset if it has that value. Variables that are not set are ignored.
+    pub async fn unset_env(&self, names: &[&str]) -> Result<(), SystemdError> {
+        environment::check_unset_names(names)?;
+        Ok(self.get_manager_proxy().unset_environment(names).await?)
+    }
+
+    /// Copies variables from the environment of the current process into the environment block
+    /// of the manager, equivalent to `systemctl import-environment`. Names may be globs, e.g.
+    /// "XDG_*". Variables that are not set in the current process are skipped. Returns the
+    /// assignments that were imported.
+    pub async fn import_env(&self, names: &[&str]) -> Result<Vec<String>, SystemdError> {
+        let assignments = environment::select_for_import(names, std::env::vars_os())?;
+        let assignments_ref: Vec<&str> = assignments.iter().map(String::as_str).collect();
+
+        if !assignments.is_empty() {
+            self.get_manager_proxy()
+                .set_environment(&assignments_ref)
+                .await?;
+        }
+
+        Ok(assignments)
+    }
+
+    /// Returns the maximum log level of the manager, equivalent to `systemctl log-level`.
+    pub async fn log_level(&self) -> Result<LogLevel, SystemdError> {
+        let proxy = uncached_manager_proxy(self.get_manager_proxy()).await?;
+        Ok(proxy.log_level().await?.into())
+    }
+
+    /// Changes the maximum log level of the manager until the next reboot, equivalent to
+    /// `systemctl log-level <level>`.
+    pub async fn set_log_level(&self, level: &LogLevel) -> Result<(), SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .set_log_level(&level.to_string())
+            .await?)
+    }
+
+    /// Returns where the manager sends its log messages, equivalent to
+    /// `systemd-analyze log-target`.
+    pub async fn log_target(&self) -> Result<LogTarget, SystemdError> {
+        let proxy = uncached_manager_proxy(self.get_manager_proxy()).await?;
+        Ok(proxy.log_target().await?.into())
+    }
+
+    /// Changes where the manager sends its log messages until the next reboot, equivalent to
+    /// `systemd-analyze log-target <target>`.
+    pub async fn set_log_target(&self, target: &LogTarget) -> Result<(), SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .set_log_target(&target.to_string())
+            .await?)
+    }
+
+    /// Returns whether the manager currently shows status messages on the console, i.e.
+    /// `ShowStatus::Yes` or `ShowStatus::No`.
+    pub async fn show_status(&self) -> Result<ShowStatus, SystemdError> {
+        let proxy = uncached_manager_proxy(self.get_manager_proxy()).await?;
+        Ok(proxy.show_status().await?.into())
+    }
+
+    /// Changes whether the manager shows status messages on the console, overriding ShowStatus=
+    /// in systemd-system.conf until the next reboot.
+    pub async fn set_show_status(&self, mode: &ShowStatus) -> Result<(), SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .set_show_status(&mode.to_string())
+            .await?)
+    }
+
+    /// Returns whether service watchdogs are enabled, equivalent to
+    /// `systemd-analyze service-watchdogs`. If disabled, WatchdogSec= of services is ignored.
+    pub async fn service_watchdogs(&self) -> Result<bool, SystemdError> {
+        let proxy = uncached_manager_proxy(self.get_manager_proxy()).await?;
+        Ok(proxy.service_watchdogs().await?)
+    }
+
+    /// Enables or disables service watchdogs, equivalent to
+    /// `systemd-analyze service-watchdogs <bool>`.
+    pub async fn set_service_watchdogs(&self, enabled: bool) -> Result<(), SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .set_service_watchdogs(enabled)
+            .await?)
+    }
+
+    /// Returns the timeout of the hardware watchdog. Zero means the watchdog is disabled, and
+    /// Duration::MAX that it never elapses.
+    pub async fn watchdog(&self, watchdog: Watchdog) -> Result<Duration, SystemdError> {
+        let proxy = uncached_manager_proxy(self.get_manager_proxy()).await?;
+
+        let usec = match watchdog {
+            Watchdog::Runtime => proxy.runtime_watchdog_usec().await?,
+            Watchdog::Reboot => proxy.reboot_watchdog_usec().await?,
+            Watchdog::Kexec => proxy.kexec_watchdog_usec().await?,
+        };
+
+        Ok(watchdog::duration_from_usec(usec))
+    }
+
+    /// Changes the timeout of the hardware watchdog until the next reboot. Pass Duration::ZERO
+    /// to disable it.
+    pub async fn set_watchdog(
+        &self,
+        watchdog: Watchdog,
+        timeout: Duration,
+    ) -> Result<(), SystemdError> {
+        let proxy = self.get_manager_proxy();
+        let usec = watchdog::usec_from_duration(timeout);
+
+        match watchdog {
+            Watchdog::Runtime => proxy.set_runtime_watchdog_usec(usec).await?,
+            Watchdog::Reboot => proxy.set_reboot_watchdog_usec(usec).await?,
+            Watchdog::Kexec => proxy.set_kexec_watchdog_usec(usec).await?,
+        }
+
+        Ok(())
+    }
+
+    /// Returns the unit search path the manager actually uses, highest priority first.
+    pub async fn unit_path(&self) -> Result<Vec<PathBuf>, SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .unit_path()
+            .await?
+            .into_iter()
+            .map(PathBuf::from)
+            .collect())
+    }
+
+    /// Computes the unit search path for the connection level locally, which allows looking up
+    /// unit files, their drop-ins and the files they override without asking the manager.
+    pub fn lookup_paths(&self) -> LookupPaths {
+        LookupPaths::for_connection_level(&self.connection_level)
+    }
+
+    /// Finds the unit file, aliases and drop-ins of a unit in the search path, like the manager
+    /// does when loading it. Returns None if there is no unit file for it.
+    pub fn find_unit_file(&self, name: &str) -> Result<Option<UnitFileLookup>, SystemdError> {
+        Ok(self.lookup_paths().resolve(&UnitName::new(name)?))
+    }
+
+    /// Lists all unit files and drop-ins in the search path that mask, override, redirect or
+    /// extend unit files further down the search path, with diffs for the ones that differ in
+    /// content. Equivalent to `systemd-delta` for the unit directories.
+    pub fn delta(&self) -> Vec<Delta> {
+        delta::delta(&self.lookup_paths())
+    }
+
+    /// Writes a unit file, e.g. a [`ServiceUnitConfig`], to /etc/systemd/system/, or to
+    /// /run/systemd/system/ if runtime is set, and the user level counterparts. Returns the path
+    /// written. The manager only picks it up after a reload, which is left to the caller so that
+    /// several unit files can be written first.
+    ///
+    /// [`ServiceUnitConfig`]: crate::ServiceUnitConfig
+    pub fn write_unit_file(
+        &self,
+        name: &str,
+        contents: &impl Display,
+        runtime: bool,
+    ) -> Result<PathBuf, SystemdError> {
+        unit_config::write_unit_file(&self.lookup_paths(), name, &contents.to_string(), runtime)
+    }
+
+    /// Returns a proxy for the service object, giving access to everything in
+    /// org.freedesktop.systemd1.Service. The unit is loaded from disk if it is not loaded yet.
+    pub async fn get_service_proxy(
+        &self,
+        name: &str,
+    ) -> Result<ServiceProxy<'static>, SystemdError> {
+        if UnitName::new(name)?.unit_type() != UnitType::Service {
+            return Err(SystemdError::NotAService(name.to_string()));
+        }
+        let path = self.get_manager_proxy().load_unit(name).await?;
+
+        Ok(
+            ServiceProxy::builder(self.get_manager_proxy().inner().connection())
+                .path(path)?
+                .build()
+                .await?,
+        )
+    }
+
+    /// Assesses how well the service is sandboxed, based on the settings the manager has loaded
+    /// for it. Equivalent to `systemd-analyze security`.
+    pub async fn security_report(&self, name: &str) -> Result<SecurityReport, SystemdError> {
+        let proxy = self.get_service_proxy(name).await?;
+        let settings = SandboxSettings::from_proxy(&proxy).await?;
+
+        Ok(SecurityReport::new(name, &settings))
+    }
+
+    /// Creates a transient timer together with the service it activates and starts the timer,
+    /// equivalent to `systemd-run --on-calendar=... --unit=name command`. Nothing is written to
+    /// disk, both units are gone once the timer is stopped or the system reboots. Returns the job
+    /// that starts the timer.
+    pub async fn start_transient_timer(
+        &self,
+        timer: &TransientTimer,
+    ) -> Result<OwnedObjectPath, SystemdError> {
+        let (timer, service) = timer.units()?;
+        transient::start(self.get_manager_proxy(), &timer, &[service], UnitMode::Fail).await
+    }
+
+    /// Runs a command as a transient service with its standard input, output and error passed
+    /// along as file descriptors, equivalent to `systemd-run --wait --pipe`. Returns once the
+    /// service is started, wait on the returned command for its exit status, runtime and
+    /// resource usage.
+    pub async fn run_command(
+        &self,
+        command: TransientCommand,
+    ) -> Result<RunningCommand, SystemdError> {
+        run::run(self.get_manager_proxy(), command).await
+    }
+
+    /// Returns a proxy for the mount object, giving access to everything in
+    /// org.freedesktop.systemd1.Mount. The unit is loaded from disk if it is not loaded yet.
+    pub async fn get_mount_proxy(&self, name: &str) -> Result<MountProxy<'static>, SystemdError> {
+        if UnitName::new(name)?.unit_type() != UnitType::Mount {
+            return Err(SystemdError::UnexpectedUnitType {
+                unit: name.to_string(),
+                expected: UnitType::Mount,
+            });
+        }
+        let path = self.get_manager_proxy().load_unit(name).await?;
+
+        Ok(
+            MountProxy::builder(self.get_manager_proxy().inner().connection())
+                .path(path)?
+                .build()
+                .await?,
+        )
+    }
+
+    /// Returns a proxy for the automount object, giving access to everything in
+    /// org.freedesktop.systemd1.Automount. The unit is loaded from disk if it is not loaded yet.
+    pub async fn get_automount_proxy(
+        &self,
+        name: &str,
+    ) -> Result<AutomountProxy<'static>, SystemdError> {
+        if UnitName::new(name)?.unit_type() != UnitType::Automount {
+            return Err(SystemdError::UnexpectedUnitType {
+                unit: name.to_string(),
+                expected: UnitType::Automount,
+            });
+        }
+        let path = self.get_manager_proxy().load_unit(name).await?;
+
+        Ok(
+            AutomountProxy::builder(self.get_manager_proxy().inner().connection())
+                .path(path)?
+                .build()
+                .await?,
+        )
+    }
+
+    /// Mounts a file system as a transient mount unit, or sets up a transient automount point for
+    /// it, equivalent to `systemd-mount`. Stop the unit to unmount it again. Returns the job that
+    /// starts the mount or automount unit.
+    pub async fn start_transient_mount(
+        &self,
+        mount: &TransientMount,
+    ) -> Result<OwnedObjectPath, SystemdError> {
+        let (unit, aux) = mount.units()?;
+        transient::start(self.get_manager_proxy(), &unit, &aux, UnitMode::Fail).await
+    }
+
     /// May be invoked to reload all unit files.
     pub async fn reload(&self) -> Result<(), SystemdError> {
         Ok(self.get_manager_proxy().reload().await?)
     }
+
+    /// Returns the current operational state of the manager, equivalent to
+    /// `systemctl is-system-running`. The value is always read fresh from the bus.
+    pub async fn system_state(&self) -> Result<SystemState, SystemdError> {
+        system_state::current_system_state(self.get_manager_proxy()).await
+    }
+
+    /// Equivalent to `systemctl is-system-running --wait`. Waits until the manager has finished
+    /// booting, i.e. is no longer "initializing" or "starting", by watching the SystemState
+    /// property and the StartupFinished signal. Resolves with the state it settled in, which is one
+    /// of running, degraded, maintenance or stopping on a healthy system. If the system is degraded,
+    /// the failed units are listed as well. Fails with a timeout error if the timeout elapses first.
+    pub async fn wait_until_running(
+        &self,
+        timeout: Duration,
+    ) -> Result<SystemRunningState, SystemdError> {
+        system_state::wait_until_running(self.get_manager_proxy(), timeout).await
+    }
 }
 
 impl ConnectionLevel {
@@ -308,6 +1151,11 @@ impl ConnectionLevel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::systemctl::{
+        job::{JobState, JobType},
+        unit::UnitActiveState,
+        unit_name::UnitType,
+    };
 
     #[test]
     fn it_can_list_jobs() {
@@ -323,6 +1171,65 @@ mod tests {
         });
     }
 
+    #[test]
+    fn can_get_dependencies_of_queued_jobs() {
+        smol::block_on(async {
+            let system_ctl = SystemCtlBuilder::new()
+                .init()
+                .await
+                .expect("Should be able to init connection");
+
+            let jobs = system_ctl
+                .list_jobs()
+                .await
+                .expect("Should be able to list jobs");
+
+            for job in jobs {
+                // Jobs may finish while we are looking at them, so only check that known job
+                // types and states are returned.
+                assert!(!matches!(job.job_type, JobType::Other(_)));
+                assert!(!matches!(job.job_state, JobState::Other(_)));
+                let _ = system_ctl.job_dependencies(job.id).await;
+            }
+        });
+    }
+
+    #[test]
+    fn can_get_freezer_state() {
+        smol::block_on(async {
+            let system_ctl = SystemCtlBuilder::new()
+                .init()
+                .await
+                .expect("Should be able to init connection");
+
+            let state = system_ctl
+                .freezer_state("dbus.service")
+                .await
+                .expect("Should be able to get freezer state");
+
+            assert_eq!(state, FreezerState::Running);
+        });
+    }
+
+    #[test]
+    fn reset_failed_reports_nothing_for_unmatched_query() {
+        smol::block_on(async {
+            let system_ctl = SystemCtlBuilder::new()
+                .init()
+                .await
+                .expect("Should be able to init connection");
+
+            let query = UnitQuery::new().with_pattern("this-unit-does-not-exist-*.service");
+
+            let reset = system_ctl
+                .reset_failed(&ResetFailedSelector::Query(query))
+                .await
+                .expect("Should be able to reset failed units");
+
+            assert!(reset.is_empty());
+        });
+    }
+
     #[test]
     fn can_get_unit() {
         smol::block_on(async {
@@ -371,6 +1278,20 @@ mod tests {
         });
     }
 
+    #[test]
+    fn can_get_unit_file_links() {
+        smol::block_on(async {
+            let system_ctl = SystemCtlBuilder::new()
+                .init()
+                .await
+                .expect("Should be able to init connection");
+
+            let links = system_ctl.unit_file_links("dbus.service", false).await;
+
+            assert!(links.is_ok());
+        });
+    }
+
     #[test]
     fn can_list_unit_files() {
         smol::block_on(async {
@@ -405,6 +1326,49 @@ mod tests {
         });
     }
 
+    #[test]
+    fn can_list_units_by_query() {
+        smol::block_on(async {
+            let system_ctl = SystemCtlBuilder::new()
+                .init()
+                .await
+                .expect("Should be able to init connection");
+
+            let query = UnitQuery::new()
+                .with_active_state(UnitActiveState::Active)
+                .with_unit_type(UnitType::Service);
+
+            let units = system_ctl
+                .list_units_by_query(&query)
+                .await
+                .expect("Should be able to list units");
+
+            assert!(!units.is_empty());
+
+            for unit in units {
+                assert!(unit.name.ends_with(".service"));
+                assert_eq!(unit.active_state, UnitActiveState::Active);
+            }
+        });
+    }
+
+    #[test]
+    fn can_wait_until_running() {
+        smol::block_on(async {
+            let system_ctl = SystemCtlBuilder::new()
+                .init()
+                .await
+                .expect("Should be able to init connection");
+
+            let running_state = system_ctl
+                .wait_until_running(Duration::from_secs(5))
+                .await
+                .expect("Should be able to get system state");
+
+            assert!(!running_state.state.is_booting());
+        });
+    }
+
     #[test]
     fn can_list_units() {
         smol::block_on(async {
@@ -422,4 +1386,41 @@ mod tests {
             assert!(!units.is_empty());
         });
     }
+
+    #[test]
+    fn can_set_and_unset_environment() {
+        smol::block_on(async {
+            let system_ctl = SystemCtlBuilder::new()
+                .init()
+                .await
+                .expect("Should be able to init connection");
+
+            // A name nothing else uses, which is unset again before anything is checked so the
+            // manager is left as it was even if the test fails.
+            let name = format!("SYSTEMDZBUS_TEST_{}", std::process::id());
+            let assignment = format!("{name}=1");
+
+            let set = system_ctl.set_env(&[&assignment]).await;
+            let with_variable = system_ctl.environment().await;
+            let unset = system_ctl.unset_env(&[&name]).await;
+            let without_variable = system_ctl.environment().await;
+
+            set.expect("Should be able to set environment");
+            unset.expect("Should be able to unset environment");
+            assert_eq!(
+                with_variable
+                    .expect("Should be able to read environment")
+                    .get(&name)
+                    .map(String::as_str),
+                Some("1")
+            );
+            assert!(
+                !without_variable
+                    .expect("Should be able to read environment")
+                    .contains_key(&name)
+            );
+
+            assert!(system_ctl.set_env(&["NOT VALID=1"]).await.is_err());
+        });
+    }
 }
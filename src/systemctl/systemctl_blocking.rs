@@ -1,18 +1,54 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Duration,
+};
+
 use zbus::{blocking::Connection, zvariant::OwnedObjectPath};
 
 use crate::{
+    ManagerProxy, ServiceProxy, UnitProxy,
+    automount::AutomountProxyBlocking,
     errors::SystemdError,
+    job::JobProxyBlocking,
+    login1::{inhibitor::Inhibitor, manager::ManagerProxy as LoginManagerProxy},
     manager::ManagerProxyBlocking,
+    mount::MountProxyBlocking,
+    service::ServiceProxyBlocking,
     systemctl::{
+        clean::CleanMask,
         connection_level::ConnectionLevel,
-        job::Job,
-        unit::{Unit, UnitEnablementResponse, UnitMode},
-        unit_file::{EnablementStatus, UnitFile},
+        delta::{self, Delta},
+        environment,
+        freezer::{self, FreezeGuard, FreezerState},
+        job::{EnqueuedTransaction, Job, JobDependencies, JobType},
+        logging::{LogLevel, LogTarget, ShowStatus},
+        lookup_paths::{LookupPaths, UnitFileLookup},
+        offline::OfflineSystemCtl,
+        power::{self, PowerAction, PowerCapability, ScheduledShutdown},
+        preset::Presets,
+        process::{self, CgroupNode, ProcessOwner, UnitProcess},
+        reset_failed::ResetFailedSelector,
+        run::{self, RunningCommand, TransientCommand},
+        security::{SandboxSettings, SecurityReport},
+        system_state::{self, SystemRunningState, SystemState},
+        transient::{self, TransientMount, TransientTimer},
+        uncached_manager_proxy,
+        unit::{Unit, UnitActiveState, UnitEnablementResponse, UnitMode},
+        unit_config,
+        unit_file::{EnablementStatus, UnitFile, UnitFileQuery},
+        unit_name::{UnitName, UnitType, unit_name_from_object_path},
+        unit_query::{UnitListMethod, UnitQuery},
+        watchdog::{self, Watchdog},
     },
+    unit::UnitProxyBlocking,
 };
 
 pub struct SystemCtlBlockingBuilder {
     connection_level: ConnectionLevel,
+    freezer_timeout: Duration,
 }
 
 impl Default for SystemCtlBlockingBuilder {
@@ -25,6 +61,7 @@ impl SystemCtlBlockingBuilder {
     pub fn new() -> Self {
         Self {
             connection_level: ConnectionLevel::UserLevel,
+            freezer_timeout: freezer::DEFAULT_FREEZER_TIMEOUT,
         }
     }
 
@@ -33,20 +70,36 @@ impl SystemCtlBlockingBuilder {
         self
     }
 
+    /// How long freeze() and thaw() wait for the processes of the unit to be frozen or running
+    /// again, 90 seconds by default.
+    pub fn with_freezer_timeout(mut self, timeout: Duration) -> Self {
+        self.freezer_timeout = timeout;
+        self
+    }
+
+    /// Manages the unit files below the given root directory instead of connecting to the
+    /// service manager, like `systemctl --root=` does.
+    pub fn init_offline(self, root: impl AsRef<Path>) -> OfflineSystemCtl {
+        OfflineSystemCtl::new(root.as_ref(), self.connection_level)
+    }
+
     pub fn init<'a>(self) -> Result<SystemCtlBlocking<'a>, SystemdError> {
         let connection = self.connection_level.get_connection_blocking()?;
         let proxy = ManagerProxyBlocking::new(&connection)?;
         Ok(SystemCtlBlocking {
             manager_proxy: proxy,
             connection_level: self.connection_level,
+            freezer_timeout: self.freezer_timeout,
+            presets: OnceLock::new(),
         })
     }
 }
 
 pub struct SystemCtlBlocking<'a> {
     manager_proxy: ManagerProxyBlocking<'a>,
-    #[allow(unused)]
     connection_level: ConnectionLevel,
+    freezer_timeout: Duration,
+    presets: OnceLock<Presets>,
 }
 
 impl<'a> SystemCtlBlocking<'a> {
@@ -67,12 +120,52 @@ impl<'a> SystemCtlBlocking<'a> {
     /// "ignore-dependencies", it will start a unit but ignore all its dependencies. If "ignore-requirements",
     /// it will start a unit but only ignore the requirement dependencies. It is not recommended to make use of
     /// the latter two options. On completion, this method returns the newly created job object.
+    /// In "isolate" mode, the unit is checked to allow isolation first, see isolate().
     pub fn start_unit(&self, name: &str, mode: UnitMode) -> Result<OwnedObjectPath, SystemdError> {
+        self.check_allow_isolate(name, &mode)?;
+
         Ok(self
             .get_manager_proxy()
             .start_unit(name, &mode.to_string())?)
     }
 
+    /// Starts the unit and stops all units that are not dependencies of it, equivalent to
+    /// `systemctl isolate`. Only units with AllowIsolate=yes may be isolated, which is checked
+    /// before the job is enqueued so a descriptive error can be returned.
+    pub fn isolate(&self, target: &str) -> Result<OwnedObjectPath, SystemdError> {
+        self.start_unit(target, UnitMode::Isolate)
+    }
+
+    /// Retrieves the name of the unit to which default.target is aliased, equivalent to
+    /// `systemctl get-default`.
+    pub fn default_target(&self) -> Result<UnitName, SystemdError> {
+        UnitName::new(self.get_manager_proxy().get_default_target()?)
+    }
+
+    /// Changes default.target to be an alias of the given target, equivalent to
+    /// `systemctl set-default`. If force is set, an existing default.target symlink is replaced
+    /// even if it does not point to a unit file of its own.
+    pub fn set_default_target(
+        &self,
+        target: &UnitName,
+        force: bool,
+    ) -> Result<UnitEnablementResponse, SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .set_default_target(target.as_str(), force)?
+            .into())
+    }
+
+    /// Makes sure the unit may be isolated if the job is enqueued in "isolate" mode. Every method
+    /// that can enqueue a start job goes through here, so the check cannot be bypassed.
+    fn check_allow_isolate(&self, name: &str, mode: &UnitMode) -> Result<(), SystemdError> {
+        if *mode != UnitMode::Isolate || self.get_unit_proxy(name)?.allow_isolate()? {
+            Ok(())
+        } else {
+            Err(SystemdError::IsolateNotAllowed(name.to_string()))
+        }
+    }
+
     /// Similar to StartUnit() but stops the specified unit rather than starting it. Note that the
     /// "isolate" mode is invalid for this method.
     pub fn stop_unit(&self, name: &str, mode: UnitMode) -> Result<OwnedObjectPath, SystemdError> {
@@ -85,14 +178,19 @@ impl<'a> SystemCtlBlocking<'a> {
             .stop_unit(name, &mode.to_string())?)
     }
 
-    /// RestartUnit method, takes in the mode, i.e. same as start unit, I quote:
-    /// The mode needs to be one of "replace", "fail", "isolate", "ignore-dependencies", or
-    /// "ignore-requirements". returns the object path of the restarted unit.
+    /// RestartUnit method, takes in the mode, i.e. same as start unit except that systemd only
+    /// accepts "isolate" for start jobs, so it is invalid here. The mode needs to be one of
+    /// "replace", "fail", "ignore-dependencies", or "ignore-requirements". returns the object
+    /// path of the restarted unit.
     pub fn restart_unit(
         &self,
         name: &str,
         mode: UnitMode,
     ) -> Result<OwnedObjectPath, SystemdError> {
+        if let UnitMode::Isolate = mode {
+            return Err(SystemdError::IsolateModeUnavailable);
+        };
+
         Ok(self
             .get_manager_proxy()
             .restart_unit(name, &mode.to_string())?)
@@ -101,23 +199,57 @@ impl<'a> SystemCtlBlocking<'a> {
     /// ReloadUnit(), RestartUnit(), TryRestartUnit(), ReloadOrRestartUnit(), or ReloadOrTryRestartUnit() may be used to restart and/or reload a unit. These methods take similar arguments as StartUnit(). Reloading is done only if the
     /// unit is already running and fails otherwise. If a service is restarted that isn't running, it will be started unless the "Try" flavor is used in which case a service that isn't running is not affected by the restart. The
     /// "ReloadOrRestart" flavors attempt a reload if the unit supports it and use a restart otherwise.
+    /// Note that the "isolate" mode is invalid for this method.
     pub fn reload_unit(&self, name: &str, mode: UnitMode) -> Result<OwnedObjectPath, SystemdError> {
+        if let UnitMode::Isolate = mode {
+            return Err(SystemdError::IsolateModeUnavailable);
+        };
+
         Ok(self
             .get_manager_proxy()
             .reload_unit(name, &mode.to_string())?)
     }
 
-    /// A combination or the reload and restart methods.
+    /// A combination or the reload and restart methods. Note that the "isolate" mode is invalid
+    /// for this method.
     pub fn reload_or_restart_unit(
         &self,
         name: &str,
         mode: UnitMode,
     ) -> Result<OwnedObjectPath, SystemdError> {
+        if let UnitMode::Isolate = mode {
+            return Err(SystemdError::IsolateModeUnavailable);
+        };
+
         Ok(self
             .get_manager_proxy()
             .reload_or_restart_unit(name, &mode.to_string())?)
     }
 
+    /// Enqueues a job of any type for the unit, the generic form of StartUnit(), StopUnit(),
+    /// RestartUnit() and friends. Besides the types that have their own methods, this allows e.g.
+    /// verify-active jobs, which check that a unit is active without starting it. The mode is the
+    /// same as for StartUnit(), where "isolate" is only valid for start jobs. Returns the new job
+    /// together with all other jobs that were enqueued as part of the same transaction, which is
+    /// what `systemctl --show-transaction` prints.
+    pub fn enqueue_job(
+        &self,
+        name: &str,
+        job_type: JobType,
+        mode: UnitMode,
+    ) -> Result<EnqueuedTransaction, SystemdError> {
+        if mode == UnitMode::Isolate && job_type != JobType::Start {
+            return Err(SystemdError::IsolateModeUnavailable);
+        }
+
+        self.check_allow_isolate(name, &mode)?;
+
+        Ok(self
+            .get_manager_proxy()
+            .enqueue_unit_job(name, &job_type.to_string(), &mode.to_string())?
+            .into())
+    }
+
     /// May be used to enable one or more units in the system (by creating symlinks to them in /etc/ or /run/). It takes a list of unit files to enable (either just file names or full
     /// absolute paths if the unit files are residing outside the usual unit search paths) and two booleans: the first controls whether the unit shall be enabled for runtime only (true, /run/), or
     /// persistently (false, /etc/). The second one controls whether symlinks pointing to other units shall be replaced if necessary. This method returns one boolean and an array of the changes made. The
@@ -227,7 +359,7 @@ impl<'a> SystemCtlBlocking<'a> {
             .into())
     }
 
-    /// Similar to unmask units except only masks a single unit file
+    /// Similar to unmask units except only unmasks a single unit file
     pub fn unmask_unit(
         &self,
         name: &str,
@@ -239,24 +371,215 @@ impl<'a> SystemCtlBlocking<'a> {
             .into())
     }
 
+    /// Lists the units matching the query, like `systemctl list-units` does with its --state=,
+    /// --type=, --all and pattern arguments. The query is answered by ListUnitsByNames(),
+    /// ListUnitsByPatterns() or ListUnitsFiltered() where possible, so only the units of interest
+    /// are sent over the bus.
+    pub fn list_units_by_query(&self, query: &UnitQuery) -> Result<Vec<Unit>, SystemdError> {
+        let proxy = self.get_manager_proxy();
+
+        let units = match query.method() {
+            UnitListMethod::All => proxy.list_units()?,
+            UnitListMethod::Filtered { states } => {
+                let states: Vec<&str> = states.iter().map(String::as_str).collect();
+                proxy.list_units_filtered(&states)?
+            }
+            UnitListMethod::ByPatterns { states, patterns } => {
+                let states: Vec<&str> = states.iter().map(String::as_str).collect();
+                let patterns: Vec<&str> = patterns.iter().map(String::as_str).collect();
+                proxy.list_units_by_patterns(&states, &patterns)?
+            }
+            UnitListMethod::ByNames { names } => {
+                let names: Vec<&str> = names.iter().map(String::as_str).collect();
+                proxy.list_units_by_names(&names)?
+            }
+        };
+
+        Ok(query.filter(units.into_iter().map(Unit::from)))
+    }
+
     /// May be used to get the unit object path for a unit name. It takes the unit name and returns
     /// the object path. If a unit has not been loaded yet by this name this method will fail.
     pub fn get_unit(&self, name: &str) -> Result<OwnedObjectPath, SystemdError> {
         Ok(self.get_manager_proxy().get_unit(name)?)
     }
 
+    /// Returns a proxy for the unit object, giving access to everything in
+    /// org.freedesktop.systemd1.Unit. The unit is loaded from disk if it is not loaded yet.
+    pub fn get_unit_proxy(&self, name: &str) -> Result<UnitProxyBlocking<'static>, SystemdError> {
+        let path = self.get_manager_proxy().load_unit(name)?;
+
+        Ok(
+            UnitProxyBlocking::builder(self.get_manager_proxy().inner().connection())
+                .path(path)?
+                .build()?,
+        )
+    }
+
+    /// Returns the freezer state of the unit, i.e. whether its processes are currently frozen.
+    pub fn freezer_state(&self, name: &str) -> Result<FreezerState, SystemdError> {
+        Ok(self.get_unit_proxy(name)?.freezer_state()?.into())
+    }
+
+    /// Freezes all processes of the unit using the cgroup freezer, equivalent to
+    /// `systemctl freeze`. Blocks until the FreezerState of the unit is "frozen".
+    pub fn freeze(&self, name: &str) -> Result<(), SystemdError> {
+        let unit = self.async_unit_proxy(name)?;
+        async_io::block_on(freezer::freeze(
+            &self.async_manager_proxy(),
+            &unit,
+            name,
+            self.freezer_timeout,
+        ))
+    }
+
+    /// Thaws the processes of a frozen unit, equivalent to `systemctl thaw`. Blocks until the
+    /// FreezerState of the unit is "running" again.
+    pub fn thaw(&self, name: &str) -> Result<(), SystemdError> {
+        let unit = self.async_unit_proxy(name)?;
+        async_io::block_on(freezer::thaw(
+            &self.async_manager_proxy(),
+            &unit,
+            name,
+            self.freezer_timeout,
+        ))
+    }
+
+    /// Freezes the unit like freeze() does, and returns a guard that thaws the unit again once it
+    /// goes out of scope.
+    pub fn freeze_scoped(&self, name: &str) -> Result<FreezeGuard<'a>, SystemdError> {
+        let unit = self.async_unit_proxy(name)?;
+        let manager_proxy = self.async_manager_proxy();
+        async_io::block_on(freezer::freeze(
+            &manager_proxy,
+            &unit,
+            name,
+            self.freezer_timeout,
+        ))?;
+
+        Ok(FreezeGuard::new(
+            manager_proxy,
+            unit,
+            name,
+            self.freezer_timeout,
+        ))
+    }
+
+    /// Removes the configuration, state, cache, log or runtime data of the unit, or its file
+    /// descriptor store, selected by the mask. Equivalent to `systemctl clean --what=`. Like
+    /// systemctl, this first checks the CanClean property of the unit, failing if any of the
+    /// requested resource types cannot be cleaned, and fails if the unit is not inactive, since the
+    /// resources of a running unit are in use.
+    pub fn clean_unit(&self, name: &str, mask: CleanMask) -> Result<(), SystemdError> {
+        let unit = self.get_unit_proxy(name)?;
+
+        let can_clean = CleanMask::from_names(&unit.can_clean()?);
+        if !can_clean.contains(mask) {
+            return Err(SystemdError::CleanNotSupported {
+                unit: name.to_string(),
+                unsupported: mask.difference(can_clean).to_string(),
+            });
+        }
+
+        let active_state = UnitActiveState::from(unit.active_state()?);
+        if !matches!(
+            active_state,
+            UnitActiveState::Inactive | UnitActiveState::Failed
+        ) {
+            return Err(SystemdError::UnitNotInactive {
+                unit: name.to_string(),
+                active_state: active_state.to_string(),
+            });
+        }
+
+        Ok(self.get_manager_proxy().clean_unit(name, &mask.names())?)
+    }
+
+    /// Resets the "failed" state of the selected units, equivalent to `systemctl reset-failed`.
+    /// Only units that are actually failed are reset, except for ResetFailedSelector::All, where
+    /// systemd resets every unit, including its start rate limit counter. Returns the units that
+    /// were in the failed state before they were reset, so the caller knows which failures were
+    /// cleared.
+    pub fn reset_failed(&self, selector: &ResetFailedSelector) -> Result<Vec<Unit>, SystemdError> {
+        let proxy = self.get_manager_proxy();
+        let failed = UnitActiveState::Failed.to_string();
+
+        let failed_units: Vec<Unit> = match selector {
+            ResetFailedSelector::All => {
+                let failed_units = proxy
+                    .list_units_filtered(&[&failed])?
+                    .into_iter()
+                    .map(Unit::from)
+                    .collect();
+                proxy.reset_failed()?;
+                return Ok(failed_units);
+            }
+            ResetFailedSelector::Units(names) => {
+                let names: Vec<&str> = names.iter().map(UnitName::as_str).collect();
+                proxy
+                    .list_units_by_names(&names)?
+                    .into_iter()
+                    .map(Unit::from)
+                    .collect()
+            }
+            ResetFailedSelector::Query(query) => self.list_units_by_query(query)?,
+        };
+
+        let failed_units: Vec<Unit> = failed_units
+            .into_iter()
+            .filter(|unit| unit.active_state == UnitActiveState::Failed)
+            .collect();
+
+        for unit in &failed_units {
+            proxy.reset_failed_unit(&unit.name)?;
+        }
+
+        Ok(failed_units)
+    }
+
     /// Returns an array of unit names and their enablement status. Note that ListUnit() returns a list of units currently loaded into memory, while ListUnitFiles() returns a list of unit
     /// files that were found on disk. Note that while most units are read directly from a unit file with the same name, some units are not backed by files and some files (templates) cannot directly be loaded
     /// as units but need to be instantiated instead.
     pub fn list_unit_files(&self) -> Result<Vec<UnitFile>, SystemdError> {
+        let presets = self.presets();
+
         Ok(self
             .get_manager_proxy()
             .list_unit_files()?
             .into_iter()
-            .map(Into::into)
+            .map(|unit_file| UnitFile::from(unit_file).with_preset(presets))
+            .collect())
+    }
+
+    /// Lists the unit files matching the query, like `systemctl list-unit-files` does with its
+    /// --state=, --type= and pattern arguments, using ListUnitFilesByPatterns(). The preset policy
+    /// for each unit file is looked up from the local preset files.
+    pub fn list_unit_files_by_query(
+        &self,
+        query: &UnitFileQuery,
+    ) -> Result<Vec<UnitFile>, SystemdError> {
+        let states = query.states();
+        let patterns = query.patterns();
+        let states: Vec<&str> = states.iter().map(String::as_str).collect();
+        let patterns: Vec<&str> = patterns.iter().map(String::as_str).collect();
+        let presets = self.presets();
+
+        Ok(self
+            .get_manager_proxy()
+            .list_unit_files_by_patterns(&states, &patterns)?
+            .into_iter()
+            .map(|unit_file| UnitFile::from(unit_file).with_preset(presets))
+            .filter(|unit_file| query.matches(unit_file))
             .collect())
     }
 
+    /// The preset policy of the manager this is connected to, read from the local preset files
+    /// the first time it is needed.
+    fn presets(&self) -> &Presets {
+        self.presets
+            .get_or_init(|| Presets::load(&self.connection_level, Path::new("/")))
+    }
+
     /// Returns the current enablement status of a specific unit file. The format of the string
     /// here is simply name.service, in other words, if you retrieved the unit files via
     /// list_unit_files, you may want to strip the prefix on the path to get the service name.
@@ -264,6 +587,65 @@ impl<'a> SystemCtlBlocking<'a> {
         Ok(self.get_manager_proxy().get_unit_file_state(file)?.into())
     }
 
+    /// Returns the paths of all symlinks pointing to the unit file, either the persistent ones in
+    /// /etc/ or, if runtime is set, the ones in /run/. This explains why a unit file is in the
+    /// enablement state it is in, e.g. which .wants/ directories make it enabled or where it is
+    /// masked.
+    pub fn unit_file_links(&self, name: &str, runtime: bool) -> Result<Vec<String>, SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .get_unit_file_links(name, runtime)?)
+    }
+
+    /// Returns all processes of the unit, grouped by the control group they are in, like the
+    /// process tree shown by `systemctl status`. The root of the tree is the control group of the
+    /// unit itself.
+    pub fn unit_processes(&self, name: &str) -> Result<CgroupNode, SystemdError> {
+        let processes = self
+            .get_manager_proxy()
+            .get_unit_processes(name)?
+            .into_iter()
+            .map(UnitProcess::from)
+            .collect();
+
+        Ok(CgroupNode::from_processes(processes))
+    }
+
+    /// Finds the unit a process belongs to. GetUnitByPID() is asked first, and if the manager does
+    /// not know the process, the control group of the process (and then each of its parents) is
+    /// looked up with GetUnitByControlGroup().
+    pub fn unit_for_pid(&self, pid: u32) -> Result<ProcessOwner, SystemdError> {
+        let proxy = self.get_manager_proxy();
+
+        let object_path = match proxy.get_unit_by_pid(pid) {
+            Ok(object_path) => object_path,
+            Err(err) if process::is_unit_not_found(&err) => {
+                let cgroup = process::cgroup_of_pid(pid).ok_or(err)?;
+                let mut found = None;
+                for ancestor in process::cgroup_ancestors(&cgroup) {
+                    match proxy.get_unit_by_control_group(&ancestor) {
+                        Ok(object_path) => {
+                            found = Some(object_path);
+                            break;
+                        }
+                        Err(err) if process::is_unit_not_found(&err) => continue,
+                        Err(err) => return Err(err.into()),
+                    }
+                }
+                found.ok_or(SystemdError::NoUnitForProcess(pid))?
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let unit_name =
+            unit_name_from_object_path(&object_path).ok_or(SystemdError::NoUnitForProcess(pid))?;
+
+        Ok(ProcessOwner {
+            unit_name,
+            object_path,
+        })
+    }
+
     /// Returns an array with all currently queued jobs. Returns an array consisting of structures with the following elements:
     /// •   The numeric job id
     /// •   The primary unit name for this job
@@ -280,10 +662,449 @@ impl<'a> SystemCtlBlocking<'a> {
             .collect())
     }
 
+    /// Returns a proxy for the job object with the given id, giving access to everything in
+    /// org.freedesktop.systemd1.Job.
+    pub fn get_job_proxy(&self, id: u32) -> Result<JobProxyBlocking<'static>, SystemdError> {
+        let path = self.get_manager_proxy().get_job(id)?;
+
+        Ok(
+            JobProxyBlocking::builder(self.get_manager_proxy().inner().connection())
+                .path(path)?
+                .build()?,
+        )
+    }
+
+    /// Cancels a specific job identified by its numeric ID. This operation is also available in
+    /// the Cancel() method of Job objects and exists primarily to reduce the necessary round trips
+    /// to execute this operation. Note that this will not have any effect on jobs whose execution
+    /// has already begun.
+    pub fn cancel_job(&self, id: u32) -> Result<(), SystemdError> {
+        Ok(self.get_manager_proxy().cancel_job(id)?)
+    }
+
+    /// Flushes the job queue, removing all jobs that are still queued. Note that this does not
+    /// have any effect on jobs whose execution has already begun, it only flushes jobs that are
+    /// queued and have not yet begun execution.
+    pub fn clear_jobs(&self) -> Result<(), SystemdError> {
+        Ok(self.get_manager_proxy().clear_jobs()?)
+    }
+
+    /// Returns the jobs the job with the given id is waiting for, and the jobs that are waiting for
+    /// it in turn. Useful to find out why a job is stuck in the waiting state.
+    pub fn job_dependencies(&self, id: u32) -> Result<JobDependencies, SystemdError> {
+        let proxy = self.get_manager_proxy();
+
+        Ok(JobDependencies::new(
+            proxy.get_job_after(id)?,
+            proxy.get_job_before(id)?,
+        ))
+    }
+
+    /// Returns whether logind allows the power action, equivalent to `loginctl can-reboot` etc.
+    pub fn can_power_action(&self, action: PowerAction) -> Result<PowerCapability, SystemdError> {
+        async_io::block_on(async {
+            power::capability(&self.login_manager_proxy().await?, action).await
+        })
+    }
+
+    /// Lists all currently active inhibitor locks, equivalent to `systemd-inhibit --list`.
+    pub fn list_inhibitors(&self) -> Result<Vec<Inhibitor>, SystemdError> {
+        async_io::block_on(async {
+            power::list_inhibitors(&self.login_manager_proxy().await?).await
+        })
+    }
+
+    /// Reboots, powers off, suspends etc. the system through logind, equivalent to
+    /// `systemctl reboot` and friends. Like systemctl, this fails without doing anything if an
+    /// inhibitor lock in block mode is held for the action, listing the locks in the error, or if
+    /// logind reports the action as unavailable. Shutdown units are run as usual.
+    pub fn power_action(&self, action: PowerAction) -> Result<(), SystemdError> {
+        async_io::block_on(async {
+            power::request(
+                &self.login_manager_proxy().await?,
+                &self.async_manager_proxy(),
+                action,
+            )
+            .await
+        })
+    }
+
+    /// Asks the service manager for an immediate reboot, power-off, halt or kexec, equivalent to
+    /// `systemctl reboot --force`. Inhibitor locks are ignored and no units are stopped first, so
+    /// only use this when power_action() is not an option. Sleep actions are not supported by the
+    /// service manager, and fail.
+    pub fn force_power_action(&self, action: PowerAction) -> Result<(), SystemdError> {
+        let proxy = self.get_manager_proxy();
+
+        match action {
+            PowerAction::Reboot => proxy.reboot()?,
+            PowerAction::PowerOff => proxy.power_off()?,
+            PowerAction::Halt => proxy.halt()?,
+            PowerAction::Kexec => proxy.kexec()?,
+            _ => return Err(SystemdError::PowerActionUnsupported(action.to_string())),
+        }
+
+        Ok(())
+    }
+
+    /// Schedules a shutdown through logind, equivalent to `shutdown` with a time argument. Only
+    /// reboot, power-off, halt and kexec can be scheduled. Replaces any shutdown that was
+    /// scheduled before.
+    pub fn schedule_shutdown(&self, shutdown: &ScheduledShutdown) -> Result<(), SystemdError> {
+        async_io::block_on(async {
+            power::schedule(&self.login_manager_proxy().await?, shutdown).await
+        })
+    }
+
+    /// Returns the currently scheduled shutdown, if any.
+    pub fn scheduled_shutdown(&self) -> Result<Option<ScheduledShutdown>, SystemdError> {
+        async_io::block_on(async { power::scheduled(&self.login_manager_proxy().await?).await })
+    }
+
+    /// Cancels the scheduled shutdown, equivalent to `shutdown -c`. Returns whether a shutdown
+    /// was scheduled.
+    pub fn cancel_scheduled_shutdown(&self) -> Result<bool, SystemdError> {
+        async_io::block_on(async {
+            Ok(self
+                .login_manager_proxy()
+                .await?
+                .cancel_scheduled_shutdown()
+                .await?)
+        })
+    }
+
+    /// Transitions to a new root directory, see SwitchRoot() on the manager. This is intended to
+    /// be used by initial RAM disks. The init binary is searched for if none is given.
+    pub fn switch_root(&self, new_root: &str, init: Option<&str>) -> Result<(), SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .switch_root(new_root, init.unwrap_or_default())?)
+    }
+
+    /// Returns the environment block of the manager, which is passed to all spawned processes.
+    /// Equivalent to `systemctl show-environment`.
+    pub fn environment(&self) -> Result<BTreeMap<String, String>, SystemdError> {
+        async_io::block_on(environment::current_environment(
+            &self.async_manager_proxy(),
+        ))
+    }
+
+    /// Sets environment variables in the environment block of the manager, overriding variables
+    /// that are set already. Takes assignments of the form "NAME=value", equivalent to
+    /// `systemctl set-environment`. Nothing is changed if any of the assignments is invalid.
+    pub fn set_env(&self, assignments: &[&str]) -> Result<(), SystemdError> {
+        environment::check_assignments(assignments)?;
+        Ok(self.get_manager_proxy().set_environment(assignments)?)
+    }
+
+    /// Unsets environment variables in the environment block of the manager, equivalent to
+    /// `systemctl unset-environment`. If an assignment of the form "NAME=value" is given, the
+    /// variable is only unset if it has that value. Variables that are not set are ignored.
+    pub fn unset_env(&self, names: &[&str]) -> Result<(), SystemdError> {
+        environment::check_unset_names(names)?;
+        Ok(self.get_manager_proxy().unset_environment(names)?)
+    }
+
+    /// Copies variables from the environment of the current process into the environment block
+    /// of the manager, equivalent to `systemctl import-environment`. Names may be globs, e.g.
+    /// "XDG_*". Variables that are not set in the current process are skipped. Returns the
+    /// assignments that were imported.
+    pub fn import_env(&self, names: &[&str]) -> Result<Vec<String>, SystemdError> {
+        let assignments = environment::select_for_import(names, std::env::vars_os())?;
+        let assignments_ref: Vec<&str> = assignments.iter().map(String::as_str).collect();
+
+        if !assignments.is_empty() {
+            self.get_manager_proxy().set_environment(&assignments_ref)?;
+        }
+
+        Ok(assignments)
+    }
+
+    /// Returns the maximum log level of the manager, equivalent to `systemctl log-level`.
+    pub fn log_level(&self) -> Result<LogLevel, SystemdError> {
+        async_io::block_on(async {
+            let proxy = uncached_manager_proxy(&self.async_manager_proxy()).await?;
+            Ok(proxy.log_level().await?.into())
+        })
+    }
+
+    /// Changes the maximum log level of the manager until the next reboot, equivalent to
+    /// `systemctl log-level <level>`.
+    pub fn set_log_level(&self, level: &LogLevel) -> Result<(), SystemdError> {
+        Ok(self.get_manager_proxy().set_log_level(&level.to_string())?)
+    }
+
+    /// Returns where the manager sends its log messages, equivalent to
+    /// `systemd-analyze log-target`.
+    pub fn log_target(&self) -> Result<LogTarget, SystemdError> {
+        async_io::block_on(async {
+            let proxy = uncached_manager_proxy(&self.async_manager_proxy()).await?;
+            Ok(proxy.log_target().await?.into())
+        })
+    }
+
+    /// Changes where the manager sends its log messages until the next reboot, equivalent to
+    /// `systemd-analyze log-target <target>`.
+    pub fn set_log_target(&self, target: &LogTarget) -> Result<(), SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .set_log_target(&target.to_string())?)
+    }
+
+    /// Returns whether the manager currently shows status messages on the console, i.e.
+    /// `ShowStatus::Yes` or `ShowStatus::No`.
+    pub fn show_status(&self) -> Result<ShowStatus, SystemdError> {
+        async_io::block_on(async {
+            let proxy = uncached_manager_proxy(&self.async_manager_proxy()).await?;
+            Ok(proxy.show_status().await?.into())
+        })
+    }
+
+    /// Changes whether the manager shows status messages on the console, overriding ShowStatus=
+    /// in systemd-system.conf until the next reboot.
+    pub fn set_show_status(&self, mode: &ShowStatus) -> Result<(), SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .set_show_status(&mode.to_string())?)
+    }
+
+    /// Returns whether service watchdogs are enabled, equivalent to
+    /// `systemd-analyze service-watchdogs`. If disabled, WatchdogSec= of services is ignored.
+    pub fn service_watchdogs(&self) -> Result<bool, SystemdError> {
+        async_io::block_on(async {
+            let proxy = uncached_manager_proxy(&self.async_manager_proxy()).await?;
+            Ok(proxy.service_watchdogs().await?)
+        })
+    }
+
+    /// Enables or disables service watchdogs, equivalent to
+    /// `systemd-analyze service-watchdogs <bool>`.
+    pub fn set_service_watchdogs(&self, enabled: bool) -> Result<(), SystemdError> {
+        Ok(self.get_manager_proxy().set_service_watchdogs(enabled)?)
+    }
+
+    /// Returns the timeout of the hardware watchdog. Zero means the watchdog is disabled, and
+    /// Duration::MAX that it never elapses.
+    pub fn watchdog(&self, watchdog: Watchdog) -> Result<Duration, SystemdError> {
+        async_io::block_on(async {
+            let proxy = uncached_manager_proxy(&self.async_manager_proxy()).await?;
+
+            let usec = match watchdog {
+                Watchdog::Runtime => proxy.runtime_watchdog_usec().await?,
+                Watchdog::Reboot => proxy.reboot_watchdog_usec().await?,
+                Watchdog::Kexec => proxy.kexec_watchdog_usec().await?,
+            };
+
+            Ok(watchdog::duration_from_usec(usec))
+        })
+    }
+
+    /// Changes the timeout of the hardware watchdog until the next reboot. Pass Duration::ZERO
+    /// to disable it.
+    pub fn set_watchdog(&self, watchdog: Watchdog, timeout: Duration) -> Result<(), SystemdError> {
+        let proxy = self.get_manager_proxy();
+        let usec = watchdog::usec_from_duration(timeout);
+
+        match watchdog {
+            Watchdog::Runtime => proxy.set_runtime_watchdog_usec(usec)?,
+            Watchdog::Reboot => proxy.set_reboot_watchdog_usec(usec)?,
+            Watchdog::Kexec => proxy.set_kexec_watchdog_usec(usec)?,
+        }
+
+        Ok(())
+    }
+
+    /// Returns the unit search path the manager actually uses, highest priority first.
+    pub fn unit_path(&self) -> Result<Vec<PathBuf>, SystemdError> {
+        Ok(self
+            .get_manager_proxy()
+            .unit_path()?
+            .into_iter()
+            .map(PathBuf::from)
+            .collect())
+    }
+
+    /// Computes the unit search path for the connection level locally, which allows looking up
+    /// unit files, their drop-ins and the files they override without asking the manager.
+    pub fn lookup_paths(&self) -> LookupPaths {
+        LookupPaths::for_connection_level(&self.connection_level)
+    }
+
+    /// Finds the unit file, aliases and drop-ins of a unit in the search path, like the manager
+    /// does when loading it. Returns None if there is no unit file for it.
+    pub fn find_unit_file(&self, name: &str) -> Result<Option<UnitFileLookup>, SystemdError> {
+        Ok(self.lookup_paths().resolve(&UnitName::new(name)?))
+    }
+
+    /// Lists all unit files and drop-ins in the search path that mask, override, redirect or
+    /// extend unit files further down the search path, with diffs for the ones that differ in
+    /// content. Equivalent to `systemd-delta` for the unit directories.
+    pub fn delta(&self) -> Vec<Delta> {
+        delta::delta(&self.lookup_paths())
+    }
+
+    /// Writes a unit file, e.g. a [`ServiceUnitConfig`], to /etc/systemd/system/, or to
+    /// /run/systemd/system/ if runtime is set, and the user level counterparts. Returns the path
+    /// written. The manager only picks it up after a reload, which is left to the caller so that
+    /// several unit files can be written first.
+    ///
+    /// [`ServiceUnitConfig`]: crate::ServiceUnitConfig
+    pub fn write_unit_file(
+        &self,
+        name: &str,
+        contents: &impl Display,
+        runtime: bool,
+    ) -> Result<PathBuf, SystemdError> {
+        unit_config::write_unit_file(&self.lookup_paths(), name, &contents.to_string(), runtime)
+    }
+
+    /// Returns a proxy for the service object, giving access to everything in
+    /// org.freedesktop.systemd1.Service. The unit is loaded from disk if it is not loaded yet.
+    pub fn get_service_proxy(
+        &self,
+        name: &str,
+    ) -> Result<ServiceProxyBlocking<'static>, SystemdError> {
+        if UnitName::new(name)?.unit_type() != UnitType::Service {
+            return Err(SystemdError::NotAService(name.to_string()));
+        }
+        let path = self.get_manager_proxy().load_unit(name)?;
+
+        Ok(
+            ServiceProxyBlocking::builder(self.get_manager_proxy().inner().connection())
+                .path(path)?
+                .build()?,
+        )
+    }
+
+    /// Assesses how well the service is sandboxed, based on the settings the manager has loaded
+    /// for it. Equivalent to `systemd-analyze security`.
+    pub fn security_report(&self, name: &str) -> Result<SecurityReport, SystemdError> {
+        let proxy: ServiceProxy<'_> = self.get_service_proxy(name)?.inner().inner().clone().into();
+        let settings = async_io::block_on(SandboxSettings::from_proxy(&proxy))?;
+
+        Ok(SecurityReport::new(name, &settings))
+    }
+
+    /// Creates a transient timer together with the service it activates and starts the timer,
+    /// equivalent to `systemd-run --on-calendar=... --unit=name command`. Nothing is written to
+    /// disk, both units are gone once the timer is stopped or the system reboots. Returns the job
+    /// that starts the timer.
+    pub fn start_transient_timer(
+        &self,
+        timer: &TransientTimer,
+    ) -> Result<OwnedObjectPath, SystemdError> {
+        let (timer, service) = timer.units()?;
+        async_io::block_on(transient::start(
+            &self.async_manager_proxy(),
+            &timer,
+            &[service],
+            UnitMode::Fail,
+        ))
+    }
+
+    /// Runs a command as a transient service with its standard input, output and error passed
+    /// along as file descriptors, equivalent to `systemd-run --wait --pipe`. Returns once the
+    /// service is started, wait on the returned command for its exit status, runtime and
+    /// resource usage.
+    pub fn run_command(&self, command: TransientCommand) -> Result<RunningCommand, SystemdError> {
+        async_io::block_on(run::run(&self.async_manager_proxy(), command))
+    }
+
+    /// Returns a proxy for the mount object, giving access to everything in
+    /// org.freedesktop.systemd1.Mount. The unit is loaded from disk if it is not loaded yet.
+    pub fn get_mount_proxy(&self, name: &str) -> Result<MountProxyBlocking<'static>, SystemdError> {
+        if UnitName::new(name)?.unit_type() != UnitType::Mount {
+            return Err(SystemdError::UnexpectedUnitType {
+                unit: name.to_string(),
+                expected: UnitType::Mount,
+            });
+        }
+        let path = self.get_manager_proxy().load_unit(name)?;
+
+        Ok(
+            MountProxyBlocking::builder(self.get_manager_proxy().inner().connection())
+                .path(path)?
+                .build()?,
+        )
+    }
+
+    /// Returns a proxy for the automount object, giving access to everything in
+    /// org.freedesktop.systemd1.Automount. The unit is loaded from disk if it is not loaded yet.
+    pub fn get_automount_proxy(
+        &self,
+        name: &str,
+    ) -> Result<AutomountProxyBlocking<'static>, SystemdError> {
+        if UnitName::new(name)?.unit_type() != UnitType::Automount {
+            return Err(SystemdError::UnexpectedUnitType {
+                unit: name.to_string(),
+                expected: UnitType::Automount,
+            });
+        }
+        let path = self.get_manager_proxy().load_unit(name)?;
+
+        Ok(
+            AutomountProxyBlocking::builder(self.get_manager_proxy().inner().connection())
+                .path(path)?
+                .build()?,
+        )
+    }
+
+    /// Mounts a file system as a transient mount unit, or sets up a transient automount point for
+    /// it, equivalent to `systemd-mount`. Stop the unit to unmount it again. Returns the job that
+    /// starts the mount or automount unit.
+    pub fn start_transient_mount(
+        &self,
+        mount: &TransientMount,
+    ) -> Result<OwnedObjectPath, SystemdError> {
+        let (unit, aux) = mount.units()?;
+        async_io::block_on(transient::start(
+            &self.async_manager_proxy(),
+            &unit,
+            &aux,
+            UnitMode::Fail,
+        ))
+    }
+
     /// May be invoked to reload all unit files.
     pub fn reload(&self) -> Result<(), SystemdError> {
         Ok(self.get_manager_proxy().reload()?)
     }
+
+    /// Returns the current operational state of the manager, equivalent to
+    /// `systemctl is-system-running`. The value is always read fresh from the bus.
+    pub fn system_state(&self) -> Result<SystemState, SystemdError> {
+        async_io::block_on(system_state::current_system_state(
+            &self.async_manager_proxy(),
+        ))
+    }
+
+    /// Equivalent to `systemctl is-system-running --wait`. Blocks until the manager has finished
+    /// booting or the timeout elapses. See the async version for details.
+    pub fn wait_until_running(
+        &self,
+        timeout: Duration,
+    ) -> Result<SystemRunningState, SystemdError> {
+        async_io::block_on(system_state::wait_until_running(
+            &self.async_manager_proxy(),
+            timeout,
+        ))
+    }
+
+    /// The blocking proxy wraps an async one, which is what the signal based helpers are built on.
+    fn async_manager_proxy(&self) -> ManagerProxy<'a> {
+        self.manager_proxy.inner().inner().clone().into()
+    }
+
+    async fn login_manager_proxy(&self) -> Result<LoginManagerProxy<'static>, SystemdError> {
+        let connection = (self.connection_level == ConnectionLevel::SystemLevel)
+            .then(|| self.get_manager_proxy().inner().connection().inner());
+
+        power::login_manager_proxy(connection).await
+    }
+
+    fn async_unit_proxy(&self, name: &str) -> Result<UnitProxy<'static>, SystemdError> {
+        Ok(self.get_unit_proxy(name)?.inner().inner().clone().into())
+    }
 }
 
 impl ConnectionLevel {
@@ -300,6 +1121,7 @@ impl ConnectionLevel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::systemctl::unit_name::UnitType;
 
     #[test]
     fn can_get_unit() {
@@ -362,6 +1184,125 @@ mod tests {
         assert!(!unit_files.is_empty())
     }
 
+    #[test]
+    fn can_list_unit_files_by_query() {
+        let system_ctl = SystemCtlBlockingBuilder::new()
+            .init()
+            .expect("Should be able to init connection");
+
+        let query = UnitFileQuery::new().with_pattern("*.service");
+
+        let unit_files = system_ctl
+            .list_unit_files_by_query(&query)
+            .expect("Should be able to list unit files");
+
+        for unit_file in unit_files {
+            assert!(unit_file.file_name().ends_with(".service"));
+            assert!(unit_file.preset.is_some());
+        }
+    }
+
+    #[test]
+    fn can_find_unit_of_process() {
+        let system_ctl = SystemCtlBlockingBuilder::new()
+            .init()
+            .expect("Should be able to init connection");
+
+        let owner = system_ctl
+            .unit_for_pid(std::process::id())
+            .expect("This process should belong to a unit");
+
+        let processes = system_ctl
+            .unit_processes(&owner.unit_name)
+            .expect("Should be able to list processes of the unit");
+
+        assert!(
+            processes
+                .all_processes()
+                .iter()
+                .any(|process| process.pid == std::process::id())
+        );
+    }
+
+    #[test]
+    fn can_enqueue_verify_job() {
+        let system_ctl = SystemCtlBlockingBuilder::new()
+            .init()
+            .expect("Should be able to init connection");
+
+        let transaction = system_ctl
+            .enqueue_job("dbus.service", JobType::VerifyActive, UnitMode::Fail)
+            .expect("Should be able to enqueue a verify job");
+
+        assert_eq!(transaction.job.unit_name, "dbus.service");
+        assert_eq!(transaction.job.job_type, JobType::VerifyActive);
+    }
+
+    #[test]
+    fn refuses_to_clean_running_unit() {
+        let system_ctl = SystemCtlBlockingBuilder::new()
+            .init()
+            .expect("Should be able to init connection");
+
+        let result = system_ctl.clean_unit("dbus.service", CleanMask::RUNTIME);
+
+        assert!(matches!(
+            result,
+            Err(SystemdError::UnitNotInactive { .. } | SystemdError::CleanNotSupported { .. })
+        ));
+    }
+
+    #[test]
+    fn can_get_default_target() {
+        let system_ctl = SystemCtlBlockingBuilder::new()
+            .init()
+            .expect("Should be able to init connection");
+
+        let target = system_ctl
+            .default_target()
+            .expect("Should be able to get default target");
+
+        assert_eq!(target.unit_type(), UnitType::Target);
+    }
+
+    #[test]
+    fn refuses_to_isolate_services() {
+        let system_ctl = SystemCtlBlockingBuilder::new()
+            .init()
+            .expect("Should be able to init connection");
+
+        let result = system_ctl.isolate("dbus.service");
+
+        assert!(matches!(result, Err(SystemdError::IsolateNotAllowed(_))));
+    }
+
+    #[test]
+    fn only_accepts_isolate_mode_for_start_jobs() {
+        let system_ctl = SystemCtlBlockingBuilder::new()
+            .init()
+            .expect("Should be able to init connection");
+
+        let restarted = system_ctl.restart_unit("multi-user.target", UnitMode::Isolate);
+        let reloaded = system_ctl.reload_unit("multi-user.target", UnitMode::Isolate);
+        let reloaded_or_restarted =
+            system_ctl.reload_or_restart_unit("multi-user.target", UnitMode::Isolate);
+        let enqueued = system_ctl.enqueue_job("dbus.service", JobType::Start, UnitMode::Isolate);
+
+        assert!(matches!(
+            restarted,
+            Err(SystemdError::IsolateModeUnavailable)
+        ));
+        assert!(matches!(
+            reloaded,
+            Err(SystemdError::IsolateModeUnavailable)
+        ));
+        assert!(matches!(
+            reloaded_or_restarted,
+            Err(SystemdError::IsolateModeUnavailable)
+        ));
+        assert!(matches!(enqueued, Err(SystemdError::IsolateNotAllowed(_))));
+    }
+
     #[test]
     fn can_use_manager_proxy_directly() {
         let system_ctl = SystemCtlBlockingBuilder::new()
@@ -375,6 +1316,19 @@ mod tests {
         assert!(state.is_ok());
     }
 
+    #[test]
+    fn can_wait_until_running() {
+        let system_ctl = SystemCtlBlockingBuilder::new()
+            .init()
+            .expect("Should be able to init connection");
+
+        let running_state = system_ctl
+            .wait_until_running(Duration::from_secs(5))
+            .expect("Should be able to get system state");
+
+        assert!(!running_state.state.is_booting());
+    }
+
     #[test]
     fn can_list_units() {
         let system_ctl = SystemCtlBlockingBuilder::new()
@@ -389,4 +1343,43 @@ mod tests {
 
         assert!(!units.is_empty());
     }
+
+    #[test]
+    fn can_read_logging_settings() {
+        let system_ctl = SystemCtlBlockingBuilder::new()
+            .init()
+            .expect("Should be able to init connection");
+
+        let level = system_ctl
+            .log_level()
+            .expect("Should be able to read the log level");
+        let target = system_ctl
+            .log_target()
+            .expect("Should be able to read the log target");
+
+        assert!(!matches!(level, LogLevel::Other(_)));
+        assert!(!matches!(target, LogTarget::Other(_)));
+    }
+
+    #[test]
+    fn can_run_commands_one_after_another() {
+        let system_ctl = SystemCtlBlockingBuilder::new()
+            .init()
+            .expect("Should be able to init connection");
+
+        // Each command subscribes while it runs, which must not get in the way of the next one
+        for _ in 0..2 {
+            let result = system_ctl
+                .run_command(TransientCommand::new(&["/bin/true"]))
+                .expect("Should be able to start the command")
+                .wait_blocking()
+                .expect("Should be able to wait for the command");
+
+            assert_eq!(result.exit_status, Some(run::ExitStatus::Exited(0)));
+        }
+
+        system_ctl
+            .wait_until_running(Duration::from_secs(5))
+            .expect("Should be able to wait after running commands");
+    }
 }
@@ -0,0 +1,64 @@
+//! A scratch directory that stands in for the root file system in tests of the code that reads
+//! and writes unit files below an alternate root.
+
+use std::{
+    env, fs,
+    os::unix::fs::symlink,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use super::{connection_level::ConnectionLevel, offline::OfflineSystemCtl};
+
+/// Tests run in parallel, so every root gets a directory of its own
+static NEXT_ROOT: AtomicUsize = AtomicUsize::new(0);
+
+/// A temporary root directory, removed again when dropped.
+pub(crate) struct TestRoot(pub(crate) PathBuf);
+
+impl TestRoot {
+    pub(crate) fn new(name: &str) -> Self {
+        let root = env::temp_dir().join(format!(
+            "systemdzbus-{}-{}-{name}",
+            std::process::id(),
+            NEXT_ROOT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).expect("Should be able to create the test root");
+        Self(root)
+    }
+
+    /// Writes a file below the root, creating its parent directories.
+    pub(crate) fn file(&self, path: &str, contents: &str) -> &Self {
+        let path = self.0.join(path);
+        fs::create_dir_all(path.parent().expect("Test paths have a parent"))
+            .expect("Should be able to create the directory");
+        fs::write(path, contents).expect("Should be able to write the file");
+        self
+    }
+
+    /// Creates a symlink below the root, creating its parent directories.
+    pub(crate) fn link(&self, path: &str, target: &str) -> &Self {
+        let path = self.0.join(path);
+        fs::create_dir_all(path.parent().expect("Test paths have a parent"))
+            .expect("Should be able to create the directory");
+        symlink(target, path).expect("Should be able to create the link");
+        self
+    }
+
+    /// Writes a vendor unit file to /usr/lib/systemd/system/.
+    pub(crate) fn unit(&self, name: &str, contents: &str) -> &Self {
+        self.file(&format!("usr/lib/systemd/system/{name}"), contents)
+    }
+
+    /// Manages the unit files of the system manager below this root.
+    pub(crate) fn systemctl(&self) -> OfflineSystemCtl {
+        OfflineSystemCtl::new(&self.0, ConnectionLevel::SystemLevel)
+    }
+}
+
+impl Drop for TestRoot {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
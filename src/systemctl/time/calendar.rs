@@ -0,0 +1,703 @@
+use std::{
+    fmt::Display,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use super::{
+    CivilDateTime, WEEKDAYS, days_in_month,
+    timestamp::{expand_year, parse_weekday},
+    tz::{TimeZone, from_seconds},
+};
+use crate::errors::SystemdError;
+
+// NOTE: These docs are from the man page of systemd.time, under Calendar Events
+
+const MIN_YEAR: u32 = 1970;
+const MAX_YEAR: u32 = 2199;
+/// Seconds are kept in microseconds, since they may have up to six decimal places
+const USEC_PER_SEC: u32 = 1_000_000;
+
+/// One comma separated value of a calendar event component, like "5", "1..5", "1..5/2" or "0/15".
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+struct ComponentValue {
+    start: u32,
+    /// The last value of a range
+    stop: Option<u32>,
+    /// The value, and every repeat after it up to the end of the range
+    repeat: Option<u32>,
+}
+
+impl ComponentValue {
+    fn matches(&self, value: u32) -> bool {
+        if value < self.start || self.stop.is_some_and(|stop| value > stop) {
+            return false;
+        }
+
+        match self.repeat {
+            Some(repeat) => (value - self.start).is_multiple_of(repeat),
+            None => self.stop.is_some() || value == self.start,
+        }
+    }
+
+    /// The first matching value at or after `from`.
+    fn next(&self, from: u32) -> Option<u32> {
+        if from <= self.start {
+            return Some(self.start);
+        }
+
+        let next = match self.repeat {
+            Some(repeat) => {
+                let steps = (from - self.start).div_ceil(repeat);
+                self.start.checked_add(steps.checked_mul(repeat)?)?
+            }
+            None if self.stop.is_some() => from,
+            None => return None,
+        };
+        self.stop.is_none_or(|stop| next <= stop).then_some(next)
+    }
+}
+
+/// The year, month, day, hour, minute or second of a calendar event. No values means "*",
+/// which matches everything.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+struct Component {
+    values: Vec<ComponentValue>,
+}
+
+impl Component {
+    fn exact(value: u32) -> Self {
+        Self {
+            values: vec![ComponentValue {
+                start: value,
+                stop: None,
+                repeat: None,
+            }],
+        }
+    }
+
+    fn parse(value: &str, min: u32, max: u32) -> Option<Self> {
+        Self::parse_scaled(value, min, max, 1)
+    }
+
+    /// Parses seconds into microseconds, like "5", "0.5" or "0/2.5". A "*" matches every whole
+    /// second rather than every microsecond.
+    fn parse_seconds(value: &str) -> Option<Self> {
+        if value == "*" {
+            return Some(Self {
+                values: vec![ComponentValue {
+                    start: 0,
+                    stop: None,
+                    repeat: Some(USEC_PER_SEC),
+                }],
+            });
+        }
+
+        Self::parse_scaled(value, 0, 60 * USEC_PER_SEC - 1, USEC_PER_SEC)
+    }
+
+    /// Parses a component where each unit is divided into `scale` parts, which are written as
+    /// decimal places.
+    fn parse_scaled(value: &str, min: u32, max: u32, scale: u32) -> Option<Self> {
+        if value == "*" {
+            return Some(Self::default());
+        }
+
+        let mut values = value
+            .split(',')
+            .map(|item| {
+                let (range, repeat) = match item.split_once('/') {
+                    Some((range, repeat)) => (
+                        range,
+                        Some(parse_number(repeat, scale).filter(|repeat| *repeat > 0)?),
+                    ),
+                    None => (item, None),
+                };
+                let (start, stop) = match range.split_once("..") {
+                    Some((start, stop)) => (start, Some(stop)),
+                    None => (range, None),
+                };
+
+                // Like systemd, "*/2" is refused rather than repeating from the first value
+                let start = parse_number(start, scale)?;
+                let stop = match stop {
+                    Some(stop) => Some(parse_number(stop, scale)?),
+                    None => None,
+                };
+
+                let in_range = |value: u32| (min..=max).contains(&value);
+                (in_range(start) && stop.is_none_or(|stop| in_range(stop) && start <= stop))
+                    .then_some(ComponentValue {
+                        start,
+                        stop,
+                        repeat,
+                    })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        values.sort();
+        values.dedup();
+        Some(Self { values })
+    }
+
+    fn matches(&self, value: u32) -> bool {
+        self.values.is_empty() || self.values.iter().any(|item| item.matches(value))
+    }
+
+    /// The first matching value from `from` up to `max`.
+    fn next(&self, from: u32, max: u32) -> Option<u32> {
+        if self.values.is_empty() {
+            return (from <= max).then_some(from);
+        }
+
+        self.values
+            .iter()
+            .filter_map(|value| value.next(from))
+            .min()
+            .filter(|next| *next <= max)
+    }
+
+    fn write(&self, f: &mut std::fmt::Formatter<'_>, width: usize) -> std::fmt::Result {
+        self.write_scaled(f, width, 1)
+    }
+
+    fn write_scaled(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        width: usize,
+        scale: u32,
+    ) -> std::fmt::Result {
+        let every_unit = ComponentValue {
+            start: 0,
+            stop: None,
+            repeat: Some(scale),
+        };
+        if self.values.is_empty() || (scale > 1 && self.values == [every_unit]) {
+            return f.write_str("*");
+        }
+
+        for (index, value) in self.values.iter().enumerate() {
+            if index > 0 {
+                f.write_str(",")?;
+            }
+            write_number(f, value.start, width, scale)?;
+            if let Some(stop) = value.stop {
+                f.write_str("..")?;
+                write_number(f, stop, width, scale)?;
+            }
+            if let Some(repeat) = value.repeat {
+                f.write_str("/")?;
+                write_number(f, repeat, 0, scale)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a number with as many decimal places as `scale` has, in units of 1/scale.
+fn parse_number(value: &str, scale: u32) -> Option<u32> {
+    let places = scale.ilog10() as usize;
+    let (whole, fraction) = match value.split_once('.') {
+        Some((whole, fraction)) if places > 0 => (whole, fraction),
+        Some(_) => return None,
+        None => (value, ""),
+    };
+    if fraction.len() > places || !fraction.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let fraction = if places > 0 {
+        format!("{fraction:0<places$}").parse().ok()?
+    } else {
+        0
+    };
+    whole
+        .parse::<u32>()
+        .ok()?
+        .checked_mul(scale)?
+        .checked_add(fraction)
+}
+
+/// Writes a number in units of 1/scale, with decimal places only if it has a fraction.
+fn write_number(
+    f: &mut std::fmt::Formatter<'_>,
+    value: u32,
+    width: usize,
+    scale: u32,
+) -> std::fmt::Result {
+    write!(f, "{:0width$}", value / scale)?;
+    if !value.is_multiple_of(scale) {
+        let places = scale.ilog10() as usize;
+        write!(f, ".{:0places$}", value % scale)?;
+    }
+
+    Ok(())
+}
+
+/// Microseconds since the epoch.
+fn to_micros(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_micros() as i64,
+        Err(before) => -(before.duration().as_micros() as i64),
+    }
+}
+
+fn from_micros(micros: i64) -> SystemTime {
+    let per_second = i64::from(USEC_PER_SEC);
+    from_seconds(micros.div_euclid(per_second))
+        + Duration::from_micros(micros.rem_euclid(per_second) as u64)
+}
+
+/// A calendar event expression as used by OnCalendar= in timer units, like
+/// "Mon..Fri *-*-* 09:00:00" or "weekly". It is parsed from a string, and displays in the
+/// normalized form `systemd-analyze calendar` shows.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CalendarSpec {
+    /// A bit per day of the week from Monday to Sunday, all days if None
+    weekdays: Option<u8>,
+    year: Component,
+    month: Component,
+    day: Component,
+    /// Days are counted from the end of the month, written as "~" instead of "-"
+    end_of_month: bool,
+    hour: Component,
+    minute: Component,
+    second: Component,
+    /// The time zone the event is in, the one given when evaluating it if None
+    zone: Option<TimeZone>,
+}
+
+impl CalendarSpec {
+    /// The first time the event elapses after `after`, with wall clock times in `zone` unless
+    /// the event names its own time zone. None if it never elapses again.
+    pub fn next_elapse(&self, after: SystemTime, zone: &TimeZone) -> Option<SystemTime> {
+        let zone = self.zone.as_ref().unwrap_or(zone);
+        let per_second = i64::from(USEC_PER_SEC);
+        let after = to_micros(after);
+        let first = (after + 1).div_euclid(per_second);
+        // Microseconds since the epoch of the wall clock time to search from
+        let mut from = after + 1 + zone.offset_seconds_at(first) * per_second;
+
+        // Wall clock times repeated when it is turned back may be before `after`
+        loop {
+            let (civil, usec) = self.next_civil(
+                CivilDateTime::from_seconds(from.div_euclid(per_second)),
+                from.rem_euclid(per_second) as u32,
+            )?;
+            let local = civil.to_seconds() * per_second + i64::from(usec);
+            let elapse = zone.local_to_utc(civil.to_seconds()) * per_second + i64::from(usec);
+            if elapse > after {
+                return Some(from_micros(elapse));
+            }
+            from = local + 1;
+        }
+    }
+
+    /// The next `count` times the event elapses after `after`, equivalent to
+    /// `systemd-analyze calendar --iterations=count`.
+    pub fn next_elapses(
+        &self,
+        after: SystemTime,
+        zone: &TimeZone,
+        count: usize,
+    ) -> Vec<SystemTime> {
+        let mut elapses = Vec::with_capacity(count);
+        let mut after = after;
+
+        while elapses.len() < count {
+            let Some(elapse) = self.next_elapse(after, zone) else {
+                break;
+            };
+            elapses.push(elapse);
+            after = elapse;
+        }
+
+        elapses
+    }
+
+    /// The time zone named in the event, if any.
+    pub fn zone(&self) -> Option<&TimeZone> {
+        self.zone.as_ref()
+    }
+
+    fn matches_day(&self, year: i32, month: u32, day: u32) -> bool {
+        let date = CivilDateTime::new(year, month, day, 0, 0, 0);
+        let day = if self.end_of_month {
+            days_in_month(year, month) - day + 1
+        } else {
+            day
+        };
+
+        self.day.matches(day)
+            && self
+                .weekdays
+                .is_none_or(|weekdays| weekdays & (1 << date.weekday()) != 0)
+    }
+
+    /// The first wall clock time at or after `from` and `usec` microseconds that matches, along
+    /// with the microseconds into its second.
+    fn next_civil(&self, mut from: CivilDateTime, usec: u32) -> Option<(CivilDateTime, u32)> {
+        let start = from;
+        let start_of = |year, month, day, hour, minute| {
+            // Normalizes overflowing values, like hour 24 or month 13
+            let (year, month) = if month > 12 {
+                (year + 1, 1)
+            } else {
+                (year, month)
+            };
+            let date = CivilDateTime::new(year, month, 1, 0, 0, 0);
+            CivilDateTime::from_seconds(
+                date.to_seconds()
+                    + (i64::from(day) - 1) * 86_400
+                    + i64::from(hour) * 3600
+                    + i64::from(minute) * 60,
+            )
+        };
+
+        loop {
+            if from.year < MIN_YEAR as i32 {
+                from = CivilDateTime::new(MIN_YEAR as i32, 1, 1, 0, 0, 0);
+            }
+            let year = self.year.next(from.year as u32, MAX_YEAR)? as i32;
+            if year != from.year {
+                from = CivilDateTime::new(year, 1, 1, 0, 0, 0);
+            }
+
+            let Some(month) = self.month.next(from.month, 12) else {
+                from = start_of(from.year, 13, 1, 0, 0);
+                continue;
+            };
+            if month != from.month {
+                from = CivilDateTime::new(from.year, month, 1, 0, 0, 0);
+            }
+
+            let Some(day) = (from.day..=days_in_month(from.year, from.month))
+                .find(|day| self.matches_day(from.year, from.month, *day))
+            else {
+                from = start_of(from.year, from.month + 1, 1, 0, 0);
+                continue;
+            };
+            if day != from.day {
+                from = CivilDateTime::new(from.year, from.month, day, 0, 0, 0);
+            }
+
+            let Some(hour) = self.hour.next(from.hour, 23) else {
+                from = start_of(from.year, from.month, from.day + 1, 0, 0);
+                continue;
+            };
+            if hour != from.hour {
+                from = CivilDateTime::new(from.year, from.month, from.day, hour, 0, 0);
+            }
+
+            let Some(minute) = self.minute.next(from.minute, 59) else {
+                from = start_of(from.year, from.month, from.day, from.hour + 1, 0);
+                continue;
+            };
+            if minute != from.minute {
+                from.minute = minute;
+                from.second = 0;
+            }
+
+            // Only the second searching started in has microseconds that already passed
+            let usec = if from == start { usec } else { 0 };
+            let Some(second) = self
+                .second
+                .next(from.second * USEC_PER_SEC + usec, 60 * USEC_PER_SEC - 1)
+            else {
+                from = start_of(from.year, from.month, from.day, from.hour, from.minute + 1);
+                continue;
+            };
+            from.second = second / USEC_PER_SEC;
+
+            return Some((from, second % USEC_PER_SEC));
+        }
+    }
+
+    fn parse_weekdays(value: &str) -> Option<u8> {
+        let mut weekdays = 0;
+        for item in value.split(',') {
+            let (start, stop) = match item.split_once("..") {
+                Some((start, stop)) => (parse_weekday(start)?, parse_weekday(stop)?),
+                None => (parse_weekday(item)?, parse_weekday(item)?),
+            };
+            if start > stop {
+                return None;
+            }
+            for weekday in start..=stop {
+                weekdays |= 1 << weekday;
+            }
+        }
+
+        Some(weekdays)
+    }
+
+    fn parse_date(&mut self, value: &str) -> Option<()> {
+        let (year_month, day) = match value.rsplit_once('~') {
+            Some((year_month, day)) => {
+                self.end_of_month = true;
+                (year_month, day)
+            }
+            None => value.rsplit_once('-')?,
+        };
+        let (year, month) = match year_month.split_once('-') {
+            Some((year, month)) => (Some(year), month),
+            None => (None, year_month),
+        };
+
+        if let Some(year) = year {
+            self.year = match year.len() {
+                2 if year != "*" => Component::exact(expand_year(year, year.parse().ok()?) as u32),
+                _ => Component::parse(year, MIN_YEAR, MAX_YEAR)?,
+            };
+        }
+        self.month = Component::parse(month, 1, 12)?;
+        self.day = Component::parse(day, 1, 31)?;
+
+        Some(())
+    }
+
+    fn parse_time(&mut self, value: &str) -> Option<()> {
+        let mut parts = value.split(':');
+        self.hour = Component::parse(parts.next()?, 0, 23)?;
+        self.minute = Component::parse(parts.next()?, 0, 59)?;
+        self.second = match parts.next() {
+            Some(second) => Component::parse_seconds(second)?,
+            None => Component::exact(0),
+        };
+
+        parts.next().is_none().then_some(())
+    }
+
+    fn shorthand(value: &str) -> Option<&'static str> {
+        Some(match value {
+            "minutely" => "*-*-* *:*:00",
+            "hourly" => "*-*-* *:00:00",
+            "daily" => "*-*-* 00:00:00",
+            "weekly" => "Mon *-*-* 00:00:00",
+            "monthly" => "*-*-01 00:00:00",
+            "yearly" | "annually" => "*-01-01 00:00:00",
+            "quarterly" => "*-01,04,07,10-01 00:00:00",
+            "semiannually" => "*-01,07-01 00:00:00",
+            _ => return None,
+        })
+    }
+}
+
+impl FromStr for CalendarSpec {
+    type Err = SystemdError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || SystemdError::InvalidCalendarSpec(value.to_string());
+
+        let mut words: Vec<&str> = value.split_whitespace().collect();
+        let mut zone = None;
+        if let Some(last) = words.last().filter(|_| words.len() > 1)
+            && last.starts_with(|c: char| c.is_ascii_alphabetic())
+            && Self::parse_weekdays(last).is_none()
+        {
+            zone = Some(TimeZone::named(last).map_err(|_| invalid())?);
+            words.pop();
+        }
+
+        let expanded;
+        if let [word] = words.as_slice()
+            && let Some(shorthand) = Self::shorthand(word)
+        {
+            expanded = shorthand;
+            words = expanded.split(' ').collect();
+        }
+
+        let mut spec = Self {
+            weekdays: None,
+            year: Component::default(),
+            month: Component::default(),
+            day: Component::default(),
+            end_of_month: false,
+            hour: Component::exact(0),
+            minute: Component::exact(0),
+            second: Component::exact(0),
+            zone,
+        };
+
+        if let Some(first) = words.first()
+            && first.starts_with(|c: char| c.is_ascii_alphabetic())
+        {
+            spec.weekdays = Some(Self::parse_weekdays(first).ok_or_else(invalid)?);
+            words.remove(0);
+        }
+
+        let parsed = match words.as_slice() {
+            [date, time] => spec.parse_date(date).and_then(|_| spec.parse_time(time)),
+            [time] if time.contains(':') => spec.parse_time(time),
+            [date] => spec.parse_date(date),
+            [] if spec.weekdays.is_some() => Some(()),
+            _ => None,
+        };
+        parsed.ok_or_else(invalid)?;
+
+        Ok(spec)
+    }
+}
+
+impl Display for CalendarSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(weekdays) = self.weekdays {
+            // Runs of three or more days are written as ranges, like systemd does
+            let mut runs: Vec<(usize, usize)> = Vec::new();
+            for weekday in (0..7).filter(|weekday| weekdays & (1 << weekday) != 0) {
+                match runs.last_mut() {
+                    Some((_, stop)) if *stop + 1 == weekday => *stop = weekday,
+                    _ => runs.push((weekday, weekday)),
+                }
+            }
+
+            let mut days = Vec::new();
+            for (start, stop) in runs {
+                if stop - start >= 2 {
+                    days.push(format!("{}..{}", WEEKDAYS[start], WEEKDAYS[stop]));
+                } else {
+                    days.extend((start..=stop).map(|weekday| WEEKDAYS[weekday].to_string()));
+                }
+            }
+            write!(f, "{} ", days.join(","))?;
+        }
+
+        self.year.write(f, 4)?;
+        f.write_str("-")?;
+        self.month.write(f, 2)?;
+        f.write_str(if self.end_of_month { "~" } else { "-" })?;
+        self.day.write(f, 2)?;
+        f.write_str(" ")?;
+        self.hour.write(f, 2)?;
+        f.write_str(":")?;
+        self.minute.write(f, 2)?;
+        f.write_str(":")?;
+        self.second.write_scaled(f, 2, USEC_PER_SEC)?;
+
+        if let Some(zone) = &self.zone {
+            write!(f, " {}", zone.name())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalized(value: &str) -> Option<String> {
+        value
+            .parse::<CalendarSpec>()
+            .ok()
+            .map(|spec| spec.to_string())
+    }
+
+    #[test]
+    fn normalizes_calendar_events() {
+        let cases = [
+            ("Mon..Fri *-*-* 09:00:00", "Mon..Fri *-*-* 09:00:00"),
+            ("Sat,Sun 10:30", "Sat,Sun *-*-* 10:30:00"),
+            ("Mon,Tue,Wed,Fri 1:2", "Mon..Wed,Fri *-*-* 01:02:00"),
+            ("*:0/15", "*-*-* *:00/15:00"),
+            ("*-1/2-1", "*-01/2-01 00:00:00"),
+            ("*:*:0.5", "*-*-* *:*:00.500000"),
+            ("*:*:0/2.25", "*-*-* *:*:00/2.250000"),
+            ("*:*:*", "*-*-* *:*:*"),
+            ("12-10 1..5:00", "*-12-10 01..05:00:00"),
+            ("2003-03-05", "2003-03-05 00:00:00"),
+            ("05-03-05 03:05", "2005-03-05 03:05:00"),
+            ("*-02~01", "*-02~01 00:00:00"),
+            ("daily", "*-*-* 00:00:00"),
+            ("weekly", "Mon *-*-* 00:00:00"),
+            ("quarterly", "*-01,04,07,10-01 00:00:00"),
+            ("*-*-* 12:00 UTC", "*-*-* 12:00:00 UTC"),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(normalized(value).as_deref(), Some(expected), "{value}");
+        }
+
+        for value in [
+            "",
+            "*-13-01",
+            "25:00",
+            "Mon..Foo",
+            "*:0/0",
+            "1..",
+            "*-*-* 1:2:3:4",
+            "*-*/2-1",
+            "*:*/5",
+            "*:*:0.1234567",
+            "*:0.5",
+        ] {
+            assert_eq!(normalized(value), None, "{value}");
+        }
+    }
+
+    #[test]
+    fn computes_next_elapses() {
+        let utc = TimeZone::utc();
+        // Fri 2025-01-31 10:00:00 UTC
+        let now = from_seconds(1_738_317_600);
+        let elapses = |value: &str, count| {
+            let spec: CalendarSpec = value.parse().expect("Valid calendar event");
+            spec.next_elapses(now, &utc, count)
+                .into_iter()
+                .map(|time| utc.to_civil(time))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            elapses("Mon..Fri *-*-* 09:00:00", 3),
+            vec![
+                CivilDateTime::new(2025, 2, 3, 9, 0, 0),
+                CivilDateTime::new(2025, 2, 4, 9, 0, 0),
+                CivilDateTime::new(2025, 2, 5, 9, 0, 0),
+            ]
+        );
+        assert_eq!(
+            elapses("*-*~01", 2),
+            vec![
+                CivilDateTime::new(2025, 2, 28, 0, 0, 0),
+                CivilDateTime::new(2025, 3, 31, 0, 0, 0),
+            ]
+        );
+        assert_eq!(
+            elapses("*:0/20", 3),
+            vec![
+                CivilDateTime::new(2025, 1, 31, 10, 20, 0),
+                CivilDateTime::new(2025, 1, 31, 10, 40, 0),
+                CivilDateTime::new(2025, 1, 31, 11, 0, 0),
+            ]
+        );
+        assert_eq!(
+            elapses("*-02-29 12:00", 1),
+            vec![CivilDateTime::new(2028, 2, 29, 12, 0, 0)]
+        );
+        assert!(elapses("2020-01-01", 1).is_empty());
+
+        let spec: CalendarSpec = "*:*:0/0.5".parse().expect("Valid calendar event");
+        let elapses: Vec<_> = spec
+            .next_elapses(now, &utc, 3)
+            .into_iter()
+            .map(to_micros)
+            .collect();
+        assert_eq!(
+            elapses,
+            vec![
+                1_738_317_600_500_000,
+                1_738_317_601_000_000,
+                1_738_317_601_500_000
+            ]
+        );
+
+        let fixed = TimeZone::fixed(3600);
+        let spec: CalendarSpec = "daily".parse().expect("Valid calendar event");
+        assert_eq!(
+            spec.next_elapse(now, &fixed).map(|time| utc.to_civil(time)),
+            Some(CivilDateTime::new(2025, 1, 31, 23, 0, 0))
+        );
+    }
+}
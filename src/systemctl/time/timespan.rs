@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use crate::errors::SystemdError;
+
+// NOTE: The units are from the man page of systemd.time, under Parsing Time Spans. The first
+// name of each unit is the one used when formatting.
+
+const TIMESPAN_UNITS: [(&[&str], u64); 9] = [
+    (&["us", "usec", "μs"], 1),
+    (&["ms", "msec"], 1_000),
+    (&["s", "sec", "second", "seconds"], 1_000_000),
+    (&["min", "m", "minute", "minutes"], 60_000_000),
+    (&["h", "hr", "hour", "hours"], 3_600_000_000),
+    (&["d", "day", "days"], 86_400_000_000),
+    (&["w", "week", "weeks"], 604_800_000_000),
+    (&["month", "M", "months"], 2_629_800_000_000),
+    (&["y", "year", "years"], 31_557_600_000_000),
+];
+
+/// Parses a time span like "5min 30s" or "1.5h". Numbers without a unit are seconds, and
+/// "infinity" is Duration::MAX.
+pub fn parse_timespan(value: &str) -> Result<Duration, SystemdError> {
+    parse_micros(value).ok_or_else(|| SystemdError::InvalidTimespan(value.to_string()))
+}
+
+fn parse_micros(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if value == "infinity" {
+        return Some(Duration::MAX);
+    }
+
+    let mut total: u64 = 0;
+    let mut rest = value;
+    if rest.is_empty() {
+        return None;
+    }
+
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_end].parse().ok()?;
+        rest = rest[number_end..].trim_start();
+
+        let unit_end = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let unit = &rest[..unit_end];
+        rest = rest[unit_end..].trim_start();
+
+        let usec = if unit.is_empty() {
+            1_000_000
+        } else {
+            TIMESPAN_UNITS
+                .iter()
+                .find(|(names, _)| names.contains(&unit))?
+                .1
+        };
+        total = total.checked_add((number * usec as f64).round() as u64)?;
+    }
+
+    Some(Duration::from_micros(total))
+}
+
+/// Formats a time span the way systemd does, e.g. "1min 30s". Duration::MAX is "infinity".
+pub fn format_timespan(value: Duration) -> String {
+    if value == Duration::MAX {
+        return "infinity".to_string();
+    }
+
+    let mut usec = value.as_micros().min(u64::MAX as u128) as u64;
+    if usec == 0 {
+        return "0".to_string();
+    }
+
+    let mut parts = Vec::new();
+    for (names, unit) in TIMESPAN_UNITS.iter().rev() {
+        if usec >= *unit {
+            parts.push(format!("{}{}", usec / unit, names[0]));
+            usec %= unit;
+        }
+    }
+
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_formats_timespans() {
+        let parse = |value| parse_timespan(value).ok();
+
+        assert_eq!(parse("30"), Some(Duration::from_secs(30)));
+        assert_eq!(parse("5min 30s"), Some(Duration::from_secs(330)));
+        assert_eq!(parse("1.5h"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse("2min10ms"), Some(Duration::from_millis(120_010)));
+        assert_eq!(parse("infinity"), Some(Duration::MAX));
+        assert_eq!(parse("5 parsecs"), None);
+        assert_eq!(parse(""), None);
+
+        assert_eq!(format_timespan(Duration::from_secs(330)), "5min 30s");
+        assert_eq!(format_timespan(Duration::from_millis(1500)), "1s 500ms");
+        assert_eq!(format_timespan(Duration::from_secs(90_000)), "1d 1h");
+        assert_eq!(format_timespan(Duration::MAX), "infinity");
+    }
+}
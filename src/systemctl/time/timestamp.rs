@@ -0,0 +1,300 @@
+use std::time::{Duration, SystemTime};
+
+use super::{
+    CivilDateTime, WEEKDAYS, days_in_month,
+    timespan::parse_timespan,
+    tz::{TimeZone, from_seconds},
+};
+use crate::errors::SystemdError;
+
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Parses a timestamp as described in "man systemd.time", like "2012-11-23 11:12:13",
+/// "Fri 2012-11-23 11:12", "11:12", "tomorrow", "+3h30min", "5min ago" or "@1395716396".
+/// Relative timestamps are relative to `now`, and wall clock times are in `zone` unless the
+/// timestamp ends in a time zone of its own, like "UTC", "Europe/Berlin" or "+02:00".
+pub fn parse_timestamp(
+    value: &str,
+    now: SystemTime,
+    zone: &TimeZone,
+) -> Result<SystemTime, SystemdError> {
+    let invalid = || SystemdError::InvalidTimestamp(value.to_string());
+    let value = value.trim();
+
+    let relative = |span: &str, forward: bool| {
+        let span = parse_timespan(span).map_err(|_| invalid())?;
+        if forward {
+            now.checked_add(span)
+        } else {
+            now.checked_sub(span)
+        }
+        .ok_or_else(invalid)
+    };
+
+    if let Some(span) = value.strip_prefix('+') {
+        return relative(span, true);
+    }
+    if let Some(span) = value.strip_prefix('-') {
+        return relative(span, false);
+    }
+    if let Some(span) = value.strip_suffix(" ago") {
+        return relative(span, false);
+    }
+    if let Some(span) = value.strip_suffix(" left") {
+        return relative(span, true);
+    }
+    if let Some(epoch) = value.strip_prefix('@') {
+        let (seconds, micros) = parse_seconds(epoch).ok_or_else(invalid)?;
+        return Ok(from_seconds(seconds) + Duration::from_micros(micros));
+    }
+
+    let days_from_today = match value {
+        "now" => return Ok(now),
+        "today" => Some(0),
+        "yesterday" => Some(-1),
+        "tomorrow" => Some(1),
+        _ => None,
+    };
+    if let Some(days) = days_from_today {
+        let today = zone.to_civil(now);
+        let midnight = CivilDateTime::new(today.year, today.month, today.day, 0, 0, 0);
+        let civil = CivilDateTime::from_seconds(midnight.to_seconds() + days * 86_400);
+        return Ok(zone.from_civil(civil));
+    }
+
+    // ISO 8601 style timestamps separate the date and time with a T
+    let value = match value.split_once('T') {
+        Some((date, time))
+            if date.ends_with(|c: char| c.is_ascii_digit())
+                && time.starts_with(|c: char| c.is_ascii_digit()) =>
+        {
+            format!("{date} {time}")
+        }
+        _ => value.to_string(),
+    };
+    let mut words: Vec<&str> = value.split_whitespace().collect();
+
+    let mut own_zone = None;
+    if let Some(last) = words.last_mut()
+        && let Some(time) = last.strip_suffix('Z').filter(|time| time.contains(':'))
+    {
+        *last = time;
+        own_zone = Some(TimeZone::utc());
+    }
+    if own_zone.is_none() && words.len() > 1 {
+        own_zone = words.last().and_then(|last| parse_zone(last));
+        if own_zone.is_some() {
+            words.pop();
+        }
+    }
+    let zone = own_zone.as_ref().unwrap_or(zone);
+
+    let mut weekday = None;
+    if let Some(first) = words.first()
+        && first.starts_with(|c: char| c.is_ascii_alphabetic())
+    {
+        weekday = Some(parse_weekday(first).ok_or_else(invalid)?);
+        words.remove(0);
+    }
+
+    let (date, time) = match words.as_slice() {
+        [date, time] => (Some(*date), Some(*time)),
+        [one] if one.contains(':') => (None, Some(*one)),
+        [one] => (Some(*one), None),
+        _ => return Err(invalid()),
+    };
+
+    let (year, month, day) = match date {
+        Some(date) => parse_date(date).ok_or_else(invalid)?,
+        None => {
+            let today = zone.to_civil(now);
+            (today.year, today.month, today.day)
+        }
+    };
+    let (hour, minute, second, micros) = match time {
+        Some(time) => parse_time(time).ok_or_else(invalid)?,
+        None => (0, 0, 0, 0),
+    };
+
+    let civil = CivilDateTime::new(year, month, day, hour, minute, second);
+    if weekday.is_some_and(|weekday| weekday != civil.weekday()) {
+        return Err(invalid());
+    }
+
+    Ok(zone.from_civil(civil) + Duration::from_micros(micros))
+}
+
+/// Formats a timestamp the way systemd does, like "Fri 2012-11-23 11:12:13 CET".
+pub fn format_timestamp(time: SystemTime, zone: &TimeZone) -> String {
+    let civil = zone.to_civil(time);
+    let abbreviation = match zone.abbreviation_at(time) {
+        "" => "UTC",
+        abbreviation => abbreviation,
+    };
+
+    format!(
+        "{} {:04}-{:02}-{:02} {:02}:{:02}:{:02} {abbreviation}",
+        WEEKDAYS[civil.weekday() as usize],
+        civil.year,
+        civil.month,
+        civil.day,
+        civil.hour,
+        civil.minute,
+        civil.second,
+    )
+}
+
+/// Parses a weekday like "Mon" or "Monday" as 0 for Monday to 6 for Sunday.
+pub(crate) fn parse_weekday(value: &str) -> Option<u32> {
+    (0..7)
+        .find(|index| {
+            WEEKDAYS[*index].eq_ignore_ascii_case(value)
+                || WEEKDAY_NAMES[*index].eq_ignore_ascii_case(value)
+        })
+        .map(|index| index as u32)
+}
+
+/// Two digit years are read like strptime(3) does, 69 to 99 are in the 1900s.
+pub(crate) fn expand_year(year: &str, value: u32) -> i32 {
+    match (year.len(), value) {
+        (2, 69..) => 1900 + value as i32,
+        (2, _) => 2000 + value as i32,
+        _ => value as i32,
+    }
+}
+
+fn parse_zone(value: &str) -> Option<TimeZone> {
+    if matches!(value, "UTC" | "Z") {
+        return Some(TimeZone::utc());
+    }
+
+    if let Some(offset) = value.strip_prefix(['+', '-']) {
+        let digits = offset.replace(':', "");
+        if !(digits.len() == 2 || digits.len() == 4) || !digits.bytes().all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        let hours: i64 = digits[..2].parse().ok()?;
+        let minutes: i64 = digits.get(2..).map_or(Ok(0), str::parse).ok()?;
+        let sign = if value.starts_with('-') { -1 } else { 1 };
+        return Some(TimeZone::fixed(sign * (hours * 3600 + minutes * 60)));
+    }
+
+    if value.starts_with(|c: char| c.is_ascii_alphabetic()) && parse_weekday(value).is_none() {
+        return TimeZone::named(value).ok();
+    }
+
+    None
+}
+
+fn parse_date(value: &str) -> Option<(i32, u32, u32)> {
+    let mut parts = value.split('-');
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() || !matches!(year.len(), 2 | 4) {
+        return None;
+    }
+
+    let year = expand_year(year, year.parse().ok()?);
+    let month: u32 = month
+        .parse()
+        .ok()
+        .filter(|month| (1..=12).contains(month))?;
+    let day: u32 = day.parse().ok()?;
+
+    (1..=days_in_month(year, month))
+        .contains(&day)
+        .then_some((year, month, day))
+}
+
+fn parse_time(value: &str) -> Option<(u32, u32, u32, u64)> {
+    let mut parts = value.split(':');
+    let hour: u32 = parts.next()?.parse().ok().filter(|hour| *hour < 24)?;
+    let minute: u32 = parts.next()?.parse().ok().filter(|minute| *minute < 60)?;
+    let (second, micros) = match parts.next() {
+        Some(second) => {
+            let (second, micros) = parse_seconds(second)?;
+            (
+                u32::try_from(second).ok().filter(|second| *second < 60)?,
+                micros,
+            )
+        }
+        None => (0, 0),
+    };
+
+    parts
+        .next()
+        .is_none()
+        .then_some((hour, minute, second, micros))
+}
+
+/// Parses seconds with an optional fraction of up to six digits, like "13.25".
+fn parse_seconds(value: &str) -> Option<(i64, u64)> {
+    let (seconds, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if seconds.is_empty()
+        || fraction.len() > 6
+        || !seconds
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let micros = format!("{fraction:0<6}").parse().ok()?;
+    Some((seconds.parse().ok()?, micros))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemctl::time::tz::to_seconds;
+
+    #[test]
+    fn parses_absolute_and_relative_timestamps() {
+        let utc = TimeZone::utc();
+        // Fri 2012-11-23 11:12:13 UTC
+        let now = from_seconds(1_353_669_133);
+        let parse = |value| parse_timestamp(value, now, &utc).ok().map(to_seconds);
+
+        assert_eq!(parse("now"), Some(1_353_669_133));
+        assert_eq!(parse("2012-11-23 11:12:13"), Some(1_353_669_133));
+        assert_eq!(parse("Fri 2012-11-23 11:12:13"), Some(1_353_669_133));
+        assert_eq!(parse("2012-11-23T11:12:13Z"), Some(1_353_669_133));
+        assert_eq!(parse("12-11-23 11:12:13"), Some(1_353_669_133));
+        assert_eq!(parse("2012-11-23 13:12:13 +02:00"), Some(1_353_669_133));
+        assert_eq!(parse("11:12:13"), Some(1_353_669_133));
+        assert_eq!(parse("2012-11-23"), Some(1_353_628_800));
+        assert_eq!(parse("today"), Some(1_353_628_800));
+        assert_eq!(parse("tomorrow"), Some(1_353_715_200));
+        assert_eq!(parse("+3h30min"), Some(1_353_681_733));
+        assert_eq!(parse("-5s"), Some(1_353_669_128));
+        assert_eq!(parse("5s ago"), Some(1_353_669_128));
+        assert_eq!(parse("@1395716396"), Some(1_395_716_396));
+
+        assert_eq!(parse("Mon 2012-11-23 11:12:13"), None);
+        assert_eq!(parse("2012-02-30"), None);
+        assert_eq!(parse("25:00"), None);
+    }
+
+    #[test]
+    fn formats_timestamps() {
+        let time = from_seconds(1_353_669_133) + Duration::from_micros(250);
+
+        assert_eq!(
+            format_timestamp(time, &TimeZone::utc()),
+            "Fri 2012-11-23 11:12:13 UTC"
+        );
+        assert_eq!(
+            format_timestamp(time, &TimeZone::fixed(-5 * 3600)),
+            "Fri 2012-11-23 06:12:13 -05:00"
+        );
+    }
+}
@@ -0,0 +1,679 @@
+use std::{
+    env, fs,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use super::{CivilDateTime, SECONDS_PER_DAY, days_from_civil, days_in_month};
+use crate::errors::SystemdError;
+
+const ZONEINFO_DIR: &str = "/usr/share/zoneinfo";
+const LOCALTIME_PATH: &str = "/etc/localtime";
+
+/// The offset from UTC along with its abbreviation, like "CEST", that applies at some time.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct LocalTimeType {
+    /// Seconds east of UTC
+    offset: i64,
+    is_dst: bool,
+    abbreviation: String,
+}
+
+/// A day of the year in a POSIX TZ rule.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum RuleDate {
+    /// "Jn", from 1 to 365, where February 29th is never counted
+    Julian(u32),
+    /// "n", from 0 to 365, where February 29th is counted in leap years
+    DayOfYear(u32),
+    /// "Mm.w.d", day d (0 is Sunday) of week w (5 is the last) of month m
+    MonthWeekDay { month: u32, week: u32, weekday: u32 },
+}
+
+impl RuleDate {
+    /// Days since the epoch of this date in the given year.
+    fn days(self, year: i32) -> i64 {
+        let new_year = days_from_civil(year, 1, 1);
+        match self {
+            RuleDate::Julian(day) => {
+                let leap_day = super::is_leap_year(year) && day >= 60;
+                new_year + i64::from(day) - 1 + i64::from(leap_day)
+            }
+            RuleDate::DayOfYear(day) => new_year + i64::from(day),
+            RuleDate::MonthWeekDay {
+                month,
+                week,
+                weekday,
+            } => {
+                let first = days_from_civil(year, month, 1);
+                // Sunday based, 1970-01-01 was a Thursday
+                let first_weekday = (first + 4).rem_euclid(7) as u32;
+                let mut day = 1 + (weekday + 7 - first_weekday) % 7 + (week - 1) * 7;
+                while day > days_in_month(year, month) {
+                    day -= 7;
+                }
+                first + i64::from(day) - 1
+            }
+        }
+    }
+}
+
+/// Daylight saving time as described by the rule part of a POSIX TZ string.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct DstRule {
+    dst: LocalTimeType,
+    start: RuleDate,
+    /// Seconds after midnight local standard time
+    start_time: i64,
+    end: RuleDate,
+    /// Seconds after midnight local daylight saving time
+    end_time: i64,
+}
+
+/// A POSIX TZ string like "CET-1CEST,M3.5.0,M10.5.0/3", used by TZif files for times after
+/// their last transition.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct PosixTz {
+    std: LocalTimeType,
+    dst: Option<DstRule>,
+}
+
+impl PosixTz {
+    fn parse(value: &str) -> Option<Self> {
+        let mut rest = value;
+        let std_abbreviation = take_abbreviation(&mut rest)?;
+        let std_offset = -take_time(&mut rest)?;
+        let std = LocalTimeType {
+            offset: std_offset,
+            is_dst: false,
+            abbreviation: std_abbreviation,
+        };
+        if rest.is_empty() {
+            return Some(Self { std, dst: None });
+        }
+
+        let dst_abbreviation = take_abbreviation(&mut rest)?;
+        let dst_offset = if rest.is_empty() || rest.starts_with(',') {
+            std_offset + 3600
+        } else {
+            -take_time(&mut rest)?
+        };
+
+        // Without a rule, the US rules are implied
+        let rule = rest.strip_prefix(',').unwrap_or("M3.2.0,M11.1.0");
+        let (start, end) = rule.split_once(',')?;
+        let (start, start_time) = parse_rule_date(start)?;
+        let (end, end_time) = parse_rule_date(end)?;
+
+        Some(Self {
+            std,
+            dst: Some(DstRule {
+                dst: LocalTimeType {
+                    offset: dst_offset,
+                    is_dst: true,
+                    abbreviation: dst_abbreviation,
+                },
+                start,
+                start_time,
+                end,
+                end_time,
+            }),
+        })
+    }
+
+    fn local_time_type(&self, seconds: i64) -> &LocalTimeType {
+        let Some(rule) = &self.dst else {
+            return &self.std;
+        };
+
+        let year = CivilDateTime::from_seconds(seconds + self.std.offset).year;
+        let start = rule.start.days(year) * SECONDS_PER_DAY + rule.start_time - self.std.offset;
+        let end = rule.end.days(year) * SECONDS_PER_DAY + rule.end_time - rule.dst.offset;
+
+        let in_dst = if start < end {
+            start <= seconds && seconds < end
+        } else {
+            // Southern hemisphere, daylight saving time spans the new year
+            !(end <= seconds && seconds < start)
+        };
+
+        if in_dst { &rule.dst } else { &self.std }
+    }
+}
+
+fn take_abbreviation(rest: &mut &str) -> Option<String> {
+    let (abbreviation, remaining) = if let Some(quoted) = rest.strip_prefix('<') {
+        let (abbreviation, remaining) = quoted.split_once('>')?;
+        (abbreviation, remaining)
+    } else {
+        let end = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        rest.split_at(end)
+    };
+
+    if abbreviation.len() < 3 {
+        return None;
+    }
+
+    *rest = remaining;
+    Some(abbreviation.to_string())
+}
+
+/// Takes "[+|-]hh[:mm[:ss]]" as seconds.
+fn take_time(rest: &mut &str) -> Option<i64> {
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit() && !matches!(c, ':' | '+' | '-'))
+        .unwrap_or(rest.len());
+    let (time, remaining) = rest.split_at(end);
+    *rest = remaining;
+    parse_time(time)
+}
+
+fn parse_time(time: &str) -> Option<i64> {
+    let (sign, time) = match time.as_bytes().first()? {
+        b'-' => (-1, &time[1..]),
+        b'+' => (1, &time[1..]),
+        _ => (1, time),
+    };
+
+    let mut seconds = 0;
+    for (index, part) in time.split(':').enumerate() {
+        if index > 2 || part.is_empty() {
+            return None;
+        }
+        let value: i64 = part.parse().ok()?;
+        seconds += value * [3600, 60, 1][index];
+    }
+
+    Some(sign * seconds)
+}
+
+fn parse_rule_date(value: &str) -> Option<(RuleDate, i64)> {
+    let (date, time) = match value.split_once('/') {
+        Some((date, time)) => (date, parse_time(time)?),
+        None => (value, 7200),
+    };
+
+    let date = if let Some(day) = date.strip_prefix('J') {
+        RuleDate::Julian(day.parse().ok().filter(|day| (1..=365).contains(day))?)
+    } else if let Some(month_week_day) = date.strip_prefix('M') {
+        let mut parts = month_week_day
+            .split('.')
+            .map(|part| part.parse::<u32>().ok());
+        let (month, week, weekday) = (parts.next()??, parts.next()??, parts.next()??);
+        if parts.next().is_some()
+            || !(1..=12).contains(&month)
+            || !(1..=5).contains(&week)
+            || weekday > 6
+        {
+            return None;
+        }
+        RuleDate::MonthWeekDay {
+            month,
+            week,
+            weekday,
+        }
+    } else {
+        RuleDate::DayOfYear(date.parse().ok().filter(|day| *day <= 365)?)
+    };
+
+    Some((date, time))
+}
+
+/// A time zone from the time zone database, used to convert between wall clock time and
+/// points in time.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TimeZone {
+    name: String,
+    /// The times, in seconds since the epoch, at which the local time type changes
+    transitions: Vec<(i64, usize)>,
+    types: Vec<LocalTimeType>,
+    rule: Option<PosixTz>,
+}
+
+impl TimeZone {
+    /// Coordinated Universal Time.
+    pub fn utc() -> Self {
+        Self {
+            name: "UTC".to_string(),
+            transitions: Vec::new(),
+            types: Vec::new(),
+            rule: None,
+        }
+    }
+
+    /// A fixed offset from UTC in seconds, positive east of Greenwich.
+    pub fn fixed(offset: i64) -> Self {
+        let sign = if offset < 0 { '-' } else { '+' };
+        let minutes = offset.abs() / 60;
+        let name = format!("{sign}{:02}:{:02}", minutes / 60, minutes % 60);
+
+        Self {
+            name: name.clone(),
+            transitions: Vec::new(),
+            types: vec![LocalTimeType {
+                offset,
+                is_dst: false,
+                abbreviation: name,
+            }],
+            rule: None,
+        }
+    }
+
+    /// Loads a time zone by its name in the time zone database, like "Europe/Berlin".
+    pub fn named(name: &str) -> Result<Self, SystemdError> {
+        let unknown = || SystemdError::UnknownTimeZone(name.to_string());
+        if name.is_empty()
+            || name.starts_with('/')
+            || name.split('/').any(|component| component == "..")
+        {
+            return Err(unknown());
+        }
+
+        match fs::read(Path::new(ZONEINFO_DIR).join(name)) {
+            Ok(data) => Self::from_tzif(name, &data).ok_or_else(unknown),
+            Err(_) if matches!(name, "UTC" | "GMT" | "Etc/UTC") => Ok(Self::utc()),
+            Err(_) => Err(unknown()),
+        }
+    }
+
+    /// The local time zone of this system, from $TZ or /etc/localtime. Falls back to UTC if
+    /// neither is usable, just like the C library.
+    pub fn local() -> Self {
+        if let Ok(tz) = env::var("TZ").map(|tz| tz.trim_start_matches(':').to_string()) {
+            if tz.is_empty() {
+                return Self::utc();
+            }
+            if tz.starts_with('/') {
+                if let Some(zone) = fs::read(&tz)
+                    .ok()
+                    .and_then(|data| Self::from_tzif(&tz, &data))
+                {
+                    return zone;
+                }
+            } else if let Ok(zone) = Self::named(&tz) {
+                return zone;
+            } else if let Some(zone) = Self::from_posix(&tz) {
+                return zone;
+            }
+            return Self::utc();
+        }
+
+        // The name is only known when /etc/localtime links into the database
+        let name = fs::read_link(LOCALTIME_PATH)
+            .ok()
+            .and_then(|target| {
+                let target = target.to_string_lossy().into_owned();
+                target
+                    .split_once("zoneinfo/")
+                    .map(|(_, name)| name.to_string())
+            })
+            .unwrap_or_else(|| "localtime".to_string());
+
+        fs::read(LOCALTIME_PATH)
+            .ok()
+            .and_then(|data| Self::from_tzif(&name, &data))
+            .unwrap_or_else(Self::utc)
+    }
+
+    /// The name this time zone was loaded with.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The offset from UTC in seconds at the given time, positive east of Greenwich.
+    pub fn offset_at(&self, time: SystemTime) -> i64 {
+        self.offset_seconds_at(to_seconds(time))
+    }
+
+    /// The wall clock time in this time zone at the given time.
+    pub fn to_civil(&self, time: SystemTime) -> CivilDateTime {
+        let seconds = to_seconds(time);
+        CivilDateTime::from_seconds(seconds + self.local_time_type(seconds).offset)
+    }
+
+    /// The point in time a wall clock time in this time zone refers to. When the wall clock
+    /// is turned back, the first occurrence is used. Wall clock times skipped when it is
+    /// turned forward are interpreted with the offset before the change, so they end up
+    /// after it, like mktime(3) does.
+    pub fn from_civil(&self, civil: CivilDateTime) -> SystemTime {
+        from_seconds(self.local_to_utc(civil.to_seconds()))
+    }
+
+    /// The abbreviation of the time zone at the given time, like "CET" or "CEST".
+    pub fn abbreviation_at(&self, time: SystemTime) -> &str {
+        &self.local_time_type(to_seconds(time)).abbreviation
+    }
+
+    pub(crate) fn offset_seconds_at(&self, seconds: i64) -> i64 {
+        self.local_time_type(seconds).offset
+    }
+
+    pub(crate) fn local_to_utc(&self, local: i64) -> i64 {
+        let before = self.offset_seconds_at(local - SECONDS_PER_DAY);
+        let after = self.offset_seconds_at(local + SECONDS_PER_DAY);
+
+        [before, after]
+            .into_iter()
+            .find(|offset| self.offset_seconds_at(local - offset) == *offset)
+            .map(|offset| local - offset)
+            .unwrap_or(local - before)
+    }
+
+    fn local_time_type(&self, seconds: i64) -> &LocalTimeType {
+        static UTC: LocalTimeType = LocalTimeType {
+            offset: 0,
+            is_dst: false,
+            abbreviation: String::new(),
+        };
+
+        let index = self
+            .transitions
+            .partition_point(|(transition, _)| *transition <= seconds);
+
+        match (index, &self.rule) {
+            (0, _) if !self.transitions.is_empty() => self
+                .types
+                .iter()
+                .find(|local_time_type| !local_time_type.is_dst)
+                .unwrap_or(&self.types[0]),
+            (index, Some(rule)) if index == self.transitions.len() => rule.local_time_type(seconds),
+            (0, None) => self.types.first().unwrap_or(&UTC),
+            (index, _) => &self.types[self.transitions[index - 1].1],
+        }
+    }
+
+    fn from_posix(tz: &str) -> Option<Self> {
+        Some(Self {
+            name: tz.to_string(),
+            transitions: Vec::new(),
+            types: Vec::new(),
+            rule: Some(PosixTz::parse(tz)?),
+        })
+    }
+
+    /// Parses a TZif file as described in RFC 8536.
+    fn from_tzif(name: &str, data: &[u8]) -> Option<Self> {
+        let mut reader = TzifReader { data, position: 0 };
+        let mut header = reader.header()?;
+        let mut time_size = 4;
+
+        if header.version >= b'2' {
+            reader.take(header.data_len(4))?;
+            header = reader.header()?;
+            time_size = 8;
+        }
+
+        // The counts come from the file, so make sure it holds that much data before allocating
+        if header.data_len(time_size) > reader.data.len() - reader.position {
+            return None;
+        }
+
+        let mut transition_times = Vec::with_capacity(header.time_count);
+        for _ in 0..header.time_count {
+            let bytes = reader.take(time_size)?;
+            transition_times.push(if time_size == 8 {
+                i64::from_be_bytes(bytes.try_into().ok()?)
+            } else {
+                i64::from(i32::from_be_bytes(bytes.try_into().ok()?))
+            });
+        }
+        let type_indices = reader.take(header.time_count)?.to_vec();
+
+        let mut raw_types = Vec::with_capacity(header.type_count);
+        for _ in 0..header.type_count {
+            let bytes = reader.take(6)?;
+            let offset = i32::from_be_bytes(bytes[..4].try_into().ok()?);
+            raw_types.push((offset, bytes[4] != 0, usize::from(bytes[5])));
+        }
+        let chars = reader.take(header.char_count)?;
+        reader.take(header.leap_count * (time_size + 4) + header.std_count + header.ut_count)?;
+
+        let types = raw_types
+            .into_iter()
+            .map(|(offset, is_dst, abbreviation_index)| {
+                let abbreviation = chars.get(abbreviation_index..)?;
+                let end = abbreviation.iter().position(|c| *c == 0)?;
+                Some(LocalTimeType {
+                    offset: i64::from(offset),
+                    is_dst,
+                    abbreviation: String::from_utf8_lossy(&abbreviation[..end]).into_owned(),
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let transitions = transition_times
+            .into_iter()
+            .zip(type_indices)
+            .map(|(time, index)| (time, usize::from(index)))
+            .collect::<Vec<_>>();
+        if types.is_empty() || transitions.iter().any(|(_, index)| *index >= types.len()) {
+            return None;
+        }
+
+        let rule = if time_size == 8 {
+            let footer = reader.data.get(reader.position..)?;
+            std::str::from_utf8(footer)
+                .ok()
+                .and_then(|footer| footer.strip_prefix('\n')?.split('\n').next())
+                .filter(|footer| !footer.is_empty())
+                .and_then(PosixTz::parse)
+        } else {
+            None
+        };
+
+        Some(Self {
+            name: name.to_string(),
+            transitions,
+            types,
+            rule,
+        })
+    }
+}
+
+struct TzifHeader {
+    version: u8,
+    ut_count: usize,
+    std_count: usize,
+    leap_count: usize,
+    time_count: usize,
+    type_count: usize,
+    char_count: usize,
+}
+
+impl TzifHeader {
+    fn data_len(&self, time_size: usize) -> usize {
+        self.time_count * (time_size + 1)
+            + self.type_count * 6
+            + self.char_count
+            + self.leap_count * (time_size + 4)
+            + self.std_count
+            + self.ut_count
+    }
+}
+
+struct TzifReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> TzifReader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self
+            .data
+            .get(self.position..self.position.checked_add(len)?)?;
+        self.position += len;
+        Some(bytes)
+    }
+
+    fn header(&mut self) -> Option<TzifHeader> {
+        let header = self.take(44)?;
+        if &header[..4] != b"TZif" {
+            return None;
+        }
+
+        let count = |index: usize| {
+            let start = 20 + index * 4;
+            u32::from_be_bytes(header[start..start + 4].try_into().unwrap_or_default()) as usize
+        };
+
+        Some(TzifHeader {
+            version: header[4],
+            ut_count: count(0),
+            std_count: count(1),
+            leap_count: count(2),
+            time_count: count(3),
+            type_count: count(4),
+            char_count: count(5),
+        })
+    }
+}
+
+/// Whole seconds since the epoch, rounded down.
+pub(crate) fn to_seconds(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_secs() as i64,
+        Err(before) => {
+            let before = before.duration();
+            -(before.as_secs() as i64) - i64::from(before.subsec_nanos() > 0)
+        }
+    }
+}
+
+pub(crate) fn from_seconds(seconds: i64) -> SystemTime {
+    if seconds >= 0 {
+        UNIX_EPOCH + Duration::from_secs(seconds as u64)
+    } else {
+        UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(zone: &TimeZone, civil: CivilDateTime) -> (i64, String) {
+        let time = zone.from_civil(civil);
+        (zone.offset_at(time), zone.abbreviation_at(time).to_string())
+    }
+
+    #[test]
+    fn follows_posix_daylight_saving_rules() {
+        let berlin = TimeZone::from_posix("CET-1CEST,M3.5.0,M10.5.0/3").expect("Valid rule");
+
+        let winter = CivilDateTime::new(2025, 1, 15, 12, 0, 0);
+        let summer = CivilDateTime::new(2025, 7, 15, 12, 0, 0);
+        assert_eq!(at(&berlin, winter), (3600, "CET".to_string()));
+        assert_eq!(at(&berlin, summer), (7200, "CEST".to_string()));
+
+        // 2025-03-30 02:30 does not exist, it is read as 03:30 CEST
+        let skipped = berlin.from_civil(CivilDateTime::new(2025, 3, 30, 2, 30, 0));
+        assert_eq!(
+            berlin.to_civil(skipped),
+            CivilDateTime::new(2025, 3, 30, 3, 30, 0)
+        );
+
+        // 2025-10-26 02:30 happens twice, the first one is in CEST
+        let repeated = CivilDateTime::new(2025, 10, 26, 2, 30, 0);
+        assert_eq!(at(&berlin, repeated), (7200, "CEST".to_string()));
+
+        let sydney = TimeZone::from_posix("AEST-10AEDT,M10.1.0,M4.1.0/3").expect("Valid rule");
+        assert_eq!(at(&sydney, winter), (39600, "AEDT".to_string()));
+        assert_eq!(at(&sydney, summer), (36000, "AEST".to_string()));
+    }
+
+    /// The offset in effect one second before and at the given UTC time.
+    fn around(zone: &TimeZone, utc: CivilDateTime) -> (i64, i64) {
+        let seconds = utc.to_seconds();
+        (
+            zone.offset_seconds_at(seconds - 1),
+            zone.offset_seconds_at(seconds),
+        )
+    }
+
+    #[test]
+    fn switches_offsets_at_the_transitions() {
+        let berlin = TimeZone::from_posix("CET-1CEST,M3.5.0,M10.5.0/3").expect("Valid rule");
+
+        // 02:00 CET and 03:00 CEST, both at 01:00 UTC
+        assert_eq!(
+            around(&berlin, CivilDateTime::new(2025, 3, 30, 1, 0, 0)),
+            (3600, 7200)
+        );
+        assert_eq!(
+            around(&berlin, CivilDateTime::new(2025, 10, 26, 1, 0, 0)),
+            (7200, 3600)
+        );
+
+        // The wall clock goes from 02:59:59 CEST back to 02:00:00 CET
+        let turned_back = CivilDateTime::new(2025, 10, 26, 1, 0, 0).to_seconds();
+        assert_eq!(
+            berlin.to_civil(from_seconds(turned_back - 1)),
+            CivilDateTime::new(2025, 10, 26, 2, 59, 59)
+        );
+        assert_eq!(
+            berlin.to_civil(from_seconds(turned_back)),
+            CivilDateTime::new(2025, 10, 26, 2, 0, 0)
+        );
+    }
+
+    #[test]
+    fn follows_southern_hemisphere_rules() {
+        let sydney = TimeZone::from_posix("AEST-10AEDT,M10.1.0,M4.1.0/3").expect("Valid rule");
+
+        // Daylight saving time ends on the first Sunday in April at 03:00 AEDT and starts on the
+        // first Sunday in October at 02:00 AEST
+        assert_eq!(
+            around(&sydney, CivilDateTime::new(2025, 4, 5, 16, 0, 0)),
+            (39600, 36000)
+        );
+        assert_eq!(
+            around(&sydney, CivilDateTime::new(2025, 10, 4, 16, 0, 0)),
+            (36000, 39600)
+        );
+
+        // It lasts over the new year
+        let new_year = sydney.from_civil(CivilDateTime::new(2026, 1, 1, 0, 0, 0));
+        assert_eq!(sydney.offset_at(new_year), 39600);
+        assert_eq!(sydney.abbreviation_at(new_year), "AEDT");
+
+        // 2025-10-05 02:30 does not exist, it is read as 03:30 AEDT
+        let skipped = sydney.from_civil(CivilDateTime::new(2025, 10, 5, 2, 30, 0));
+        assert_eq!(
+            sydney.to_civil(skipped),
+            CivilDateTime::new(2025, 10, 5, 3, 30, 0)
+        );
+
+        // West of Greenwich, with quoted abbreviations and transitions at 24:00
+        let santiago = TimeZone::from_posix("<-04>4<-03>,M9.1.6/24,M4.1.6/24").expect("Valid rule");
+        assert_eq!(
+            around(&santiago, CivilDateTime::new(2025, 4, 6, 3, 0, 0)),
+            (-10800, -14400)
+        );
+        assert_eq!(
+            around(&santiago, CivilDateTime::new(2025, 9, 7, 4, 0, 0)),
+            (-14400, -10800)
+        );
+        let summer = santiago.from_civil(CivilDateTime::new(2025, 12, 24, 12, 0, 0));
+        assert_eq!(santiago.abbreviation_at(summer), "-03");
+    }
+
+    #[test]
+    fn rejects_truncated_tzif_data() {
+        let mut data = b"TZif".to_vec();
+        data.resize(44, 0);
+        data[32..36].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(TimeZone::from_tzif("Broken", &data).is_none());
+    }
+
+    #[test]
+    fn rejects_time_zone_names_outside_the_database() {
+        assert!(TimeZone::named("../../etc/passwd").is_err());
+        assert!(TimeZone::named("/etc/localtime").is_err());
+        assert_eq!(
+            TimeZone::utc().to_civil(UNIX_EPOCH),
+            CivilDateTime::new(1970, 1, 1, 0, 0, 0)
+        );
+    }
+}
@@ -0,0 +1,469 @@
+//! Transient units are created at runtime through StartTransientUnit() instead of being loaded
+//! from unit files, like `systemd-run` does. They are released once they are no longer running
+//! or referenced.
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use zbus::zvariant::{OwnedObjectPath, Value};
+
+use crate::{
+    ManagerProxy,
+    errors::SystemdError,
+    systemctl::{
+        time::calendar::CalendarSpec,
+        unit::UnitMode,
+        unit_name::{UnitName, UnitType},
+    },
+};
+
+/// Unit properties as passed to StartTransientUnit(), in the same format as
+/// SetUnitProperties().
+pub(crate) type Properties = Vec<(&'static str, Value<'static>)>;
+
+/// A transient unit to create, along with its properties.
+pub(crate) struct TransientUnit {
+    pub(crate) name: UnitName,
+    pub(crate) properties: Properties,
+}
+
+/// Creates and starts the unit. The auxiliary units are created along with it, but not started,
+/// e.g. the service a timer activates.
+pub(crate) async fn start(
+    manager_proxy: &ManagerProxy<'_>,
+    unit: &TransientUnit,
+    aux: &[TransientUnit],
+    mode: UnitMode,
+) -> Result<OwnedObjectPath, SystemdError> {
+    let properties = borrow(&unit.properties);
+    let properties: Vec<_> = properties.iter().collect();
+
+    let aux_properties: Vec<_> = aux.iter().map(|unit| borrow(&unit.properties)).collect();
+    let aux_properties: Vec<Vec<_>> = aux_properties
+        .iter()
+        .map(|properties| properties.iter().collect())
+        .collect();
+    let aux: Vec<_> = aux
+        .iter()
+        .zip(&aux_properties)
+        .map(|(unit, properties)| (unit.name.as_str(), properties.as_slice()))
+        .collect();
+    let aux: Vec<_> = aux.iter().collect();
+
+    Ok(manager_proxy
+        .start_transient_unit(unit.name.as_str(), &mode.to_string(), &properties, &aux)
+        .await?)
+}
+
+fn borrow(properties: &Properties) -> Vec<(&str, &Value<'static>)> {
+    properties
+        .iter()
+        .map(|(name, value)| (*name, value))
+        .collect()
+}
+
+/// A command line as an ExecStart= property, "a(sasb)" of the path, the arguments including
+/// the path and whether failures are ignored.
+pub(crate) fn exec_command(command: &[String]) -> Value<'static> {
+    Value::from(vec![(command[0].clone(), command.to_vec(), false)])
+}
+
+/// Time spans are sent in microseconds, where Duration::MAX is infinity.
+pub(crate) fn usec(value: Duration) -> u64 {
+    value.as_micros().try_into().unwrap_or(u64::MAX)
+}
+
+/// A transient timer together with the service it activates, equivalent to e.g.
+/// `systemd-run --on-calendar=... --unit=name command`. This schedules a command without writing
+/// any unit files. Both units share the name, e.g. "backup.timer" and "backup.service".
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TransientTimer {
+    name: String,
+    command: Vec<String>,
+    description: Option<String>,
+    on_calendar: Vec<CalendarSpec>,
+    on_active: Vec<Duration>,
+    on_boot: Vec<Duration>,
+    on_unit_active: Vec<Duration>,
+    randomized_delay: Option<Duration>,
+    accuracy: Option<Duration>,
+    persistent: Option<bool>,
+}
+
+impl TransientTimer {
+    /// Creates a timer named after `name`, which does not include the unit type suffix, that
+    /// runs `command`. The first element of the command is the executable.
+    pub fn new(name: &str, command: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            command: command.iter().map(ToString::to_string).collect(),
+            description: None,
+            on_calendar: Vec::new(),
+            on_active: Vec::new(),
+            on_boot: Vec::new(),
+            on_unit_active: Vec::new(),
+            randomized_delay: None,
+            accuracy: None,
+            persistent: None,
+        }
+    }
+
+    /// Describes both units, the command line is used if not set.
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Elapses whenever the calendar event does, like OnCalendar=.
+    pub fn with_on_calendar(mut self, spec: CalendarSpec) -> Self {
+        self.on_calendar.push(spec);
+        self
+    }
+
+    /// Elapses this long after the timer was started, like OnActiveSec=.
+    pub fn with_on_active(mut self, after: Duration) -> Self {
+        self.on_active.push(after);
+        self
+    }
+
+    /// Elapses this long after the machine was booted, like OnBootSec=.
+    pub fn with_on_boot(mut self, after: Duration) -> Self {
+        self.on_boot.push(after);
+        self
+    }
+
+    /// Elapses this long after the service was last activated, like OnUnitActiveSec=. Together
+    /// with with_on_active() this runs the command repeatedly.
+    pub fn with_on_unit_active(mut self, after: Duration) -> Self {
+        self.on_unit_active.push(after);
+        self
+    }
+
+    /// Delays each elapse by a random time up to this long, like RandomizedDelaySec=, to spread
+    /// out the load of timers that would otherwise elapse at the same time.
+    pub fn with_randomized_delay(mut self, delay: Duration) -> Self {
+        self.randomized_delay = Some(delay);
+        self
+    }
+
+    /// How precisely the timer elapses, like AccuracySec=. systemd defaults to one minute, so
+    /// that wake-ups can be coalesced.
+    pub fn with_accuracy(mut self, accuracy: Duration) -> Self {
+        self.accuracy = Some(accuracy);
+        self
+    }
+
+    /// Whether a calendar event that was missed while the machine was off triggers the service
+    /// once the timer is started again, like Persistent=.
+    pub fn with_persistent(mut self, persistent: bool) -> Self {
+        self.persistent = Some(persistent);
+        self
+    }
+
+    /// The name of the timer unit, e.g. "backup.timer".
+    pub fn timer_name(&self) -> Result<UnitName, SystemdError> {
+        UnitName::new(format!("{}.timer", self.name))
+    }
+
+    /// The name of the service unit the timer activates, e.g. "backup.service".
+    pub fn service_name(&self) -> Result<UnitName, SystemdError> {
+        UnitName::new(format!("{}.service", self.name))
+    }
+
+    /// The timer unit and the service it activates.
+    pub(crate) fn units(&self) -> Result<(TransientUnit, TransientUnit), SystemdError> {
+        let timer_name = self.timer_name()?;
+        let invalid = |reason| SystemdError::InvalidTransientUnit {
+            unit: timer_name.to_string(),
+            reason,
+        };
+
+        if self.command.is_empty() {
+            return Err(invalid("no command to run"));
+        }
+        if self.on_calendar.is_empty()
+            && self.on_active.is_empty()
+            && self.on_boot.is_empty()
+            && self.on_unit_active.is_empty()
+        {
+            return Err(invalid("no time to elapse at"));
+        }
+
+        let description = self
+            .description
+            .clone()
+            .unwrap_or_else(|| self.command.join(" "));
+
+        let monotonic = [
+            ("OnActiveSec", &self.on_active),
+            ("OnBootSec", &self.on_boot),
+            ("OnUnitActiveSec", &self.on_unit_active),
+        ]
+        .into_iter()
+        .flat_map(|(base, list)| {
+            list.iter()
+                .map(move |after| (base.to_string(), usec(*after)))
+        })
+        .collect::<Vec<_>>();
+        let calendar = self
+            .on_calendar
+            .iter()
+            .map(|spec| ("OnCalendar".to_string(), spec.to_string()))
+            .collect::<Vec<_>>();
+
+        let mut timer_properties: Properties =
+            vec![("Description", Value::from(description.clone()))];
+        if !monotonic.is_empty() {
+            timer_properties.push(("TimersMonotonic", Value::from(monotonic)));
+        }
+        if !calendar.is_empty() {
+            timer_properties.push(("TimersCalendar", Value::from(calendar)));
+        }
+        if let Some(delay) = self.randomized_delay {
+            timer_properties.push(("RandomizedDelayUSec", Value::from(usec(delay))));
+        }
+        if let Some(accuracy) = self.accuracy {
+            timer_properties.push(("AccuracyUSec", Value::from(usec(accuracy))));
+        }
+        if let Some(persistent) = self.persistent {
+            timer_properties.push(("Persistent", Value::from(persistent)));
+        }
+
+        let service = TransientUnit {
+            name: self.service_name()?,
+            properties: vec![
+                ("Description", Value::from(description)),
+                ("ExecStart", exec_command(&self.command)),
+            ],
+        };
+        let timer = TransientUnit {
+            name: timer_name,
+            properties: timer_properties,
+        };
+
+        Ok((timer, service))
+    }
+}
+
+/// A transient mount, equivalent to `systemd-mount`. Unlike calling mount(8), the mount gets the
+/// lifecycle of a unit: it is ordered against other units, can be stopped to unmount it and
+/// shows up in `systemctl list-units`. The unit is named after the mount point, e.g.
+/// "mnt-data.mount" for "/mnt/data".
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TransientMount {
+    what: String,
+    mount_point: PathBuf,
+    fs_type: Option<String>,
+    options: Option<String>,
+    description: Option<String>,
+    timeout: Option<Duration>,
+    automount: bool,
+    idle_timeout: Option<Duration>,
+}
+
+impl TransientMount {
+    /// Mounts `what`, e.g. a device node like "/dev/sdb1" or a network share, on `mount_point`.
+    pub fn new(what: &str, mount_point: impl AsRef<Path>) -> Self {
+        Self {
+            what: what.to_string(),
+            mount_point: mount_point.as_ref().to_path_buf(),
+            fs_type: None,
+            options: None,
+            description: None,
+            timeout: None,
+            automount: false,
+            idle_timeout: None,
+        }
+    }
+
+    /// The file system type, e.g. "ext4". Detected automatically if not set.
+    pub fn with_type(mut self, fs_type: &str) -> Self {
+        self.fs_type = Some(fs_type.to_string());
+        self
+    }
+
+    /// Mount options as passed to mount(8) with -o, e.g. "ro,noatime".
+    pub fn with_options(mut self, options: &str) -> Self {
+        self.options = Some(options.to_string());
+        self
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// How long mounting and unmounting may take, like TimeoutSec=.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Creates an automount point instead of mounting right away, equivalent to
+    /// `systemd-mount --automount=yes`. The file system is mounted on first access, and unmounted
+    /// again after being idle for `idle_timeout`, if given.
+    pub fn with_automount(mut self, idle_timeout: Option<Duration>) -> Self {
+        self.automount = true;
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// The name of the mount unit, e.g. "mnt-data.mount".
+    pub fn mount_name(&self) -> Result<UnitName, SystemdError> {
+        UnitName::from_path(&self.mount_point, UnitType::Mount)
+    }
+
+    /// The name of the automount unit, e.g. "mnt-data.automount".
+    pub fn automount_name(&self) -> Result<UnitName, SystemdError> {
+        UnitName::from_path(&self.mount_point, UnitType::Automount)
+    }
+
+    /// The unit to start, along with the mount unit if that is an automount unit.
+    pub(crate) fn units(&self) -> Result<(TransientUnit, Vec<TransientUnit>), SystemdError> {
+        let mount_name = self.mount_name()?;
+        if self.what.is_empty() {
+            return Err(SystemdError::InvalidTransientUnit {
+                unit: mount_name.to_string(),
+                reason: "nothing to mount",
+            });
+        }
+
+        let mount_point = self.mount_point.to_string_lossy().into_owned();
+        let description = self
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("Mount {} on {mount_point}", self.what));
+
+        let mut properties: Properties = vec![
+            ("Description", Value::from(description.clone())),
+            ("What", Value::from(self.what.clone())),
+            ("Where", Value::from(mount_point.clone())),
+        ];
+        if let Some(fs_type) = &self.fs_type {
+            properties.push(("Type", Value::from(fs_type.clone())));
+        }
+        if let Some(options) = &self.options {
+            properties.push(("Options", Value::from(options.clone())));
+        }
+        if let Some(timeout) = self.timeout {
+            properties.push(("TimeoutUSec", Value::from(usec(timeout))));
+        }
+        let mount = TransientUnit {
+            name: mount_name,
+            properties,
+        };
+
+        if !self.automount {
+            return Ok((mount, Vec::new()));
+        }
+
+        let mut properties: Properties = vec![
+            ("Description", Value::from(description)),
+            ("Where", Value::from(mount_point)),
+        ];
+        if let Some(idle_timeout) = self.idle_timeout {
+            properties.push(("TimeoutIdleUSec", Value::from(usec(idle_timeout))));
+        }
+        let automount = TransientUnit {
+            name: self.automount_name()?,
+            properties,
+        };
+
+        Ok((automount, vec![mount]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn property<'a>(unit: &'a TransientUnit, name: &str) -> Option<&'a Value<'static>> {
+        unit.properties
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+
+    #[test]
+    fn builds_timer_and_service_properties() {
+        let timer = TransientTimer::new("backup", &["/usr/bin/rsync", "-a", "/src", "/dst"])
+            .with_on_calendar("daily".parse().expect("Valid calendar event"))
+            .with_on_boot(Duration::from_secs(900))
+            .with_randomized_delay(Duration::from_secs(60))
+            .with_persistent(true);
+
+        let (timer, service) = timer.units().expect("The timer has a trigger");
+
+        assert_eq!(timer.name.as_str(), "backup.timer");
+        assert_eq!(service.name.as_str(), "backup.service");
+        assert_eq!(
+            property(&timer, "TimersCalendar"),
+            Some(&Value::from(vec![(
+                "OnCalendar".to_string(),
+                "*-*-* 00:00:00".to_string()
+            )]))
+        );
+        assert_eq!(
+            property(&timer, "TimersMonotonic"),
+            Some(&Value::from(vec![(
+                "OnBootSec".to_string(),
+                900_000_000u64
+            )]))
+        );
+        assert_eq!(
+            property(&timer, "RandomizedDelayUSec"),
+            Some(&Value::from(60_000_000u64))
+        );
+        assert_eq!(property(&timer, "AccuracyUSec"), None);
+        assert_eq!(
+            property(&service, "Description"),
+            Some(&Value::from("/usr/bin/rsync -a /src /dst"))
+        );
+    }
+
+    #[test]
+    fn builds_mount_and_automount_properties() {
+        let mount = TransientMount::new("/dev/sdb1", "/mnt/data").with_type("ext4");
+
+        let (unit, aux) = mount.units().expect("The mount is valid");
+        assert_eq!(unit.name.as_str(), "mnt-data.mount");
+        assert!(aux.is_empty());
+        assert_eq!(property(&unit, "Type"), Some(&Value::from("ext4")));
+        assert_eq!(property(&unit, "Where"), Some(&Value::from("/mnt/data")));
+
+        let automount = mount.with_automount(Some(Duration::from_secs(300)));
+        let (unit, aux) = automount.units().expect("The mount is valid");
+        assert_eq!(unit.name.as_str(), "mnt-data.automount");
+        assert_eq!(
+            property(&unit, "TimeoutIdleUSec"),
+            Some(&Value::from(300_000_000u64))
+        );
+        assert_eq!(aux[0].name.as_str(), "mnt-data.mount");
+        assert_eq!(property(&aux[0], "What"), Some(&Value::from("/dev/sdb1")));
+
+        assert!(TransientMount::new("/dev/sdb1", "data").units().is_err());
+    }
+
+    #[test]
+    fn rejects_timers_that_never_elapse() {
+        assert!(
+            TransientTimer::new("backup", &["/bin/true"])
+                .units()
+                .is_err()
+        );
+        assert!(
+            TransientTimer::new("backup", &[])
+                .with_on_active(Duration::from_secs(1))
+                .units()
+                .is_err()
+        );
+        assert!(
+            TransientTimer::new("bad/name", &["/bin/true"])
+                .with_on_active(Duration::from_secs(1))
+                .units()
+                .is_err()
+        );
+    }
+}
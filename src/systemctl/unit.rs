@@ -17,6 +17,16 @@ pub enum UnitEnablementResponse {
     AdditionalContext(Vec<UnitEnablementChange>),
 }
 
+impl UnitEnablementResponse {
+    /// The symlinks that were created or removed, if they were reported.
+    pub fn changes(self) -> Vec<UnitEnablementChange> {
+        match self {
+            UnitEnablementResponse::NoContext => Vec::new(),
+            UnitEnablementResponse::AdditionalContext(changes) => changes,
+        }
+    }
+}
+
 impl From<Vec<(String, String, String)>> for UnitEnablementResponse {
     fn from(value: Vec<(String, String, String)>) -> Self {
         UnitEnablementResponse::AdditionalContext(
@@ -0,0 +1,547 @@
+use std::fmt::Display;
+
+use crate::systemctl::unit_config::{
+    AssignError, Section, UnitWriter, absolute_path_value, assign_capabilities, assign_each,
+    assign_list, bool_value, enum_value, mode_value, number_value, parse_bool, string_value,
+};
+
+// NOTE: These docs are from the man pages of systemd.exec, systemd.kill and
+// systemd.resource-control
+
+/// The documented settings of those man pages that are not modeled by ExecSettings.
+const OTHER_KEYS: &[&str] = &[
+    // systemd.exec
+    "ExecSearchPath",
+    "RootImage",
+    "RootImageOptions",
+    "RootEphemeral",
+    "RootHash",
+    "RootHashSignature",
+    "RootVerity",
+    "RootImagePolicy",
+    "MountImagePolicy",
+    "ExtensionImagePolicy",
+    "MountAPIVFS",
+    "BindLogSockets",
+    "BindPaths",
+    "BindReadOnlyPaths",
+    "MountImages",
+    "ExtensionImages",
+    "ExtensionDirectories",
+    "PAMName",
+    "SetLoginEnvironment",
+    "SecureBits",
+    "SELinuxContext",
+    "AppArmorProfile",
+    "SmackProcessLabel",
+    "LimitCPU",
+    "LimitFSIZE",
+    "LimitDATA",
+    "LimitSTACK",
+    "LimitCORE",
+    "LimitRSS",
+    "LimitAS",
+    "LimitNPROC",
+    "LimitMEMLOCK",
+    "LimitLOCKS",
+    "LimitSIGPENDING",
+    "LimitMSGQUEUE",
+    "LimitNICE",
+    "LimitRTPRIO",
+    "LimitRTTIME",
+    "CoredumpFilter",
+    "KeyringMode",
+    "OOMScoreAdjust",
+    "TimerSlackNSec",
+    "Personality",
+    "IgnoreSIGPIPE",
+    "CPUSchedulingPolicy",
+    "CPUSchedulingPriority",
+    "CPUSchedulingResetOnFork",
+    "CPUAffinity",
+    "NUMAPolicy",
+    "NUMAMask",
+    "IOSchedulingClass",
+    "IOSchedulingPriority",
+    "ExecPaths",
+    "NoExecPaths",
+    "TemporaryFileSystem",
+    "PrivateMounts",
+    "PrivatePIDs",
+    "NetworkNamespacePath",
+    "IPCNamespacePath",
+    "MemoryKSM",
+    "MountFlags",
+    "RuntimeDirectoryMode",
+    "StateDirectoryMode",
+    "CacheDirectoryMode",
+    "LogsDirectoryMode",
+    "ConfigurationDirectoryMode",
+    "RuntimeDirectoryPreserve",
+    "SystemCallErrorNumber",
+    "SystemCallLog",
+    "RestrictFileSystems",
+    "UnsetEnvironment",
+    "PassEnvironment",
+    "StandardInputText",
+    "StandardInputData",
+    "LogLevelMax",
+    "LogExtraFields",
+    "LogRateLimitIntervalSec",
+    "LogRateLimitBurst",
+    "LogFilterPatterns",
+    "LogNamespace",
+    "SyslogFacility",
+    "SyslogLevel",
+    "SyslogLevelPrefix",
+    "TTYPath",
+    "TTYReset",
+    "TTYVHangup",
+    "TTYRows",
+    "TTYColumns",
+    "TTYVTDisallocate",
+    "LoadCredential",
+    "LoadCredentialEncrypted",
+    "ImportCredential",
+    "SetCredential",
+    "SetCredentialEncrypted",
+    "UtmpIdentifier",
+    "UtmpMode",
+    // systemd.kill
+    "RestartKillSignal",
+    "SendSIGHUP",
+    "FinalKillSignal",
+    "WatchdogSignal",
+    // systemd.resource-control
+    "CPUAccounting",
+    "StartupCPUWeight",
+    "CPUQuotaPeriodSec",
+    "AllowedCPUs",
+    "StartupAllowedCPUs",
+    "AllowedMemoryNodes",
+    "StartupAllowedMemoryNodes",
+    "MemoryAccounting",
+    "MemoryMin",
+    "MemoryLow",
+    "StartupMemoryLow",
+    "DefaultStartupMemoryLow",
+    "StartupMemoryHigh",
+    "StartupMemoryMax",
+    "MemorySwapMax",
+    "StartupMemorySwapMax",
+    "MemoryZSwapMax",
+    "StartupMemoryZSwapMax",
+    "MemoryZSwapWriteback",
+    "TasksAccounting",
+    "IOAccounting",
+    "StartupIOWeight",
+    "IODeviceWeight",
+    "IOReadBandwidthMax",
+    "IOWriteBandwidthMax",
+    "IOReadIOPSMax",
+    "IOWriteIOPSMax",
+    "IODeviceLatencyTargetSec",
+    "IPAccounting",
+    "IPIngressFilterPath",
+    "IPEgressFilterPath",
+    "SocketBindAllow",
+    "SocketBindDeny",
+    "RestrictNetworkInterfaces",
+    "NFTSet",
+    "BPFProgram",
+    "Slice",
+    "DelegateSubgroup",
+    "DisableControllers",
+    "ManagedOOMSwap",
+    "ManagedOOMMemoryPressure",
+    "ManagedOOMMemoryPressureLimit",
+    "ManagedOOMMemoryPressureDurationSec",
+    "ManagedOOMPreference",
+    "MemoryPressureWatch",
+    "MemoryPressureThresholdSec",
+    "CoredumpReceive",
+];
+
+/// ProtectSystem=, which makes the OS directories read-only for the processes of the unit.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ProtectSystem {
+    No,
+    /// /usr/ and the boot loader directories are read-only
+    Yes,
+    /// Like yes, and /etc/ is read-only as well
+    Full,
+    /// The entire file system hierarchy is read-only, except for the API file systems
+    Strict,
+    Other(String),
+}
+
+impl From<String> for ProtectSystem {
+    fn from(value: String) -> Self {
+        match parse_bool(&value) {
+            Some(false) => return ProtectSystem::No,
+            Some(true) => return ProtectSystem::Yes,
+            None => (),
+        }
+
+        match value.as_ref() {
+            "full" => ProtectSystem::Full,
+            "strict" => ProtectSystem::Strict,
+            _ => ProtectSystem::Other(value),
+        }
+    }
+}
+
+impl Display for ProtectSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            ProtectSystem::No => "no",
+            ProtectSystem::Yes => "yes",
+            ProtectSystem::Full => "full",
+            ProtectSystem::Strict => "strict",
+            ProtectSystem::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+/// ProtectHome=, which hides the home directories from the processes of the unit.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ProtectHome {
+    No,
+    /// /home/, /root/ and /run/user/ are inaccessible and empty
+    Yes,
+    /// /home/, /root/ and /run/user/ are read-only
+    ReadOnly,
+    /// /home/, /root/ and /run/user/ are replaced by empty tmpfs mounts
+    Tmpfs,
+    Other(String),
+}
+
+impl From<String> for ProtectHome {
+    fn from(value: String) -> Self {
+        match parse_bool(&value) {
+            Some(false) => return ProtectHome::No,
+            Some(true) => return ProtectHome::Yes,
+            None => (),
+        }
+
+        match value.as_ref() {
+            "read-only" => ProtectHome::ReadOnly,
+            "tmpfs" => ProtectHome::Tmpfs,
+            _ => ProtectHome::Other(value),
+        }
+    }
+}
+
+impl Display for ProtectHome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            ProtectHome::No => "no",
+            ProtectHome::Yes => "yes",
+            ProtectHome::ReadOnly => "read-only",
+            ProtectHome::Tmpfs => "tmpfs",
+            ProtectHome::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+/// KillMode=, which processes of the unit are killed when it is stopped.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum KillMode {
+    /// All remaining processes in the control group of the unit are killed
+    ControlGroup,
+    /// SIGTERM is sent to the main process, SIGKILL to the whole control group
+    Mixed,
+    /// Only the main process is killed
+    Process,
+    /// No process is killed, only ExecStop= is run
+    None,
+    Other(String),
+}
+
+impl From<String> for KillMode {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "control-group" => KillMode::ControlGroup,
+            "mixed" => KillMode::Mixed,
+            "process" => KillMode::Process,
+            "none" => KillMode::None,
+            _ => KillMode::Other(value),
+        }
+    }
+}
+
+impl Display for KillMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            KillMode::ControlGroup => "control-group",
+            KillMode::Mixed => "mixed",
+            KillMode::Process => "process",
+            KillMode::None => "none",
+            KillMode::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+/// The settings for the processes a unit spawns, shared by services and sockets. This covers the
+/// execution environment, sandboxing, killing and resource control.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ExecSettings {
+    pub user: Option<String>,
+    pub group: Option<String>,
+    /// Whether a user and group are allocated dynamically while the unit is running
+    pub dynamic_user: Option<bool>,
+    pub supplementary_groups: Vec<String>,
+    pub working_directory: Option<String>,
+    pub root_directory: Option<String>,
+    /// Each assignment, which may set several variables, e.g. "A=1 B=2"
+    pub environment: Vec<String>,
+    pub environment_file: Vec<String>,
+    pub umask: Option<String>,
+    pub nice: Option<i32>,
+    pub standard_input: Option<String>,
+    pub standard_output: Option<String>,
+    pub standard_error: Option<String>,
+    pub syslog_identifier: Option<String>,
+    pub limit_nofile: Option<String>,
+    pub runtime_directory: Vec<String>,
+    pub state_directory: Vec<String>,
+    pub cache_directory: Vec<String>,
+    pub logs_directory: Vec<String>,
+    pub configuration_directory: Vec<String>,
+
+    pub protect_system: Option<ProtectSystem>,
+    pub protect_home: Option<ProtectHome>,
+    pub private_tmp: Option<bool>,
+    pub private_devices: Option<bool>,
+    pub private_network: Option<bool>,
+    pub private_users: Option<bool>,
+    pub private_ipc: Option<bool>,
+    pub no_new_privileges: Option<bool>,
+    pub protect_kernel_tunables: Option<bool>,
+    pub protect_kernel_modules: Option<bool>,
+    pub protect_kernel_logs: Option<bool>,
+    pub protect_control_groups: Option<bool>,
+    pub protect_clock: Option<bool>,
+    pub protect_hostname: Option<bool>,
+    /// e.g. "invisible" or "noaccess"
+    pub protect_proc: Option<String>,
+    /// e.g. "pid"
+    pub proc_subset: Option<String>,
+    /// A boolean, or a list of namespace types, optionally prefixed with "~" to invert it
+    pub restrict_namespaces: Option<String>,
+    pub restrict_realtime: Option<bool>,
+    pub restrict_suid_sgid: Option<bool>,
+    pub lock_personality: Option<bool>,
+    pub memory_deny_write_execute: Option<bool>,
+    pub remove_ipc: Option<bool>,
+    pub read_write_paths: Vec<String>,
+    pub read_only_paths: Vec<String>,
+    pub inaccessible_paths: Vec<String>,
+    /// Each assignment, as capabilities are combined across assignments unless prefixed with "~".
+    /// An empty assignment resets to no capabilities and a bare "~" resets to all of them.
+    pub capability_bounding_set: Vec<String>,
+    /// Each assignment, combined like `capability_bounding_set`
+    pub ambient_capabilities: Vec<String>,
+    /// Each assignment, e.g. "AF_UNIX AF_INET" or "none"
+    pub restrict_address_families: Vec<String>,
+    /// Each assignment, e.g. "@system-service" or "~@privileged @resources"
+    pub system_call_filter: Vec<String>,
+    pub system_call_architectures: Vec<String>,
+    /// e.g. "closed" or "strict"
+    pub device_policy: Option<String>,
+    pub device_allow: Vec<String>,
+
+    pub kill_mode: Option<KillMode>,
+    pub kill_signal: Option<String>,
+    pub send_sigkill: Option<bool>,
+
+    pub memory_max: Option<String>,
+    pub memory_high: Option<String>,
+    pub cpu_quota: Option<String>,
+    pub cpu_weight: Option<String>,
+    pub io_weight: Option<String>,
+    pub tasks_max: Option<String>,
+    pub ip_address_allow: Vec<String>,
+    pub ip_address_deny: Vec<String>,
+    pub delegate: Option<String>,
+
+    /// Documented settings that are not modeled, as key and value in the order they are assigned
+    pub other: Vec<(String, String)>,
+}
+
+impl Section for ExecSettings {
+    fn assign(&mut self, key: &str, value: &str) -> Result<(), AssignError> {
+        match key {
+            "User" => self.user = string_value(value),
+            "Group" => self.group = string_value(value),
+            "DynamicUser" => self.dynamic_user = bool_value(value)?,
+            "SupplementaryGroups" => assign_list(&mut self.supplementary_groups, value),
+            "WorkingDirectory" => self.working_directory = string_value(value),
+            "RootDirectory" => self.root_directory = absolute_path_value(value)?,
+            "Environment" => assign_each(&mut self.environment, value),
+            "EnvironmentFile" => assign_each(&mut self.environment_file, value),
+            "UMask" => self.umask = mode_value(value)?,
+            "Nice" => match number_value(value)? {
+                nice @ (Some(-20..=19) | None) => self.nice = nice,
+                _ => return Err(AssignError::InvalidValue("a number from -20 to 19")),
+            },
+            "StandardInput" => self.standard_input = string_value(value),
+            "StandardOutput" => self.standard_output = string_value(value),
+            "StandardError" => self.standard_error = string_value(value),
+            "SyslogIdentifier" => self.syslog_identifier = string_value(value),
+            "LimitNOFILE" => self.limit_nofile = string_value(value),
+            "RuntimeDirectory" => assign_list(&mut self.runtime_directory, value),
+            "StateDirectory" => assign_list(&mut self.state_directory, value),
+            "CacheDirectory" => assign_list(&mut self.cache_directory, value),
+            "LogsDirectory" => assign_list(&mut self.logs_directory, value),
+            "ConfigurationDirectory" => assign_list(&mut self.configuration_directory, value),
+
+            "ProtectSystem" => {
+                self.protect_system = enum_value(
+                    value,
+                    |v| matches!(v, ProtectSystem::Other(_)),
+                    "yes, no, full or strict",
+                )?
+            }
+            "ProtectHome" => {
+                self.protect_home = enum_value(
+                    value,
+                    |v| matches!(v, ProtectHome::Other(_)),
+                    "yes, no, read-only or tmpfs",
+                )?
+            }
+            "PrivateTmp" => self.private_tmp = bool_value(value)?,
+            "PrivateDevices" => self.private_devices = bool_value(value)?,
+            "PrivateNetwork" => self.private_network = bool_value(value)?,
+            "PrivateUsers" => self.private_users = bool_value(value)?,
+            "PrivateIPC" => self.private_ipc = bool_value(value)?,
+            "NoNewPrivileges" => self.no_new_privileges = bool_value(value)?,
+            "ProtectKernelTunables" => self.protect_kernel_tunables = bool_value(value)?,
+            "ProtectKernelModules" => self.protect_kernel_modules = bool_value(value)?,
+            "ProtectKernelLogs" => self.protect_kernel_logs = bool_value(value)?,
+            "ProtectControlGroups" => self.protect_control_groups = bool_value(value)?,
+            "ProtectClock" => self.protect_clock = bool_value(value)?,
+            "ProtectHostname" => self.protect_hostname = bool_value(value)?,
+            "ProtectProc" => self.protect_proc = string_value(value),
+            "ProcSubset" => self.proc_subset = string_value(value),
+            "RestrictNamespaces" => self.restrict_namespaces = string_value(value),
+            "RestrictRealtime" => self.restrict_realtime = bool_value(value)?,
+            "RestrictSUIDSGID" => self.restrict_suid_sgid = bool_value(value)?,
+            "LockPersonality" => self.lock_personality = bool_value(value)?,
+            "MemoryDenyWriteExecute" => self.memory_deny_write_execute = bool_value(value)?,
+            "RemoveIPC" => self.remove_ipc = bool_value(value)?,
+            "ReadWritePaths" => assign_list(&mut self.read_write_paths, value),
+            "ReadOnlyPaths" => assign_list(&mut self.read_only_paths, value),
+            "InaccessiblePaths" => assign_list(&mut self.inaccessible_paths, value),
+            "CapabilityBoundingSet" => {
+                assign_capabilities(&mut self.capability_bounding_set, value)
+            }
+            "AmbientCapabilities" => assign_capabilities(&mut self.ambient_capabilities, value),
+            "RestrictAddressFamilies" => assign_each(&mut self.restrict_address_families, value),
+            "SystemCallFilter" => assign_each(&mut self.system_call_filter, value),
+            "SystemCallArchitectures" => assign_list(&mut self.system_call_architectures, value),
+            "DevicePolicy" => self.device_policy = string_value(value),
+            "DeviceAllow" => assign_each(&mut self.device_allow, value),
+
+            "KillMode" => {
+                self.kill_mode = enum_value(
+                    value,
+                    |v| matches!(v, KillMode::Other(_)),
+                    "control-group, mixed, process or none",
+                )?
+            }
+            "KillSignal" => self.kill_signal = string_value(value),
+            "SendSIGKILL" => self.send_sigkill = bool_value(value)?,
+
+            "MemoryMax" => self.memory_max = string_value(value),
+            "MemoryHigh" => self.memory_high = string_value(value),
+            "CPUQuota" => self.cpu_quota = string_value(value),
+            "CPUWeight" => self.cpu_weight = string_value(value),
+            "IOWeight" => self.io_weight = string_value(value),
+            "TasksMax" => self.tasks_max = string_value(value),
+            "IPAddressAllow" => assign_list(&mut self.ip_address_allow, value),
+            "IPAddressDeny" => assign_list(&mut self.ip_address_deny, value),
+            "Delegate" => self.delegate = string_value(value),
+            key if OTHER_KEYS.contains(&key) => {
+                self.other.push((key.to_string(), value.to_string()))
+            }
+            _ => return Err(AssignError::UnknownKey),
+        }
+
+        Ok(())
+    }
+
+    fn write(&self, writer: &mut UnitWriter) {
+        writer.value("User", self.user.as_ref());
+        writer.value("Group", self.group.as_ref());
+        writer.bool("DynamicUser", self.dynamic_user);
+        writer.list("SupplementaryGroups", &self.supplementary_groups);
+        writer.value("WorkingDirectory", self.working_directory.as_ref());
+        writer.value("RootDirectory", self.root_directory.as_ref());
+        writer.each("Environment", &self.environment);
+        writer.each("EnvironmentFile", &self.environment_file);
+        writer.value("UMask", self.umask.as_ref());
+        writer.value("Nice", self.nice);
+        writer.value("StandardInput", self.standard_input.as_ref());
+        writer.value("StandardOutput", self.standard_output.as_ref());
+        writer.value("StandardError", self.standard_error.as_ref());
+        writer.value("SyslogIdentifier", self.syslog_identifier.as_ref());
+        writer.value("LimitNOFILE", self.limit_nofile.as_ref());
+        writer.list("RuntimeDirectory", &self.runtime_directory);
+        writer.list("StateDirectory", &self.state_directory);
+        writer.list("CacheDirectory", &self.cache_directory);
+        writer.list("LogsDirectory", &self.logs_directory);
+        writer.list("ConfigurationDirectory", &self.configuration_directory);
+
+        writer.value("ProtectSystem", self.protect_system.as_ref());
+        writer.value("ProtectHome", self.protect_home.as_ref());
+        writer.bool("PrivateTmp", self.private_tmp);
+        writer.bool("PrivateDevices", self.private_devices);
+        writer.bool("PrivateNetwork", self.private_network);
+        writer.bool("PrivateUsers", self.private_users);
+        writer.bool("PrivateIPC", self.private_ipc);
+        writer.bool("NoNewPrivileges", self.no_new_privileges);
+        writer.bool("ProtectKernelTunables", self.protect_kernel_tunables);
+        writer.bool("ProtectKernelModules", self.protect_kernel_modules);
+        writer.bool("ProtectKernelLogs", self.protect_kernel_logs);
+        writer.bool("ProtectControlGroups", self.protect_control_groups);
+        writer.bool("ProtectClock", self.protect_clock);
+        writer.bool("ProtectHostname", self.protect_hostname);
+        writer.value("ProtectProc", self.protect_proc.as_ref());
+        writer.value("ProcSubset", self.proc_subset.as_ref());
+        writer.value("RestrictNamespaces", self.restrict_namespaces.as_ref());
+        writer.bool("RestrictRealtime", self.restrict_realtime);
+        writer.bool("RestrictSUIDSGID", self.restrict_suid_sgid);
+        writer.bool("LockPersonality", self.lock_personality);
+        writer.bool("MemoryDenyWriteExecute", self.memory_deny_write_execute);
+        writer.bool("RemoveIPC", self.remove_ipc);
+        writer.list("ReadWritePaths", &self.read_write_paths);
+        writer.list("ReadOnlyPaths", &self.read_only_paths);
+        writer.list("InaccessiblePaths", &self.inaccessible_paths);
+        writer.each("CapabilityBoundingSet", &self.capability_bounding_set);
+        writer.each("AmbientCapabilities", &self.ambient_capabilities);
+        writer.each("RestrictAddressFamilies", &self.restrict_address_families);
+        writer.each("SystemCallFilter", &self.system_call_filter);
+        writer.list("SystemCallArchitectures", &self.system_call_architectures);
+        writer.value("DevicePolicy", self.device_policy.as_ref());
+        writer.each("DeviceAllow", &self.device_allow);
+
+        writer.value("KillMode", self.kill_mode.as_ref());
+        writer.value("KillSignal", self.kill_signal.as_ref());
+        writer.bool("SendSIGKILL", self.send_sigkill);
+
+        writer.value("MemoryMax", self.memory_max.as_ref());
+        writer.value("MemoryHigh", self.memory_high.as_ref());
+        writer.value("CPUQuota", self.cpu_quota.as_ref());
+        writer.value("CPUWeight", self.cpu_weight.as_ref());
+        writer.value("IOWeight", self.io_weight.as_ref());
+        writer.value("TasksMax", self.tasks_max.as_ref());
+        writer.list("IPAddressAllow", &self.ip_address_allow);
+        writer.list("IPAddressDeny", &self.ip_address_deny);
+        writer.value("Delegate", self.delegate.as_ref());
+        writer.pairs(&self.other);
+    }
+}
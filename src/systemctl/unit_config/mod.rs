@@ -0,0 +1,650 @@
+//! Typed models of unit files, which can be parsed from and written to the ini-like unit file
+//! syntax. Documented settings that are not modeled are kept as they are, in the `other` field of
+//! their section. Unknown settings are rejected, except for "X-" extension sections and keys,
+//! which systemd ignores as well. Assigning the empty string resets a setting to its default.
+
+pub mod exec;
+pub mod service;
+pub mod socket;
+pub mod timer;
+
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    errors::SystemdError,
+    systemctl::{
+        ini::{Ini, IniSection},
+        install::InstallInfo,
+        lookup_paths::LookupPaths,
+        time::timespan::{format_timespan, parse_timespan},
+        unit_name::UnitName,
+    },
+};
+
+/// What is wrong with a line of a unit file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ConfigErrorKind {
+    /// A line that is neither a section header, an assignment nor a comment
+    Syntax,
+    /// A section that does not exist in this type of unit file
+    UnknownSection,
+    /// A key that does not exist in its section
+    UnknownKey,
+    /// A value that cannot be parsed, along with a description of what is expected
+    InvalidValue { value: String, expected: String },
+}
+
+/// A problem with a single line of a unit file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ConfigError {
+    /// The line the problem is on, counting from 1
+    pub line: usize,
+    pub section: String,
+    pub key: Option<String>,
+    pub kind: ConfigErrorKind,
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = self.key.as_deref().unwrap_or_default();
+        match &self.kind {
+            ConfigErrorKind::Syntax => write!(f, "line {}: invalid syntax", self.line),
+            ConfigErrorKind::UnknownSection => {
+                write!(f, "line {}: unknown section [{}]", self.line, self.section)
+            }
+            ConfigErrorKind::UnknownKey => write!(
+                f,
+                "line {}: unknown key {key} in section [{}]",
+                self.line, self.section
+            ),
+            ConfigErrorKind::InvalidValue { value, expected } => write!(
+                f,
+                "line {}: invalid value \"{value}\" for {key}, expected {expected}",
+                self.line
+            ),
+        }
+    }
+}
+
+/// Why an assignment was rejected by a section.
+pub(crate) enum AssignError {
+    UnknownKey,
+    /// Describes what is expected instead
+    InvalidValue(&'static str),
+}
+
+/// A section of a unit file, which takes assignments one at a time, like systemd reads them.
+pub(crate) trait Section {
+    fn assign(&mut self, key: &str, value: &str) -> Result<(), AssignError>;
+
+    fn write(&self, writer: &mut UnitWriter);
+}
+
+/// Applies all assignments of a parsed section, collecting the rejected ones as errors.
+pub(crate) fn assign_section(
+    target: &mut dyn Section,
+    section: &IniSection,
+    errors: &mut Vec<ConfigError>,
+) {
+    for entry in &section.entries {
+        if entry.key.starts_with("X-") {
+            continue;
+        }
+
+        let kind = match target.assign(&entry.key, &entry.value) {
+            Ok(()) => continue,
+            Err(AssignError::UnknownKey) => ConfigErrorKind::UnknownKey,
+            Err(AssignError::InvalidValue(expected)) => ConfigErrorKind::InvalidValue {
+                value: entry.value.clone(),
+                expected: expected.to_string(),
+            },
+        };
+
+        errors.push(ConfigError {
+            line: entry.line,
+            section: section.name.clone(),
+            key: Some(entry.key.clone()),
+            kind,
+        });
+    }
+}
+
+/// A whole unit file, made up of sections.
+pub(crate) trait Sections {
+    /// The model of the section with that name, if this type of unit file has it.
+    fn section(&mut self, name: &str) -> Option<&mut dyn Section>;
+}
+
+/// Reads the sections of a unit file into the models of the sections. Errors for syntax errors
+/// and unknown sections are included.
+pub(crate) fn read_sections(ini: &Ini, config: &mut impl Sections) -> Vec<ConfigError> {
+    let mut errors: Vec<ConfigError> = ini
+        .invalid_lines
+        .iter()
+        .map(|(line, _)| ConfigError {
+            line: *line,
+            section: String::new(),
+            key: None,
+            kind: ConfigErrorKind::Syntax,
+        })
+        .collect();
+
+    for section in &ini.sections {
+        if section.name.starts_with("X-") {
+            continue;
+        }
+
+        match config.section(&section.name) {
+            Some(model) => assign_section(model, section, &mut errors),
+            None => errors.push(ConfigError {
+                line: section.line,
+                section: section.name.clone(),
+                key: None,
+                kind: ConfigErrorKind::UnknownSection,
+            }),
+        }
+    }
+
+    errors.sort_by_key(|error| error.line);
+    errors
+}
+
+/// Turns the errors of reading a unit file into the result of parsing it.
+pub(crate) fn into_result<T>(config: T, errors: Vec<ConfigError>) -> Result<T, SystemdError> {
+    if errors.is_empty() {
+        Ok(config)
+    } else {
+        Err(SystemdError::InvalidUnitConfig(errors))
+    }
+}
+
+/// Writes a unit file to the configuration directory of the search path, i.e. /etc/systemd/system/
+/// for the system manager, or its counterpart in /run/ if runtime is set.
+pub(crate) fn write_unit_file(
+    lookup_paths: &LookupPaths,
+    name: &str,
+    contents: &str,
+    runtime: bool,
+) -> Result<PathBuf, SystemdError> {
+    let name = UnitName::new(name)?;
+    let dir: &Path = lookup_paths.config_dir(runtime).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "the unit search path has no configuration directory",
+        )
+    })?;
+
+    fs::create_dir_all(dir)?;
+    let path = dir.join(name.as_str());
+    fs::write(&path, contents)?;
+
+    Ok(path)
+}
+
+// NOTE: These docs are from the man page of systemd.unit, under [Unit] Section Options
+
+/// The documented settings of the [Unit] section that are not modeled by UnitSection.
+const OTHER_UNIT_KEYS: &[&str] = &[
+    "PropagatesReloadTo",
+    "ReloadPropagatedFrom",
+    "PropagatesStopTo",
+    "StopPropagatedFrom",
+    "JoinsNamespaceOf",
+    "RequiresMountsFor",
+    "WantsMountsFor",
+    "OnFailureJobMode",
+    "OnSuccessJobMode",
+    "CollectMode",
+    "FailureAction",
+    "SuccessAction",
+    "FailureActionExitStatus",
+    "SuccessActionExitStatus",
+    "JobRunningTimeoutSec",
+    "JobTimeoutAction",
+    "JobTimeoutRebootArgument",
+    "StartLimitAction",
+    "RebootArgument",
+    "SourcePath",
+    "SurviveFinalKillSignal",
+];
+
+/// What Condition*= and Assert*= settings check, e.g. "PathExists" for ConditionPathExists=.
+const CONDITIONS: &[&str] = &[
+    "Architecture",
+    "Firmware",
+    "Virtualization",
+    "Host",
+    "KernelCommandLine",
+    "KernelVersion",
+    "Credential",
+    "Environment",
+    "Security",
+    "Capability",
+    "ACPower",
+    "NeedsUpdate",
+    "FirstBoot",
+    "PathExists",
+    "PathExistsGlob",
+    "PathIsDirectory",
+    "PathIsSymbolicLink",
+    "PathIsMountPoint",
+    "PathIsReadWrite",
+    "PathIsEncrypted",
+    "DirectoryNotEmpty",
+    "FileNotEmpty",
+    "FileIsExecutable",
+    "User",
+    "Group",
+    "ControlGroupController",
+    "Memory",
+    "CPUs",
+    "CPUFeature",
+    "OSRelease",
+    "MemoryPressure",
+    "CPUPressure",
+    "IOPressure",
+];
+
+fn is_condition(key: &str) -> bool {
+    key.strip_prefix("Condition")
+        .or_else(|| key.strip_prefix("Assert"))
+        .is_some_and(|condition| CONDITIONS.contains(&condition))
+}
+
+/// The [Unit] section, which is shared by all unit types.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct UnitSection {
+    /// A short human readable title of the unit
+    pub description: Option<String>,
+    /// URIs referencing documentation for the unit
+    pub documentation: Vec<String>,
+    pub wants: Vec<String>,
+    pub requires: Vec<String>,
+    pub requisite: Vec<String>,
+    pub binds_to: Vec<String>,
+    pub part_of: Vec<String>,
+    pub upholds: Vec<String>,
+    pub conflicts: Vec<String>,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+    pub on_failure: Vec<String>,
+    pub on_success: Vec<String>,
+    /// Whether the implicit dependencies of the unit type are added, true if not set
+    pub default_dependencies: Option<bool>,
+    pub stop_when_unneeded: Option<bool>,
+    pub refuse_manual_start: Option<bool>,
+    pub refuse_manual_stop: Option<bool>,
+    pub allow_isolate: Option<bool>,
+    pub ignore_on_isolate: Option<bool>,
+    pub job_timeout: Option<Duration>,
+    pub start_limit_interval: Option<Duration>,
+    pub start_limit_burst: Option<u32>,
+    /// Condition*= and Assert*= settings as key and value, in the order they are checked
+    pub conditions: Vec<(String, String)>,
+    /// Documented settings that are not modeled, as key and value in the order they are assigned
+    pub other: Vec<(String, String)>,
+}
+
+impl Section for UnitSection {
+    fn assign(&mut self, key: &str, value: &str) -> Result<(), AssignError> {
+        match key {
+            "Description" => self.description = string_value(value),
+            "Documentation" => assign_list(&mut self.documentation, value),
+            "Wants" => assign_list(&mut self.wants, value),
+            "Requires" => assign_list(&mut self.requires, value),
+            "Requisite" => assign_list(&mut self.requisite, value),
+            "BindsTo" => assign_list(&mut self.binds_to, value),
+            "PartOf" => assign_list(&mut self.part_of, value),
+            "Upholds" => assign_list(&mut self.upholds, value),
+            "Conflicts" => assign_list(&mut self.conflicts, value),
+            "Before" => assign_list(&mut self.before, value),
+            "After" => assign_list(&mut self.after, value),
+            "OnFailure" => assign_list(&mut self.on_failure, value),
+            "OnSuccess" => assign_list(&mut self.on_success, value),
+            "DefaultDependencies" => self.default_dependencies = bool_value(value)?,
+            "StopWhenUnneeded" => self.stop_when_unneeded = bool_value(value)?,
+            "RefuseManualStart" => self.refuse_manual_start = bool_value(value)?,
+            "RefuseManualStop" => self.refuse_manual_stop = bool_value(value)?,
+            "AllowIsolate" => self.allow_isolate = bool_value(value)?,
+            "IgnoreOnIsolate" => self.ignore_on_isolate = bool_value(value)?,
+            "JobTimeoutSec" => self.job_timeout = timespan_value(value)?,
+            "StartLimitIntervalSec" => self.start_limit_interval = timespan_value(value)?,
+            "StartLimitBurst" => self.start_limit_burst = number_value(value)?,
+            key if is_condition(key) => {
+                if value.is_empty() {
+                    // Resets all conditions, or all assertions, not just those of this kind
+                    let prefix = if key.starts_with("Assert") {
+                        "Assert"
+                    } else {
+                        "Condition"
+                    };
+                    self.conditions
+                        .retain(|(condition, _)| !condition.starts_with(prefix));
+                } else {
+                    self.conditions.push((key.to_string(), value.to_string()));
+                }
+            }
+            key if OTHER_UNIT_KEYS.contains(&key) => {
+                self.other.push((key.to_string(), value.to_string()))
+            }
+            _ => return Err(AssignError::UnknownKey),
+        }
+
+        Ok(())
+    }
+
+    fn write(&self, writer: &mut UnitWriter) {
+        writer.section("Unit");
+        writer.value("Description", self.description.as_ref());
+        writer.list("Documentation", &self.documentation);
+        writer.list("Wants", &self.wants);
+        writer.list("Requires", &self.requires);
+        writer.list("Requisite", &self.requisite);
+        writer.list("BindsTo", &self.binds_to);
+        writer.list("PartOf", &self.part_of);
+        writer.list("Upholds", &self.upholds);
+        writer.list("Conflicts", &self.conflicts);
+        writer.list("Before", &self.before);
+        writer.list("After", &self.after);
+        writer.list("OnFailure", &self.on_failure);
+        writer.list("OnSuccess", &self.on_success);
+        writer.bool("DefaultDependencies", self.default_dependencies);
+        writer.bool("StopWhenUnneeded", self.stop_when_unneeded);
+        writer.bool("RefuseManualStart", self.refuse_manual_start);
+        writer.bool("RefuseManualStop", self.refuse_manual_stop);
+        writer.bool("AllowIsolate", self.allow_isolate);
+        writer.bool("IgnoreOnIsolate", self.ignore_on_isolate);
+        writer.timespan("JobTimeoutSec", self.job_timeout);
+        writer.timespan("StartLimitIntervalSec", self.start_limit_interval);
+        writer.value("StartLimitBurst", self.start_limit_burst);
+        writer.pairs(&self.conditions);
+        writer.pairs(&self.other);
+    }
+}
+
+impl Section for InstallInfo {
+    fn assign(&mut self, key: &str, value: &str) -> Result<(), AssignError> {
+        match key {
+            "WantedBy" => assign_list(&mut self.wanted_by, value),
+            "RequiredBy" => assign_list(&mut self.required_by, value),
+            "UpheldBy" => assign_list(&mut self.upheld_by, value),
+            "Alias" => assign_list(&mut self.alias, value),
+            "Also" => assign_list(&mut self.also, value),
+            "DefaultInstance" => {
+                self.default_instance = Some(value.to_string()).filter(|value| !value.is_empty())
+            }
+            _ => return Err(AssignError::UnknownKey),
+        }
+
+        Ok(())
+    }
+
+    fn write(&self, writer: &mut UnitWriter) {
+        if *self == InstallInfo::default() {
+            return;
+        }
+
+        writer.section("Install");
+        writer.list("WantedBy", &self.wanted_by);
+        writer.list("RequiredBy", &self.required_by);
+        writer.list("UpheldBy", &self.upheld_by);
+        writer.list("Alias", &self.alias);
+        writer.list("Also", &self.also);
+        writer.value("DefaultInstance", self.default_instance.as_ref());
+    }
+}
+
+/// Writes sections and assignments in the unit file syntax. Unset settings are skipped.
+#[derive(Debug, Default)]
+pub(crate) struct UnitWriter {
+    contents: String,
+}
+
+impl UnitWriter {
+    pub(crate) fn section(&mut self, name: &str) {
+        if !self.contents.is_empty() {
+            self.contents.push('\n');
+        }
+        self.contents.push_str(&format!("[{name}]\n"));
+    }
+
+    pub(crate) fn value(&mut self, key: &str, value: Option<impl Display>) {
+        if let Some(value) = value {
+            self.contents.push_str(&format!("{key}={value}\n"));
+        }
+    }
+
+    pub(crate) fn bool(&mut self, key: &str, value: Option<bool>) {
+        self.value(key, value.map(|value| if value { "yes" } else { "no" }));
+    }
+
+    pub(crate) fn timespan(&mut self, key: &str, value: Option<Duration>) {
+        self.value(key, value.map(format_timespan));
+    }
+
+    /// A list setting on a single line, separated by spaces.
+    pub(crate) fn list(&mut self, key: &str, values: &[String]) {
+        if !values.is_empty() {
+            self.value(key, Some(values.join(" ")));
+        }
+    }
+
+    /// A setting that is assigned once per value, like ExecStart=.
+    pub(crate) fn each(&mut self, key: &str, values: &[impl Display]) {
+        for value in values {
+            self.value(key, Some(value));
+        }
+    }
+
+    /// Settings kept as key and value, in the order they were assigned.
+    pub(crate) fn pairs(&mut self, values: &[(String, String)]) {
+        for (key, value) in values {
+            self.value(key, Some(value));
+        }
+    }
+
+    pub(crate) fn finish(self) -> String {
+        self.contents
+    }
+}
+
+/// Adds the whitespace separated values to a list setting. Assigning the empty string resets it.
+pub(crate) fn assign_list(list: &mut Vec<String>, value: &str) {
+    if value.is_empty() {
+        list.clear();
+    } else {
+        list.extend(value.split_whitespace().map(ToString::to_string));
+    }
+}
+
+/// Adds the value to a setting that is assigned once per value, like ExecStart=. Assigning the
+/// empty string resets it.
+pub(crate) fn assign_each(list: &mut Vec<String>, value: &str) {
+    if value.is_empty() {
+        list.clear();
+    } else {
+        list.push(value.to_string());
+    }
+}
+
+/// Adds the value to a capability set like CapabilityBoundingSet=. Unlike other settings, the
+/// empty string resets it to the empty set and a bare "~" to all capabilities, so these are kept
+/// as an assignment that replaces all earlier ones.
+pub(crate) fn assign_capabilities(list: &mut Vec<String>, value: &str) {
+    if value.trim_start_matches('~').trim().is_empty() {
+        list.clear();
+    }
+    list.push(value.to_string());
+}
+
+/// Parses a boolean like systemd does, see "man systemd.syntax".
+pub(crate) fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "yes" | "y" | "true" | "t" | "on" => Some(true),
+        "0" | "no" | "n" | "false" | "f" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+// The value parsers below return None for the empty string, which resets a setting to its
+// default.
+
+pub(crate) fn string_value(value: &str) -> Option<String> {
+    Some(value.to_string()).filter(|value| !value.is_empty())
+}
+
+pub(crate) fn bool_value(value: &str) -> Result<Option<bool>, AssignError> {
+    optional(value, |value| {
+        parse_bool(value).ok_or(AssignError::InvalidValue("a boolean"))
+    })
+}
+
+pub(crate) fn number_value<T: std::str::FromStr>(value: &str) -> Result<Option<T>, AssignError> {
+    optional(value, |value| {
+        value
+            .parse()
+            .map_err(|_| AssignError::InvalidValue("a number"))
+    })
+}
+
+pub(crate) fn timespan_value(value: &str) -> Result<Option<Duration>, AssignError> {
+    optional(value, |value| {
+        parse_timespan(value).map_err(|_| AssignError::InvalidValue("a time span"))
+    })
+}
+
+pub(crate) fn absolute_path_value(value: &str) -> Result<Option<String>, AssignError> {
+    optional(value, |value| {
+        if value.starts_with('/') {
+            Ok(value.to_string())
+        } else {
+            Err(AssignError::InvalidValue("an absolute path"))
+        }
+    })
+}
+
+/// An octal file mode, e.g. "0755", kept as it is written.
+pub(crate) fn mode_value(value: &str) -> Result<Option<String>, AssignError> {
+    optional(value, |value| {
+        u32::from_str_radix(value, 8)
+            .map(|_| value.to_string())
+            .map_err(|_| AssignError::InvalidValue("an octal file mode"))
+    })
+}
+
+/// Parses an enum that maps unknown values to Other(_), rejecting those.
+pub(crate) fn enum_value<T: From<String>>(
+    value: &str,
+    is_other: impl Fn(&T) -> bool,
+    expected: &'static str,
+) -> Result<Option<T>, AssignError> {
+    optional(value, |value| {
+        let parsed = T::from(value.to_string());
+        if is_other(&parsed) {
+            Err(AssignError::InvalidValue(expected))
+        } else {
+            Ok(parsed)
+        }
+    })
+}
+
+fn optional<T>(
+    value: &str,
+    parse: impl FnOnce(&str) -> Result<T, AssignError>,
+) -> Result<Option<T>, AssignError> {
+    if value.is_empty() {
+        Ok(None)
+    } else {
+        parse(value).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl Sections for UnitSection {
+        fn section(&mut self, name: &str) -> Option<&mut dyn Section> {
+            (name == "Unit").then_some(self as &mut dyn Section)
+        }
+    }
+
+    #[test]
+    fn reports_unknown_sections_and_keys_with_lines() {
+        let ini = Ini::parse("[Unit]\nDescription=Foo\nWat=1\nX-Custom=1\n[Bogus]\nA=1\n");
+        let mut unit = UnitSection::default();
+
+        let errors = read_sections(&ini, &mut unit);
+
+        assert_eq!(
+            errors.iter().map(|e| (e.line, &e.kind)).collect::<Vec<_>>(),
+            vec![
+                (3, &ConfigErrorKind::UnknownKey),
+                (5, &ConfigErrorKind::UnknownSection)
+            ]
+        );
+        assert_eq!(unit.description.as_deref(), Some("Foo"));
+    }
+
+    #[test]
+    fn checks_conditions_and_keeps_documented_settings() {
+        let ini = Ini::parse(
+            "[Unit]\nConditionPathExists=/etc/foo\nAssertFoo=1\nConditionfoo=1\n\
+             SuccessAction=reboot\n",
+        );
+        let mut unit = UnitSection::default();
+
+        let errors = read_sections(&ini, &mut unit);
+
+        assert_eq!(
+            errors.iter().map(|e| (e.line, &e.kind)).collect::<Vec<_>>(),
+            vec![
+                (3, &ConfigErrorKind::UnknownKey),
+                (4, &ConfigErrorKind::UnknownKey)
+            ]
+        );
+        assert_eq!(
+            unit.conditions,
+            vec![("ConditionPathExists".to_string(), "/etc/foo".to_string())]
+        );
+        assert_eq!(
+            unit.other,
+            vec![("SuccessAction".to_string(), "reboot".to_string())]
+        );
+    }
+
+    #[test]
+    fn empty_conditions_reset_all_conditions_or_assertions() {
+        let ini = Ini::parse(
+            "[Unit]\nConditionPathExists=/etc/foo\nConditionUser=root\n\
+             AssertPathExists=/etc/bar\nConditionHost=\nAssertUser=root\n",
+        );
+        let mut unit = UnitSection::default();
+
+        let errors = read_sections(&ini, &mut unit);
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            unit.conditions,
+            vec![
+                ("AssertPathExists".to_string(), "/etc/bar".to_string()),
+                ("AssertUser".to_string(), "root".to_string())
+            ]
+        );
+
+        let ini =
+            Ini::parse("[Unit]\nConditionUser=root\nAssertPathExists=/etc/bar\nAssertHost=\n");
+        let mut unit = UnitSection::default();
+        read_sections(&ini, &mut unit);
+
+        assert_eq!(
+            unit.conditions,
+            vec![("ConditionUser".to_string(), "root".to_string())]
+        );
+    }
+}
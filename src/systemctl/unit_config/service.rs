@@ -0,0 +1,479 @@
+use std::{fmt::Display, time::Duration};
+
+use crate::{
+    errors::SystemdError,
+    systemctl::{
+        ini::Ini,
+        install::InstallInfo,
+        unit_config::{
+            AssignError, ConfigError, Section, Sections, UnitSection, UnitWriter,
+            absolute_path_value, assign_each, assign_list, bool_value, enum_value,
+            exec::ExecSettings, into_result, read_sections, string_value, timespan_value,
+        },
+    },
+};
+
+// NOTE: These docs are from the man page of systemd.service, under Options
+
+/// Type=, how the manager knows that the service has finished starting up.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ServiceType {
+    /// Started as soon as the main process is forked off
+    Simple,
+    /// Started as soon as the main binary is executed
+    Exec,
+    /// Started once the process started with ExecStart= forks and exits
+    Forking,
+    /// Started once the main process exits, after which the service is usually inactive
+    Oneshot,
+    /// Started once the name set in BusName= is acquired on the bus
+    Dbus,
+    /// Started once the service sends "READY=1" through sd_notify()
+    Notify,
+    /// Like notify, and reloads are also signaled through sd_notify()
+    NotifyReload,
+    /// Like simple, but the process is only started once all jobs are dispatched
+    Idle,
+    Other(String),
+}
+
+impl From<String> for ServiceType {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "simple" => ServiceType::Simple,
+            "exec" => ServiceType::Exec,
+            "forking" => ServiceType::Forking,
+            "oneshot" => ServiceType::Oneshot,
+            "dbus" => ServiceType::Dbus,
+            "notify" => ServiceType::Notify,
+            "notify-reload" => ServiceType::NotifyReload,
+            "idle" => ServiceType::Idle,
+            _ => ServiceType::Other(value),
+        }
+    }
+}
+
+impl Display for ServiceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            ServiceType::Simple => "simple",
+            ServiceType::Exec => "exec",
+            ServiceType::Forking => "forking",
+            ServiceType::Oneshot => "oneshot",
+            ServiceType::Dbus => "dbus",
+            ServiceType::Notify => "notify",
+            ServiceType::NotifyReload => "notify-reload",
+            ServiceType::Idle => "idle",
+            ServiceType::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+/// ExitType=, when the service is considered finished.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ExitType {
+    /// When the main process exits
+    Main,
+    /// When the last process in the control group exits
+    Cgroup,
+    Other(String),
+}
+
+impl From<String> for ExitType {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "main" => ExitType::Main,
+            "cgroup" => ExitType::Cgroup,
+            _ => ExitType::Other(value),
+        }
+    }
+}
+
+impl Display for ExitType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            ExitType::Main => "main",
+            ExitType::Cgroup => "cgroup",
+            ExitType::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+/// Restart=, when the service is restarted after its processes exit.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum RestartPolicy {
+    No,
+    /// Only after a clean exit
+    OnSuccess,
+    /// After an unclean exit code, an unclean signal, a timeout or the watchdog
+    OnFailure,
+    /// After an unclean signal, a timeout or the watchdog
+    OnAbnormal,
+    /// Only after the watchdog timed out
+    OnWatchdog,
+    /// Only after an unclean signal
+    OnAbort,
+    Always,
+    Other(String),
+}
+
+impl From<String> for RestartPolicy {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "no" => RestartPolicy::No,
+            "on-success" => RestartPolicy::OnSuccess,
+            "on-failure" => RestartPolicy::OnFailure,
+            "on-abnormal" => RestartPolicy::OnAbnormal,
+            "on-watchdog" => RestartPolicy::OnWatchdog,
+            "on-abort" => RestartPolicy::OnAbort,
+            "always" => RestartPolicy::Always,
+            _ => RestartPolicy::Other(value),
+        }
+    }
+}
+
+impl Display for RestartPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            RestartPolicy::No => "no",
+            RestartPolicy::OnSuccess => "on-success",
+            RestartPolicy::OnFailure => "on-failure",
+            RestartPolicy::OnAbnormal => "on-abnormal",
+            RestartPolicy::OnWatchdog => "on-watchdog",
+            RestartPolicy::OnAbort => "on-abort",
+            RestartPolicy::Always => "always",
+            RestartPolicy::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+/// NotifyAccess=, which processes of the service may send sd_notify() messages.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum NotifyAccess {
+    None,
+    /// Only the main process
+    Main,
+    /// The main process and the processes started by the Exec*= settings
+    Exec,
+    /// All processes in the control group of the service
+    All,
+    Other(String),
+}
+
+impl From<String> for NotifyAccess {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "none" => NotifyAccess::None,
+            "main" => NotifyAccess::Main,
+            "exec" => NotifyAccess::Exec,
+            "all" => NotifyAccess::All,
+            _ => NotifyAccess::Other(value),
+        }
+    }
+}
+
+impl Display for NotifyAccess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            NotifyAccess::None => "none",
+            NotifyAccess::Main => "main",
+            NotifyAccess::Exec => "exec",
+            NotifyAccess::All => "all",
+            NotifyAccess::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+/// The documented settings of the [Service] section that are not modeled by ServiceSection.
+const OTHER_KEYS: &[&str] = &[
+    "GuessMainPID",
+    "TimeoutAbortSec",
+    "TimeoutStartFailureMode",
+    "TimeoutStopFailureMode",
+    "RuntimeRandomizedExtraSec",
+    "RestartMode",
+    "RestartSteps",
+    "RestartMaxDelaySec",
+    "RestartForceExitStatus",
+    "RootDirectoryStartOnly",
+    "NonBlocking",
+    "FileDescriptorStoreMax",
+    "FileDescriptorStorePreserve",
+    "USBFunctionDescriptors",
+    "USBFunctionStrings",
+    "OOMPolicy",
+    "OpenFile",
+    "ReloadSignal",
+    "Sockets",
+];
+
+/// The [Service] section of a service unit file.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ServiceSection {
+    pub service_type: Option<ServiceType>,
+    pub exit_type: Option<ExitType>,
+    pub remain_after_exit: Option<bool>,
+    pub pid_file: Option<String>,
+    pub bus_name: Option<String>,
+    /// Command lines, one per assignment
+    pub exec_condition: Vec<String>,
+    pub exec_start_pre: Vec<String>,
+    pub exec_start: Vec<String>,
+    pub exec_start_post: Vec<String>,
+    pub exec_reload: Vec<String>,
+    pub exec_stop: Vec<String>,
+    pub exec_stop_post: Vec<String>,
+    pub restart: Option<RestartPolicy>,
+    pub restart_sec: Option<Duration>,
+    pub timeout_start: Option<Duration>,
+    pub timeout_stop: Option<Duration>,
+    pub runtime_max: Option<Duration>,
+    pub watchdog: Option<Duration>,
+    pub success_exit_status: Vec<String>,
+    pub restart_prevent_exit_status: Vec<String>,
+    pub notify_access: Option<NotifyAccess>,
+    /// Documented settings that are not modeled, as key and value in the order they are assigned
+    pub other: Vec<(String, String)>,
+    /// The execution environment, sandboxing, kill and resource control settings
+    pub exec: ExecSettings,
+}
+
+impl Section for ServiceSection {
+    fn assign(&mut self, key: &str, value: &str) -> Result<(), AssignError> {
+        match key {
+            "Type" => {
+                self.service_type = enum_value(
+                    value,
+                    |v| matches!(v, ServiceType::Other(_)),
+                    "simple, exec, forking, oneshot, dbus, notify, notify-reload or idle",
+                )?
+            }
+            "ExitType" => {
+                self.exit_type =
+                    enum_value(value, |v| matches!(v, ExitType::Other(_)), "main or cgroup")?
+            }
+            "RemainAfterExit" => self.remain_after_exit = bool_value(value)?,
+            "PIDFile" => self.pid_file = absolute_path_value(value)?,
+            "BusName" => self.bus_name = string_value(value),
+            "ExecCondition" => assign_each(&mut self.exec_condition, value),
+            "ExecStartPre" => assign_each(&mut self.exec_start_pre, value),
+            "ExecStart" => assign_each(&mut self.exec_start, value),
+            "ExecStartPost" => assign_each(&mut self.exec_start_post, value),
+            "ExecReload" => assign_each(&mut self.exec_reload, value),
+            "ExecStop" => assign_each(&mut self.exec_stop, value),
+            "ExecStopPost" => assign_each(&mut self.exec_stop_post, value),
+            "Restart" => {
+                self.restart = enum_value(
+                    value,
+                    |v| matches!(v, RestartPolicy::Other(_)),
+                    "no, on-success, on-failure, on-abnormal, on-watchdog, on-abort or always",
+                )?
+            }
+            "RestartSec" => self.restart_sec = timespan_value(value)?,
+            "TimeoutStartSec" => self.timeout_start = timespan_value(value)?,
+            "TimeoutStopSec" => self.timeout_stop = timespan_value(value)?,
+            "TimeoutSec" => {
+                let timeout = timespan_value(value)?;
+                self.timeout_start = timeout;
+                self.timeout_stop = timeout;
+            }
+            "RuntimeMaxSec" => self.runtime_max = timespan_value(value)?,
+            "WatchdogSec" => self.watchdog = timespan_value(value)?,
+            "SuccessExitStatus" => assign_list(&mut self.success_exit_status, value),
+            "RestartPreventExitStatus" => assign_list(&mut self.restart_prevent_exit_status, value),
+            "NotifyAccess" => {
+                self.notify_access = enum_value(
+                    value,
+                    |v| matches!(v, NotifyAccess::Other(_)),
+                    "none, main, exec or all",
+                )?
+            }
+            key if OTHER_KEYS.contains(&key) => {
+                self.other.push((key.to_string(), value.to_string()))
+            }
+            _ => return self.exec.assign(key, value),
+        }
+
+        Ok(())
+    }
+
+    fn write(&self, writer: &mut UnitWriter) {
+        writer.section("Service");
+        writer.value("Type", self.service_type.as_ref());
+        writer.value("ExitType", self.exit_type.as_ref());
+        writer.bool("RemainAfterExit", self.remain_after_exit);
+        writer.value("PIDFile", self.pid_file.as_ref());
+        writer.value("BusName", self.bus_name.as_ref());
+        writer.each("ExecCondition", &self.exec_condition);
+        writer.each("ExecStartPre", &self.exec_start_pre);
+        writer.each("ExecStart", &self.exec_start);
+        writer.each("ExecStartPost", &self.exec_start_post);
+        writer.each("ExecReload", &self.exec_reload);
+        writer.each("ExecStop", &self.exec_stop);
+        writer.each("ExecStopPost", &self.exec_stop_post);
+        writer.value("Restart", self.restart.as_ref());
+        writer.timespan("RestartSec", self.restart_sec);
+        writer.timespan("TimeoutStartSec", self.timeout_start);
+        writer.timespan("TimeoutStopSec", self.timeout_stop);
+        writer.timespan("RuntimeMaxSec", self.runtime_max);
+        writer.timespan("WatchdogSec", self.watchdog);
+        writer.list("SuccessExitStatus", &self.success_exit_status);
+        writer.list(
+            "RestartPreventExitStatus",
+            &self.restart_prevent_exit_status,
+        );
+        writer.value("NotifyAccess", self.notify_access.as_ref());
+        writer.pairs(&self.other);
+        self.exec.write(writer);
+    }
+}
+
+/// A service unit file, see "man systemd.service". Display writes it in the unit file syntax.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ServiceUnitConfig {
+    pub unit: UnitSection,
+    pub service: ServiceSection,
+    pub install: InstallInfo,
+}
+
+impl ServiceUnitConfig {
+    /// Parses a service unit file. Fails with all problems found if there are unknown sections
+    /// or keys, or values that cannot be parsed.
+    pub fn parse(contents: &str) -> Result<Self, SystemdError> {
+        let (config, errors) = Self::read(&Ini::parse(contents));
+        into_result(config, errors)
+    }
+
+    /// Reads as much of the unit file as possible, along with the problems found.
+    pub(crate) fn read(ini: &Ini) -> (Self, Vec<ConfigError>) {
+        let mut config = Self::default();
+        let errors = read_sections(ini, &mut config);
+        (config, errors)
+    }
+}
+
+impl Sections for ServiceUnitConfig {
+    fn section(&mut self, name: &str) -> Option<&mut dyn Section> {
+        match name {
+            "Unit" => Some(&mut self.unit),
+            "Service" => Some(&mut self.service),
+            "Install" => Some(&mut self.install),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ServiceUnitConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut writer = UnitWriter::default();
+        self.unit.write(&mut writer);
+        self.service.write(&mut writer);
+        self.install.write(&mut writer);
+        f.write_str(&writer.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemctl::unit_config::{
+        ConfigErrorKind,
+        exec::{ProtectHome, ProtectSystem},
+    };
+
+    #[test]
+    fn round_trips_service_unit_files() {
+        let config = ServiceUnitConfig {
+            unit: UnitSection {
+                description: Some("Example".to_string()),
+                after: vec!["network-online.target".to_string()],
+                ..Default::default()
+            },
+            service: ServiceSection {
+                service_type: Some(ServiceType::Notify),
+                exec_start: vec!["/usr/bin/example --serve".to_string()],
+                restart: Some(RestartPolicy::OnFailure),
+                restart_sec: Some(Duration::from_secs(90)),
+                exec: ExecSettings {
+                    protect_system: Some(ProtectSystem::Strict),
+                    private_tmp: Some(true),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            install: InstallInfo {
+                wanted_by: vec!["multi-user.target".to_string()],
+                ..Default::default()
+            },
+        };
+
+        let contents = config.to_string();
+
+        assert_eq!(
+            contents,
+            "[Unit]\nDescription=Example\nAfter=network-online.target\n\n\
+             [Service]\nType=notify\nExecStart=/usr/bin/example --serve\nRestart=on-failure\n\
+             RestartSec=1min 30s\nProtectSystem=strict\nPrivateTmp=yes\n\n\
+             [Install]\nWantedBy=multi-user.target\n"
+        );
+        assert_eq!(
+            ServiceUnitConfig::parse(&contents).expect("Written unit files should parse"),
+            config
+        );
+    }
+
+    #[test]
+    fn keeps_documented_settings_and_resets_empty_values() {
+        let contents = "[Service]\nType=notify\nExecStart=/bin/foo\nLimitNPROC=64\n\
+                        OOMPolicy=stop\nSlice=foo.slice\nType=\nTimeoutSec=5\nTimeoutSec=\n\
+                        PrivateTmp=yes\nPrivateTmp=\nRootDirectory=\nProtectSystem=1\n\
+                        ProtectHome=off\n";
+
+        let config = ServiceUnitConfig::parse(contents).expect("This is a valid unit file");
+        let service = &config.service;
+
+        assert_eq!(service.service_type, None);
+        assert_eq!(service.timeout_start, None);
+        assert_eq!(service.timeout_stop, None);
+        assert_eq!(service.exec.private_tmp, None);
+        assert_eq!(service.exec.root_directory, None);
+        assert_eq!(service.exec.protect_system, Some(ProtectSystem::Yes));
+        assert_eq!(service.exec.protect_home, Some(ProtectHome::No));
+        assert_eq!(
+            service.other,
+            vec![("OOMPolicy".to_string(), "stop".to_string())]
+        );
+        assert_eq!(
+            service.exec.other,
+            vec![
+                ("LimitNPROC".to_string(), "64".to_string()),
+                ("Slice".to_string(), "foo.slice".to_string())
+            ]
+        );
+        assert!(config.to_string().contains("OOMPolicy=stop\n"));
+    }
+
+    #[test]
+    fn rejects_bad_values() {
+        let contents = "[Service]\nType=sometimes\nRestartSec=soon\nPIDFile=run/foo.pid\n";
+
+        let Err(SystemdError::InvalidUnitConfig(errors)) = ServiceUnitConfig::parse(contents)
+        else {
+            panic!("The unit file should be rejected");
+        };
+
+        assert_eq!(
+            errors.iter().map(|error| error.line).collect::<Vec<_>>(),
+            vec![2, 3, 4]
+        );
+        assert!(
+            errors
+                .iter()
+                .all(|error| matches!(error.kind, ConfigErrorKind::InvalidValue { .. }))
+        );
+    }
+}
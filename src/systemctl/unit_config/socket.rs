@@ -0,0 +1,259 @@
+use std::{fmt::Display, time::Duration};
+
+use crate::{
+    errors::SystemdError,
+    systemctl::{
+        ini::Ini,
+        install::InstallInfo,
+        unit_config::{
+            AssignError, ConfigError, Section, Sections, UnitSection, UnitWriter, assign_each,
+            assign_list, bool_value, enum_value, exec::ExecSettings, into_result, mode_value,
+            number_value, read_sections, string_value, timespan_value,
+        },
+    },
+};
+
+// NOTE: These docs are from the man page of systemd.socket, under Options
+
+/// BindIPv6Only=, whether IPv6 sockets also accept IPv4 connections.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum BindIPv6Only {
+    /// The system wide setting in /proc/sys/net/ipv6/bindv6only is used
+    Default,
+    /// IPv4 and IPv6 connections are accepted
+    Both,
+    /// Only IPv6 connections are accepted
+    Ipv6Only,
+    Other(String),
+}
+
+impl From<String> for BindIPv6Only {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "default" => BindIPv6Only::Default,
+            "both" => BindIPv6Only::Both,
+            "ipv6-only" => BindIPv6Only::Ipv6Only,
+            _ => BindIPv6Only::Other(value),
+        }
+    }
+}
+
+impl Display for BindIPv6Only {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            BindIPv6Only::Default => "default",
+            BindIPv6Only::Both => "both",
+            BindIPv6Only::Ipv6Only => "ipv6-only",
+            BindIPv6Only::Other(val) => val,
+        };
+        f.write_str(value)
+    }
+}
+
+/// The documented settings of the [Socket] section that are not modeled by SocketSection.
+const OTHER_KEYS: &[&str] = &[
+    "ListenMessageQueue",
+    "ListenUSBFunction",
+    "SocketProtocol",
+    "BindToDevice",
+    "Writable",
+    "FlushPending",
+    "KeepAliveTimeSec",
+    "KeepAliveIntervalSec",
+    "KeepAliveProbes",
+    "DeferAcceptSec",
+    "ReceiveBuffer",
+    "SendBuffer",
+    "IPTOS",
+    "IPTTL",
+    "Mark",
+    "SmackLabel",
+    "SmackLabelIPIn",
+    "SmackLabelIPOut",
+    "SELinuxContextFromNet",
+    "PipeSize",
+    "MessageQueueMaxMessages",
+    "MessageQueueMessageSize",
+    "FreeBind",
+    "Transparent",
+    "Broadcast",
+    "PassSecurity",
+    "PassPacketInfo",
+    "Timestamping",
+    "TCPCongestion",
+    "PassFileDescriptorsToExec",
+    "PollLimitIntervalSec",
+    "PollLimitBurst",
+];
+
+/// The [Socket] section of a socket unit file.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SocketSection {
+    /// Stream sockets, e.g. "8080", "[::1]:80" or "/run/foo.sock"
+    pub listen_stream: Vec<String>,
+    pub listen_datagram: Vec<String>,
+    pub listen_sequential_packet: Vec<String>,
+    pub listen_fifo: Vec<String>,
+    pub listen_special: Vec<String>,
+    pub listen_netlink: Vec<String>,
+    /// Whether a service instance is spawned for each connection
+    pub accept: Option<bool>,
+    pub bind_ipv6_only: Option<BindIPv6Only>,
+    pub backlog: Option<u32>,
+    pub socket_user: Option<String>,
+    pub socket_group: Option<String>,
+    pub socket_mode: Option<String>,
+    pub directory_mode: Option<String>,
+    /// The service to activate, the service with the same name as the socket if not set
+    pub service: Option<String>,
+    pub file_descriptor_name: Option<String>,
+    pub max_connections: Option<u32>,
+    pub max_connections_per_source: Option<u32>,
+    pub keep_alive: Option<bool>,
+    pub no_delay: Option<bool>,
+    pub reuse_port: Option<bool>,
+    pub pass_credentials: Option<bool>,
+    pub remove_on_stop: Option<bool>,
+    pub symlinks: Vec<String>,
+    pub trigger_limit_interval: Option<Duration>,
+    pub trigger_limit_burst: Option<u32>,
+    pub exec_start_pre: Vec<String>,
+    pub exec_start_post: Vec<String>,
+    pub exec_stop_pre: Vec<String>,
+    pub exec_stop_post: Vec<String>,
+    pub timeout: Option<Duration>,
+    /// Documented settings that are not modeled, as key and value in the order they are assigned
+    pub other: Vec<(String, String)>,
+    /// The execution environment, sandboxing, kill and resource control settings
+    pub exec: ExecSettings,
+}
+
+impl Section for SocketSection {
+    fn assign(&mut self, key: &str, value: &str) -> Result<(), AssignError> {
+        match key {
+            "ListenStream" => assign_each(&mut self.listen_stream, value),
+            "ListenDatagram" => assign_each(&mut self.listen_datagram, value),
+            "ListenSequentialPacket" => assign_each(&mut self.listen_sequential_packet, value),
+            "ListenFIFO" => assign_each(&mut self.listen_fifo, value),
+            "ListenSpecial" => assign_each(&mut self.listen_special, value),
+            "ListenNetlink" => assign_each(&mut self.listen_netlink, value),
+            "Accept" => self.accept = bool_value(value)?,
+            "BindIPv6Only" => {
+                self.bind_ipv6_only = enum_value(
+                    value,
+                    |v| matches!(v, BindIPv6Only::Other(_)),
+                    "default, both or ipv6-only",
+                )?
+            }
+            "Backlog" => self.backlog = number_value(value)?,
+            "SocketUser" => self.socket_user = string_value(value),
+            "SocketGroup" => self.socket_group = string_value(value),
+            "SocketMode" => self.socket_mode = mode_value(value)?,
+            "DirectoryMode" => self.directory_mode = mode_value(value)?,
+            "Service" => self.service = string_value(value),
+            "FileDescriptorName" => self.file_descriptor_name = string_value(value),
+            "MaxConnections" => self.max_connections = number_value(value)?,
+            "MaxConnectionsPerSource" => self.max_connections_per_source = number_value(value)?,
+            "KeepAlive" => self.keep_alive = bool_value(value)?,
+            "NoDelay" => self.no_delay = bool_value(value)?,
+            "ReusePort" => self.reuse_port = bool_value(value)?,
+            "PassCredentials" => self.pass_credentials = bool_value(value)?,
+            "RemoveOnStop" => self.remove_on_stop = bool_value(value)?,
+            "Symlinks" => assign_list(&mut self.symlinks, value),
+            "TriggerLimitIntervalSec" => self.trigger_limit_interval = timespan_value(value)?,
+            "TriggerLimitBurst" => self.trigger_limit_burst = number_value(value)?,
+            "ExecStartPre" => assign_each(&mut self.exec_start_pre, value),
+            "ExecStartPost" => assign_each(&mut self.exec_start_post, value),
+            "ExecStopPre" => assign_each(&mut self.exec_stop_pre, value),
+            "ExecStopPost" => assign_each(&mut self.exec_stop_post, value),
+            "TimeoutSec" => self.timeout = timespan_value(value)?,
+            key if OTHER_KEYS.contains(&key) => {
+                self.other.push((key.to_string(), value.to_string()))
+            }
+            _ => return self.exec.assign(key, value),
+        }
+
+        Ok(())
+    }
+
+    fn write(&self, writer: &mut UnitWriter) {
+        writer.section("Socket");
+        writer.each("ListenStream", &self.listen_stream);
+        writer.each("ListenDatagram", &self.listen_datagram);
+        writer.each("ListenSequentialPacket", &self.listen_sequential_packet);
+        writer.each("ListenFIFO", &self.listen_fifo);
+        writer.each("ListenSpecial", &self.listen_special);
+        writer.each("ListenNetlink", &self.listen_netlink);
+        writer.bool("Accept", self.accept);
+        writer.value("BindIPv6Only", self.bind_ipv6_only.as_ref());
+        writer.value("Backlog", self.backlog);
+        writer.value("SocketUser", self.socket_user.as_ref());
+        writer.value("SocketGroup", self.socket_group.as_ref());
+        writer.value("SocketMode", self.socket_mode.as_ref());
+        writer.value("DirectoryMode", self.directory_mode.as_ref());
+        writer.value("Service", self.service.as_ref());
+        writer.value("FileDescriptorName", self.file_descriptor_name.as_ref());
+        writer.value("MaxConnections", self.max_connections);
+        writer.value("MaxConnectionsPerSource", self.max_connections_per_source);
+        writer.bool("KeepAlive", self.keep_alive);
+        writer.bool("NoDelay", self.no_delay);
+        writer.bool("ReusePort", self.reuse_port);
+        writer.bool("PassCredentials", self.pass_credentials);
+        writer.bool("RemoveOnStop", self.remove_on_stop);
+        writer.list("Symlinks", &self.symlinks);
+        writer.timespan("TriggerLimitIntervalSec", self.trigger_limit_interval);
+        writer.value("TriggerLimitBurst", self.trigger_limit_burst);
+        writer.each("ExecStartPre", &self.exec_start_pre);
+        writer.each("ExecStartPost", &self.exec_start_post);
+        writer.each("ExecStopPre", &self.exec_stop_pre);
+        writer.each("ExecStopPost", &self.exec_stop_post);
+        writer.timespan("TimeoutSec", self.timeout);
+        writer.pairs(&self.other);
+        self.exec.write(writer);
+    }
+}
+
+/// A socket unit file, see "man systemd.socket". Display writes it in the unit file syntax.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SocketUnitConfig {
+    pub unit: UnitSection,
+    pub socket: SocketSection,
+    pub install: InstallInfo,
+}
+
+impl SocketUnitConfig {
+    /// Parses a socket unit file. Fails with all problems found if there are unknown sections
+    /// or keys, or values that cannot be parsed.
+    pub fn parse(contents: &str) -> Result<Self, SystemdError> {
+        let (config, errors) = Self::read(&Ini::parse(contents));
+        into_result(config, errors)
+    }
+
+    /// Reads as much of the unit file as possible, along with the problems found.
+    pub(crate) fn read(ini: &Ini) -> (Self, Vec<ConfigError>) {
+        let mut config = Self::default();
+        let errors = read_sections(ini, &mut config);
+        (config, errors)
+    }
+}
+
+impl Sections for SocketUnitConfig {
+    fn section(&mut self, name: &str) -> Option<&mut dyn Section> {
+        match name {
+            "Unit" => Some(&mut self.unit),
+            "Socket" => Some(&mut self.socket),
+            "Install" => Some(&mut self.install),
+            _ => None,
+        }
+    }
+}
+
+impl Display for SocketUnitConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut writer = UnitWriter::default();
+        self.unit.write(&mut writer);
+        self.socket.write(&mut writer);
+        self.install.write(&mut writer);
+        f.write_str(&writer.finish())
+    }
+}
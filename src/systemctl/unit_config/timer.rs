@@ -0,0 +1,219 @@
+use std::{fmt::Display, time::Duration};
+
+use crate::{
+    errors::SystemdError,
+    systemctl::{
+        ini::Ini,
+        install::InstallInfo,
+        time::calendar::CalendarSpec,
+        unit_config::{
+            AssignError, ConfigError, Section, Sections, UnitSection, UnitWriter, assign_each,
+            bool_value, into_result, read_sections, string_value, timespan_value,
+        },
+    },
+};
+
+// NOTE: These docs are from the man page of systemd.timer, under Options
+
+/// The documented settings of the [Timer] section that are not modeled by TimerSection.
+const OTHER_KEYS: &[&str] = &["RandomizedOffsetSec", "DeferReactivation"];
+
+/// The settings that each add a timer.
+const TIMER_KEYS: &[&str] = &[
+    "OnActiveSec",
+    "OnBootSec",
+    "OnStartupSec",
+    "OnUnitActiveSec",
+    "OnUnitInactiveSec",
+    "OnCalendar",
+];
+
+/// The [Timer] section of a timer unit file.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TimerSection {
+    /// Relative to when the timer was activated
+    pub on_active: Vec<Duration>,
+    /// Relative to when the machine was booted
+    pub on_boot: Vec<Duration>,
+    /// Relative to when the service manager was started
+    pub on_startup: Vec<Duration>,
+    /// Relative to when the unit the timer activates was last activated
+    pub on_unit_active: Vec<Duration>,
+    /// Relative to when the unit the timer activates was last deactivated
+    pub on_unit_inactive: Vec<Duration>,
+    /// Calendar event expressions, one per assignment, e.g. "Mon..Fri 09:00"
+    pub on_calendar: Vec<String>,
+    pub accuracy: Option<Duration>,
+    pub randomized_delay: Option<Duration>,
+    pub fixed_random_delay: Option<bool>,
+    pub on_clock_change: Option<bool>,
+    pub on_timezone_change: Option<bool>,
+    /// The unit to activate, the service with the same name as the timer if not set
+    pub unit: Option<String>,
+    /// Whether missed OnCalendar= events are caught up on at boot
+    pub persistent: Option<bool>,
+    pub wake_system: Option<bool>,
+    pub remain_after_elapse: Option<bool>,
+    /// Documented settings that are not modeled, as key and value in the order they are assigned
+    pub other: Vec<(String, String)>,
+}
+
+impl TimerSection {
+    fn clear_timers(&mut self) {
+        self.on_active.clear();
+        self.on_boot.clear();
+        self.on_startup.clear();
+        self.on_unit_active.clear();
+        self.on_unit_inactive.clear();
+        self.on_calendar.clear();
+    }
+}
+
+impl Section for TimerSection {
+    fn assign(&mut self, key: &str, value: &str) -> Result<(), AssignError> {
+        // An empty assignment to any of the timers resets all of them, not just that kind
+        if TIMER_KEYS.contains(&key) && value.is_empty() {
+            self.clear_timers();
+            return Ok(());
+        }
+
+        match key {
+            "OnActiveSec" => self.on_active.extend(timespan_value(value)?),
+            "OnBootSec" => self.on_boot.extend(timespan_value(value)?),
+            "OnStartupSec" => self.on_startup.extend(timespan_value(value)?),
+            "OnUnitActiveSec" => self.on_unit_active.extend(timespan_value(value)?),
+            "OnUnitInactiveSec" => self.on_unit_inactive.extend(timespan_value(value)?),
+            "OnCalendar" => {
+                if value.parse::<CalendarSpec>().is_err() {
+                    return Err(AssignError::InvalidValue("a calendar event"));
+                }
+                assign_each(&mut self.on_calendar, value)
+            }
+            "AccuracySec" => self.accuracy = timespan_value(value)?,
+            "RandomizedDelaySec" => self.randomized_delay = timespan_value(value)?,
+            "FixedRandomDelay" => self.fixed_random_delay = bool_value(value)?,
+            "OnClockChange" => self.on_clock_change = bool_value(value)?,
+            "OnTimezoneChange" => self.on_timezone_change = bool_value(value)?,
+            "Unit" => self.unit = string_value(value),
+            "Persistent" => self.persistent = bool_value(value)?,
+            "WakeSystem" => self.wake_system = bool_value(value)?,
+            "RemainAfterElapse" => self.remain_after_elapse = bool_value(value)?,
+            key if OTHER_KEYS.contains(&key) => {
+                self.other.push((key.to_string(), value.to_string()))
+            }
+            _ => return Err(AssignError::UnknownKey),
+        }
+
+        Ok(())
+    }
+
+    fn write(&self, writer: &mut UnitWriter) {
+        let timespans = |writer: &mut UnitWriter, key: &str, list: &[Duration]| {
+            for value in list {
+                writer.timespan(key, Some(*value));
+            }
+        };
+
+        writer.section("Timer");
+        timespans(writer, "OnActiveSec", &self.on_active);
+        timespans(writer, "OnBootSec", &self.on_boot);
+        timespans(writer, "OnStartupSec", &self.on_startup);
+        timespans(writer, "OnUnitActiveSec", &self.on_unit_active);
+        timespans(writer, "OnUnitInactiveSec", &self.on_unit_inactive);
+        writer.each("OnCalendar", &self.on_calendar);
+        writer.timespan("AccuracySec", self.accuracy);
+        writer.timespan("RandomizedDelaySec", self.randomized_delay);
+        writer.bool("FixedRandomDelay", self.fixed_random_delay);
+        writer.bool("OnClockChange", self.on_clock_change);
+        writer.bool("OnTimezoneChange", self.on_timezone_change);
+        writer.value("Unit", self.unit.as_ref());
+        writer.bool("Persistent", self.persistent);
+        writer.bool("WakeSystem", self.wake_system);
+        writer.bool("RemainAfterElapse", self.remain_after_elapse);
+        writer.pairs(&self.other);
+    }
+}
+
+/// A timer unit file, see "man systemd.timer". Display writes it in the unit file syntax.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TimerUnitConfig {
+    pub unit: UnitSection,
+    pub timer: TimerSection,
+    pub install: InstallInfo,
+}
+
+impl TimerUnitConfig {
+    /// Parses a timer unit file. Fails with all problems found if there are unknown sections
+    /// or keys, or values that cannot be parsed.
+    pub fn parse(contents: &str) -> Result<Self, SystemdError> {
+        let (config, errors) = Self::read(&Ini::parse(contents));
+        into_result(config, errors)
+    }
+
+    /// Reads as much of the unit file as possible, along with the problems found.
+    pub(crate) fn read(ini: &Ini) -> (Self, Vec<ConfigError>) {
+        let mut config = Self::default();
+        let errors = read_sections(ini, &mut config);
+        (config, errors)
+    }
+}
+
+impl Sections for TimerUnitConfig {
+    fn section(&mut self, name: &str) -> Option<&mut dyn Section> {
+        match name {
+            "Unit" => Some(&mut self.unit),
+            "Timer" => Some(&mut self.timer),
+            "Install" => Some(&mut self.install),
+            _ => None,
+        }
+    }
+}
+
+impl Display for TimerUnitConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut writer = UnitWriter::default();
+        self.unit.write(&mut writer);
+        self.timer.write(&mut writer);
+        self.install.write(&mut writer);
+        f.write_str(&writer.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_timer_unit_files() {
+        let config = TimerUnitConfig::parse(
+            "[Unit]\nDescription=Daily backup\n\n\
+             [Timer]\nOnCalendar=*-*-* 03:00\nOnBootSec=15min\nPersistent=true\n\n\
+             [Install]\nWantedBy=timers.target\n",
+        )
+        .expect("This is a valid timer unit file");
+
+        assert_eq!(config.timer.on_calendar, vec!["*-*-* 03:00"]);
+        assert_eq!(config.timer.on_boot, vec![Duration::from_secs(900)]);
+        assert_eq!(config.timer.persistent, Some(true));
+        assert!(
+            config
+                .to_string()
+                .contains("[Timer]\nOnBootSec=15min\nOnCalendar=*-*-* 03:00\nPersistent=yes\n")
+        );
+    }
+
+    #[test]
+    fn empty_timer_settings_reset_all_timers() {
+        let timer = TimerUnitConfig::parse(
+            "[Timer]\nOnCalendar=daily\nOnBootSec=15min\nOnUnitActiveSec=1h\n\
+             OnActiveSec=\nOnStartupSec=5min\n",
+        )
+        .expect("This is a valid timer unit file")
+        .timer;
+
+        assert!(timer.on_calendar.is_empty());
+        assert!(timer.on_boot.is_empty());
+        assert!(timer.on_unit_active.is_empty());
+        assert_eq!(timer.on_startup, vec![Duration::from_secs(300)]);
+    }
+}
@@ -1,32 +1,183 @@
 use std::fmt::Display;
 
+use crate::systemctl::{
+    preset::{PresetAction, Presets},
+    unit_name::{UnitName, UnitType},
+};
+
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
 pub struct UnitFile {
     /// The location of the unit file on disk, I think
     pub path: String,
     pub enablement_status: EnablementStatus,
+    /// The unit name derived from the file name, if it is a valid unit name
+    pub unit_name: Option<UnitName>,
+    /// The unit type derived from the file name suffix
+    pub unit_type: Option<UnitType>,
+    /// Whether this is a template, i.e. named like "foo@.service"
+    pub is_template: bool,
+    /// What `systemctl preset` would do with this unit file, if the preset policy was looked up
+    pub preset: Option<PresetAction>,
+}
+
+impl UnitFile {
+    /// The file name part of the path, which is the name the unit is known by.
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+
+    /// Looks up the preset policy for this unit file.
+    pub fn with_preset(mut self, presets: &Presets) -> Self {
+        self.preset = Some(presets.action_for(self.file_name()));
+        self
+    }
 }
 
+/// Describes which unit files to list, similar to the --state=, --type= and pattern arguments of
+/// `systemctl list-unit-files`. Passed to ListUnitFilesByPatterns(), so the filtering is done by
+/// systemd.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct UnitFileQuery {
+    states: Vec<EnablementStatus>,
+    patterns: Vec<String>,
+    unit_types: Vec<UnitType>,
+}
+
+impl UnitFileQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only list unit files in this enablement state, e.g. enabled or masked.
+    pub fn with_state(mut self, state: EnablementStatus) -> Self {
+        self.states.push(state);
+        self
+    }
+
+    /// Only list unit files whose name matches this shell-style glob pattern, e.g. "systemd-*".
+    pub fn with_pattern(mut self, pattern: &str) -> Self {
+        self.patterns.push(pattern.to_string());
+        self
+    }
+
+    /// Only list unit files of this type, e.g. services or timers.
+    pub fn with_unit_type(mut self, unit_type: UnitType) -> Self {
+        self.unit_types.push(unit_type);
+        self
+    }
+
+    /// The states argument to ListUnitFilesByPatterns().
+    pub(crate) fn states(&self) -> Vec<String> {
+        self.states.iter().map(ToString::to_string).collect()
+    }
+
+    /// The patterns argument to ListUnitFilesByPatterns(). Unit types are turned into patterns
+    /// when no explicit patterns are given, otherwise they are applied by [`Self::matches`].
+    pub(crate) fn patterns(&self) -> Vec<String> {
+        if self.patterns.is_empty() {
+            self.unit_types
+                .iter()
+                .map(|unit_type| format!("*.{unit_type}"))
+                .collect()
+        } else {
+            self.patterns.clone()
+        }
+    }
+
+    /// Whether a unit file returned by the manager is of one of the requested types.
+    pub fn matches(&self, unit_file: &UnitFile) -> bool {
+        self.unit_types.is_empty()
+            || unit_file
+                .unit_type
+                .is_some_and(|unit_type| self.unit_types.contains(&unit_type))
+    }
+}
+
+// NOTE: These docs are from the man page of systemctl, under is-enabled
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum EnablementStatus {
+    /// The name is an alias (symlink to another unit file).
     Alias,
+    /// The unit file is invalid or another error occurred.
+    Bad,
+    /// The unit file is not enabled, but contains an "[Install]" section with installation
+    /// instructions.
     Disabled,
+    /// Enabled via .wants/, .requires/ or Alias= symlinks (permanently in /etc/systemd/system/).
     Enabled,
+    /// Enabled via .wants/, .requires/ or Alias= symlinks (transiently in /run/systemd/system/).
     EnabledRuntime,
+    /// The unit file was generated dynamically via a generator tool. Generated unit files may not
+    /// be enabled, they are enabled implicitly by their generator.
     Generated,
+    /// The unit file itself is not enabled, but it has a non-empty Also= setting in the "[Install]"
+    /// section, listing other unit files that might be enabled, or it has an alias under a
+    /// different name through a symlink that is not specified in Also=.
+    Indirect,
+    /// Made available through one or more symlinks to the unit file (permanently in
+    /// /etc/systemd/system/), even though the unit file might reside outside of the unit file
+    /// search path.
+    Linked,
+    /// Made available through one or more symlinks to the unit file (transiently in
+    /// /run/systemd/system/).
+    LinkedRuntime,
+    /// Completely disabled, so that any start operation on it fails (permanently in
+    /// /etc/systemd/system/).
+    Masked,
+    /// Completely disabled, so that any start operation on it fails (transiently in
+    /// /run/systemd/system/).
+    MaskedRuntime,
+    /// The unit file is not enabled, and has no provisions for enabling in the "[Install]" unit
+    /// file section.
     Static,
+    /// The unit file has been created dynamically with the runtime API. Transient units may not be
+    /// enabled.
     Transient,
     Other(String),
 }
 
+impl EnablementStatus {
+    /// Whether the state only lasts until the next reboot, i.e. comes from /run/.
+    pub fn is_runtime(&self) -> bool {
+        matches!(
+            self,
+            EnablementStatus::EnabledRuntime
+                | EnablementStatus::LinkedRuntime
+                | EnablementStatus::MaskedRuntime
+        )
+    }
+
+    /// Whether `systemctl is-enabled` would exit successfully for this state.
+    pub fn is_enabled(&self) -> bool {
+        matches!(
+            self,
+            EnablementStatus::Enabled
+                | EnablementStatus::EnabledRuntime
+                | EnablementStatus::Alias
+                | EnablementStatus::Static
+                | EnablementStatus::Indirect
+                | EnablementStatus::Generated
+                | EnablementStatus::Transient
+        )
+    }
+}
+
 impl From<String> for EnablementStatus {
     fn from(value: String) -> Self {
         match value.as_ref() {
             "alias" => EnablementStatus::Alias,
+            "bad" => EnablementStatus::Bad,
             "disabled" => EnablementStatus::Disabled,
             "enabled" => EnablementStatus::Enabled,
             "enabled-runtime" => EnablementStatus::EnabledRuntime,
             "generated" => EnablementStatus::Generated,
+            "indirect" => EnablementStatus::Indirect,
+            "linked" => EnablementStatus::Linked,
+            "linked-runtime" => EnablementStatus::LinkedRuntime,
+            "masked" => EnablementStatus::Masked,
+            "masked-runtime" => EnablementStatus::MaskedRuntime,
             "static" => EnablementStatus::Static,
             "transient" => EnablementStatus::Transient,
             _ => EnablementStatus::Other(value),
@@ -38,10 +189,16 @@ impl Display for EnablementStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let value = match self {
             EnablementStatus::Alias => "alias",
+            EnablementStatus::Bad => "bad",
             EnablementStatus::Disabled => "disabled",
             EnablementStatus::Enabled => "enabled",
             EnablementStatus::EnabledRuntime => "enabled-runtime",
             EnablementStatus::Generated => "generated",
+            EnablementStatus::Indirect => "indirect",
+            EnablementStatus::Linked => "linked",
+            EnablementStatus::LinkedRuntime => "linked-runtime",
+            EnablementStatus::Masked => "masked",
+            EnablementStatus::MaskedRuntime => "masked-runtime",
             EnablementStatus::Static => "static",
             EnablementStatus::Transient => "transient",
             EnablementStatus::Other(val) => val,
@@ -52,9 +209,74 @@ impl Display for EnablementStatus {
 
 impl From<(String, String)> for UnitFile {
     fn from(value: (String, String)) -> Self {
+        let file_name = value.0.rsplit('/').next().unwrap_or(&value.0);
+        let unit_name = UnitName::new(file_name).ok();
+
         Self {
+            unit_type: UnitType::from_unit_name(file_name),
+            is_template: unit_name.as_ref().is_some_and(UnitName::is_template),
+            unit_name,
             path: value.0,
             enablement_status: value.1.into(),
+            preset: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_unit_name_from_path() {
+        let unit_file: UnitFile = (
+            "/usr/lib/systemd/system/getty@.service".to_string(),
+            "enabled".to_string(),
+        )
+            .into();
+
+        assert_eq!(unit_file.file_name(), "getty@.service");
+        assert_eq!(unit_file.unit_type, Some(UnitType::Service));
+        assert!(unit_file.is_template);
+        assert_eq!(
+            unit_file.unit_name.map(|name| name.to_string()),
+            Some("getty@.service".to_string())
+        );
+    }
+
+    #[test]
+    fn enablement_status_covers_all_unit_file_states() {
+        for raw in [
+            "enabled",
+            "enabled-runtime",
+            "linked",
+            "linked-runtime",
+            "alias",
+            "masked",
+            "masked-runtime",
+            "static",
+            "disabled",
+            "indirect",
+            "generated",
+            "transient",
+            "bad",
+        ] {
+            let status: EnablementStatus = raw.to_string().into();
+            assert!(!matches!(status, EnablementStatus::Other(_)));
+            assert_eq!(status.to_string(), raw);
+        }
+    }
+
+    #[test]
+    fn unit_types_become_patterns_without_explicit_patterns() {
+        let query = UnitFileQuery::new()
+            .with_state(EnablementStatus::Enabled)
+            .with_unit_type(UnitType::Timer);
+
+        assert_eq!(query.states(), vec!["enabled"]);
+        assert_eq!(query.patterns(), vec!["*.timer"]);
+
+        let query = query.with_pattern("systemd-*");
+        assert_eq!(query.patterns(), vec!["systemd-*"]);
+    }
+}
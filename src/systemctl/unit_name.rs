@@ -0,0 +1,459 @@
+use std::{
+    ffi::OsString,
+    fmt::Display,
+    os::unix::ffi::{OsStrExt, OsStringExt},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::errors::SystemdError;
+
+// NOTE: These docs are from the man page of systemd.unit
+
+/// Unit names may be at most this long, including the type suffix.
+const UNIT_NAME_MAX: usize = 255;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum UnitType {
+    /// A process controlled and supervised by systemd
+    Service,
+    /// An IPC or network socket or a file system FIFO controlled and supervised by systemd
+    Socket,
+    /// A group of units, used as synchronisation points during start-up
+    Target,
+    /// A device as exposed in the sysfs/udev device tree
+    Device,
+    /// A file system mount point controlled and supervised by systemd
+    Mount,
+    /// A file system automount point
+    Automount,
+    /// A swap device or swap file
+    Swap,
+    /// A timer controlled and supervised by systemd, for timer-based activation
+    Timer,
+    /// A path monitored by systemd, for path-based activation
+    Path,
+    /// A group of hierarchically organised units that manage system processes
+    Slice,
+    /// A group of externally created processes
+    Scope,
+}
+
+impl UnitType {
+    pub const ALL: [UnitType; 11] = [
+        UnitType::Service,
+        UnitType::Socket,
+        UnitType::Target,
+        UnitType::Device,
+        UnitType::Mount,
+        UnitType::Automount,
+        UnitType::Swap,
+        UnitType::Timer,
+        UnitType::Path,
+        UnitType::Slice,
+        UnitType::Scope,
+    ];
+
+    /// The suffix, without the leading dot, that unit names of this type end in.
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            UnitType::Service => "service",
+            UnitType::Socket => "socket",
+            UnitType::Target => "target",
+            UnitType::Device => "device",
+            UnitType::Mount => "mount",
+            UnitType::Automount => "automount",
+            UnitType::Swap => "swap",
+            UnitType::Timer => "timer",
+            UnitType::Path => "path",
+            UnitType::Slice => "slice",
+            UnitType::Scope => "scope",
+        }
+    }
+
+    /// Finds the unit type from a unit name (or path to a unit file) by looking at its suffix.
+    pub fn from_unit_name(name: &str) -> Option<UnitType> {
+        let (_, suffix) = name.rsplit_once('.')?;
+        suffix.parse().ok()
+    }
+}
+
+impl FromStr for UnitType {
+    type Err = SystemdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        UnitType::ALL
+            .into_iter()
+            .find(|unit_type| unit_type.suffix() == s)
+            .ok_or_else(|| SystemdError::InvalidUnitType(s.to_string()))
+    }
+}
+
+impl Display for UnitType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.suffix())
+    }
+}
+
+/// A validated unit name, e.g. "dbus.service", "getty@.service" (a template) or
+/// "getty@tty1.service" (an instance of that template).
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct UnitName {
+    name: String,
+    unit_type: UnitType,
+}
+
+impl UnitName {
+    /// Validates the name the same way systemd does. Unit names consist of a prefix, an optional
+    /// "@" followed by an instance string, and a type suffix. Only ASCII letters, digits, ":", "-",
+    /// "_", ".", and "\" are allowed in the prefix and instance.
+    pub fn new(name: impl Into<String>) -> Result<Self, SystemdError> {
+        let name = name.into();
+        let invalid = || SystemdError::InvalidUnitName(name.clone());
+
+        if name.is_empty() || name.len() > UNIT_NAME_MAX {
+            return Err(invalid());
+        }
+
+        let (stem, suffix) = name.rsplit_once('.').ok_or_else(invalid)?;
+        let unit_type = suffix.parse().map_err(|_| invalid())?;
+
+        // Only the first "@" separates the instance, which may contain "@" itself
+        let (prefix, instance) = match stem.split_once('@') {
+            Some((prefix, instance)) => (prefix, Some(instance)),
+            None => (stem, None),
+        };
+
+        let valid_chars = |s: &str, extra: &str| {
+            s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || ":-_.\\".contains(c) || extra.contains(c))
+        };
+
+        if prefix.is_empty() || !valid_chars(prefix, "") {
+            return Err(invalid());
+        }
+
+        if instance.is_some_and(|instance| !valid_chars(instance, "@")) {
+            return Err(invalid());
+        }
+
+        Ok(Self { name, unit_type })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    pub fn unit_type(&self) -> UnitType {
+        self.unit_type
+    }
+
+    /// The part of the name before the "@" or type suffix, e.g. "getty" for "getty@tty1.service".
+    pub fn prefix(&self) -> &str {
+        let stem = self.stem();
+        stem.split_once('@').map_or(stem, |(prefix, _)| prefix)
+    }
+
+    /// The instance string, e.g. "tty1" for "getty@tty1.service". Templates have an empty
+    /// instance and plain units have none at all.
+    pub fn instance(&self) -> Option<&str> {
+        self.stem().split_once('@').map(|(_, instance)| instance)
+    }
+
+    /// Whether this is a template, i.e. of the form "foo@.service".
+    pub fn is_template(&self) -> bool {
+        self.instance().is_some_and(str::is_empty)
+    }
+
+    /// Whether this is an instance of a template, i.e. of the form "foo@bar.service".
+    pub fn is_instance(&self) -> bool {
+        self.instance().is_some_and(|instance| !instance.is_empty())
+    }
+
+    /// For instances, the template the instance was created from, e.g. "getty@.service" for
+    /// "getty@tty1.service".
+    pub fn template(&self) -> Option<UnitName> {
+        if !self.is_instance() {
+            return None;
+        }
+
+        Some(Self {
+            name: format!("{}@.{}", self.prefix(), self.unit_type),
+            unit_type: self.unit_type,
+        })
+    }
+
+    /// For templates, creates the named instance of the template.
+    pub fn with_instance(&self, instance: &str) -> Result<UnitName, SystemdError> {
+        if !self.is_template() {
+            return Err(SystemdError::InvalidUnitName(self.name.clone()));
+        }
+
+        UnitName::new(format!("{}@{}.{}", self.prefix(), instance, self.unit_type))
+    }
+
+    /// The name of the unit for a path, equivalent to `systemd-escape --path --suffix=`, e.g.
+    /// "home-user.mount" for "/home/user". Mount, automount and swap units must be named after
+    /// their path this way.
+    pub fn from_path(path: impl AsRef<Path>, unit_type: UnitType) -> Result<Self, SystemdError> {
+        let path = path.as_ref();
+        let invalid = || SystemdError::InvalidUnitName(path.display().to_string());
+
+        // Redundant slashes and "." are dropped, like systemd simplifies the path first
+        let components: Vec<&[u8]> = path
+            .as_os_str()
+            .as_bytes()
+            .split(|byte| *byte == b'/')
+            .filter(|component| !component.is_empty() && *component != b".")
+            .collect();
+        if !path.is_absolute() || components.contains(&&b".."[..]) {
+            return Err(invalid());
+        }
+        if components.is_empty() {
+            return UnitName::new(format!("-.{unit_type}"));
+        }
+
+        let mut escaped = String::new();
+        for (index, byte) in components.join(&b'/').into_iter().enumerate() {
+            match byte {
+                b'/' => escaped.push('-'),
+                b'.' if index == 0 => escaped.push_str("\\x2e"),
+                b':' | b'_' | b'.' => escaped.push(byte as char),
+                byte if byte.is_ascii_alphanumeric() => escaped.push(byte as char),
+                byte => escaped.push_str(&format!("\\x{byte:02x}")),
+            }
+        }
+
+        UnitName::new(format!("{escaped}.{unit_type}"))
+    }
+
+    /// The path the name stands for, the reverse of from_path(), e.g. "/home/user" for
+    /// "home-user.mount". None for templates and instances.
+    pub fn to_path(&self) -> Option<PathBuf> {
+        if self.instance().is_some() {
+            return None;
+        }
+
+        let stem = self.stem().as_bytes();
+        let mut path = vec![b'/'];
+        let mut index = 0;
+        while index < stem.len() {
+            match stem[index] {
+                b'-' => path.push(b'/'),
+                b'\\' => {
+                    let hex = self.stem().get(index + 2..index + 4)?;
+                    if stem.get(index + 1) != Some(&b'x') {
+                        return None;
+                    }
+                    path.push(u8::from_str_radix(hex, 16).ok()?);
+                    index += 3;
+                }
+                byte => path.push(byte),
+            }
+            index += 1;
+        }
+
+        // "-" is the root directory
+        if path == b"//" {
+            path.pop();
+        }
+
+        Some(PathBuf::from(OsString::from_vec(path)))
+    }
+
+    fn stem(&self) -> &str {
+        &self.name[..self.name.len() - self.unit_type.suffix().len() - 1]
+    }
+}
+
+impl FromStr for UnitName {
+    type Err = SystemdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        UnitName::new(s)
+    }
+}
+
+impl TryFrom<String> for UnitName {
+    type Error = SystemdError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        UnitName::new(value)
+    }
+}
+
+impl TryFrom<&str> for UnitName {
+    type Error = SystemdError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        UnitName::new(value)
+    }
+}
+
+impl AsRef<str> for UnitName {
+    fn as_ref(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Display for UnitName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Recovers the unit name from a unit object path such as
+/// "/org/freedesktop/systemd1/unit/dbus_2eservice". The last path element is the unit name with
+/// every character other than ASCII letters and digits escaped as "_" followed by two hex digits.
+pub(crate) fn unit_name_from_object_path(path: &str) -> Option<String> {
+    let label = path.strip_prefix("/org/freedesktop/systemd1/unit/")?;
+    let bytes = label.as_bytes();
+
+    let mut name = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'_' {
+            let hex = label.get(i + 1..i + 3)?;
+            name.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            name.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(name).ok()
+}
+
+/// The object path of a unit, the inverse of [`unit_name_from_object_path`].
+pub(crate) fn unit_object_path(name: &str) -> String {
+    let mut path = String::from("/org/freedesktop/systemd1/unit/");
+    for (index, byte) in name.bytes().enumerate() {
+        // Path elements may not start with a digit
+        if byte.is_ascii_alphabetic() || (byte.is_ascii_digit() && index > 0) {
+            path.push(byte as char);
+        } else {
+            path.push_str(&format!("_{byte:02x}"));
+        }
+    }
+
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_plain_unit_names() {
+        let name = UnitName::new("dbus.service").expect("Should be a valid unit name");
+
+        assert_eq!(name.unit_type(), UnitType::Service);
+        assert_eq!(name.prefix(), "dbus");
+        assert_eq!(name.instance(), None);
+        assert!(!name.is_template());
+        assert!(!name.is_instance());
+    }
+
+    #[test]
+    fn can_parse_templates_and_instances() {
+        let template = UnitName::new("getty@.service").expect("Should be a valid template");
+        assert!(template.is_template());
+        assert_eq!(template.prefix(), "getty");
+
+        let instance = template
+            .with_instance("tty1")
+            .expect("Should be able to instantiate template");
+        assert_eq!(instance.as_str(), "getty@tty1.service");
+        assert!(instance.is_instance());
+        assert_eq!(instance.instance(), Some("tty1"));
+        assert_eq!(instance.template(), Some(template));
+
+        let nested = UnitName::new("foo@a@b.service").expect("Instances may contain \"@\"");
+        assert_eq!(nested.prefix(), "foo");
+        assert_eq!(nested.instance(), Some("a@b"));
+    }
+
+    #[test]
+    fn rejects_invalid_unit_names() {
+        for name in [
+            "",
+            "dbus",
+            "dbus.notatype",
+            ".service",
+            "foo bar.service",
+            "@foo.service",
+            "foo@bar baz.service",
+        ] {
+            assert!(UnitName::new(name).is_err(), "{name} should be invalid");
+        }
+    }
+
+    #[test]
+    fn derives_unit_names_from_paths() {
+        let name = |path| {
+            UnitName::from_path(path, UnitType::Mount)
+                .ok()
+                .map(|name| name.to_string())
+        };
+
+        assert_eq!(name("/").as_deref(), Some("-.mount"));
+        assert_eq!(name("/home/user").as_deref(), Some("home-user.mount"));
+        assert_eq!(name("//home//user/./").as_deref(), Some("home-user.mount"));
+        assert_eq!(
+            name("/mnt/my-disk 1").as_deref(),
+            Some("mnt-my\\x2ddisk\\x201.mount")
+        );
+        assert_eq!(name("/.snapshots").as_deref(), Some("\\x2esnapshots.mount"));
+        assert_eq!(name("relative/path"), None);
+        assert_eq!(name("/mnt/../etc"), None);
+
+        for path in [
+            "/",
+            "/home/user",
+            "/mnt/my-disk 1",
+            "/.snapshots",
+            "/srv/a.b_c:d",
+        ] {
+            let unit = UnitName::from_path(path, UnitType::Automount).expect("Valid path");
+            assert_eq!(unit.to_path(), Some(PathBuf::from(path)));
+        }
+    }
+
+    #[test]
+    fn can_escape_unit_object_paths() {
+        assert_eq!(
+            unit_object_path("run-r1f.service"),
+            "/org/freedesktop/systemd1/unit/run_2dr1f_2eservice"
+        );
+        assert_eq!(
+            unit_name_from_object_path(&unit_object_path("-.mount")).as_deref(),
+            Some("-.mount")
+        );
+    }
+
+    #[test]
+    fn can_unescape_unit_object_paths() {
+        assert_eq!(
+            unit_name_from_object_path("/org/freedesktop/systemd1/unit/dbus_2eservice"),
+            Some("dbus.service".to_string())
+        );
+        assert_eq!(
+            unit_name_from_object_path("/org/freedesktop/systemd1/unit/_2d_2emount"),
+            Some("-.mount".to_string())
+        );
+        assert_eq!(
+            unit_name_from_object_path("/org/freedesktop/systemd1"),
+            None
+        );
+    }
+
+    #[test]
+    fn can_get_unit_type_from_path() {
+        assert_eq!(
+            UnitType::from_unit_name("/usr/lib/systemd/system/systemd-journald.socket"),
+            Some(UnitType::Socket)
+        );
+        assert_eq!(UnitType::from_unit_name("README"), None);
+    }
+}
@@ -0,0 +1,330 @@
+use crate::systemctl::{
+    unit::{Unit, UnitActiveState, UnitLoadState},
+    unit_name::{UnitName, UnitType},
+};
+
+/// Describes which loaded units to list, similar to the filtering options of
+/// `systemctl list-units`. The query is translated to the most specific manager method available,
+/// i.e. ListUnitsByNames(), ListUnitsByPatterns(), ListUnitsFiltered() or ListUnits(), and whatever
+/// cannot be filtered on the server side is filtered afterwards.
+///
+/// Note that, like `systemctl --state=`, a unit matches the states if any of its load, active or
+/// sub state is one of the requested states.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct UnitQuery {
+    load_states: Vec<UnitLoadState>,
+    active_states: Vec<UnitActiveState>,
+    sub_states: Vec<String>,
+    patterns: Vec<String>,
+    names: Vec<UnitName>,
+    unit_types: Vec<UnitType>,
+    all: bool,
+}
+
+/// The manager method a query is sent to, along with its arguments.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum UnitListMethod {
+    All,
+    Filtered {
+        states: Vec<String>,
+    },
+    ByPatterns {
+        states: Vec<String>,
+        patterns: Vec<String>,
+    },
+    ByNames {
+        names: Vec<String>,
+    },
+}
+
+impl UnitQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only list units in this load state, e.g. loaded, not-found or masked.
+    pub fn with_load_state(mut self, state: UnitLoadState) -> Self {
+        self.load_states.push(state);
+        self
+    }
+
+    /// Only list units in this active state, e.g. active, inactive or failed.
+    pub fn with_active_state(mut self, state: UnitActiveState) -> Self {
+        self.active_states.push(state);
+        self
+    }
+
+    /// Only list units in this sub state. Sub states are specific to the unit type, e.g. "running"
+    /// or "exited" for services and "listening" for sockets.
+    pub fn with_sub_state(mut self, state: &str) -> Self {
+        self.sub_states.push(state.to_string());
+        self
+    }
+
+    /// Only list units whose name matches this shell-style glob pattern, e.g. "systemd-*".
+    pub fn with_pattern(mut self, pattern: &str) -> Self {
+        self.patterns.push(pattern.to_string());
+        self
+    }
+
+    /// Only list the units with these names. Unlike the other filters, this also returns units that
+    /// are not currently loaded, as systemd will load them to answer the query.
+    pub fn with_name(mut self, name: UnitName) -> Self {
+        self.names.push(name);
+        self
+    }
+
+    /// Only list units of this type, e.g. services or timers.
+    pub fn with_unit_type(mut self, unit_type: UnitType) -> Self {
+        self.unit_types.push(unit_type);
+        self
+    }
+
+    /// Equivalent to `systemctl list-units --all`. By default inactive units without a queued job
+    /// are left out, unless a state filter is given.
+    pub fn with_all(mut self) -> Self {
+        self.all = true;
+        self
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub fn names(&self) -> &[UnitName] {
+        &self.names
+    }
+
+    fn states(&self) -> Vec<String> {
+        self.load_states
+            .iter()
+            .map(ToString::to_string)
+            .chain(self.active_states.iter().map(ToString::to_string))
+            .chain(self.sub_states.iter().cloned())
+            .collect()
+    }
+
+    /// Picks the manager method that filters out the most on the server side.
+    pub(crate) fn method(&self) -> UnitListMethod {
+        let states = self.states();
+
+        if !self.names.is_empty() {
+            return UnitListMethod::ByNames {
+                names: self.names.iter().map(ToString::to_string).collect(),
+            };
+        }
+
+        if !self.patterns.is_empty() {
+            return UnitListMethod::ByPatterns {
+                states,
+                patterns: self.patterns.clone(),
+            };
+        }
+
+        if !self.unit_types.is_empty() {
+            return UnitListMethod::ByPatterns {
+                states,
+                patterns: self
+                    .unit_types
+                    .iter()
+                    .map(|unit_type| format!("*.{unit_type}"))
+                    .collect(),
+            };
+        }
+
+        if !states.is_empty() {
+            return UnitListMethod::Filtered { states };
+        }
+
+        UnitListMethod::All
+    }
+
+    /// Whether a unit returned by the manager satisfies the whole query. This is applied to every
+    /// result, since not every filter can be expressed in every manager method.
+    ///
+    /// The names given with `with_name` are not checked here. They are only filtered on the server
+    /// side by ListUnitsByNames(), which follows aliases and so may return a unit under its primary
+    /// name rather than the name that was asked for.
+    pub fn matches(&self, unit: &Unit) -> bool {
+        self.matches_states(&self.states(), unit)
+    }
+
+    /// Keeps the units that satisfy the whole query, see `matches`.
+    pub(crate) fn filter(&self, units: impl IntoIterator<Item = Unit>) -> Vec<Unit> {
+        let states = self.states();
+        units
+            .into_iter()
+            .filter(|unit| self.matches_states(&states, unit))
+            .collect()
+    }
+
+    fn matches_states(&self, states: &[String], unit: &Unit) -> bool {
+        if !states.is_empty()
+            && !states.iter().any(|state| {
+                *state == unit.load_state.to_string()
+                    || *state == unit.active_state.to_string()
+                    || *state == unit.sub_state
+            })
+        {
+            return false;
+        }
+
+        if !self.patterns.is_empty()
+            && !self
+                .patterns
+                .iter()
+                .any(|pattern| glob_match(pattern, &unit.name))
+        {
+            return false;
+        }
+
+        if !self.unit_types.is_empty()
+            && !UnitType::from_unit_name(&unit.name)
+                .is_some_and(|unit_type| self.unit_types.contains(&unit_type))
+        {
+            return false;
+        }
+
+        if !self.all
+            && states.is_empty()
+            && self.names.is_empty()
+            && unit.active_state == UnitActiveState::Inactive
+            && unit.queued_job_id.is_none()
+        {
+            return false;
+        }
+
+        true
+    }
+}
+
+/// Shell-style glob matching as done by fnmatch(3) without any flags, which is what systemd uses
+/// for unit name patterns. Supports "*", "?" and bracket expressions like "[a-z]" or "[!0-9]".
+pub(crate) fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // Where to resume from if a later part of the pattern fails to match after a "*".
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        let step = match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+                continue;
+            }
+            Some('?') => Some(p + 1),
+            Some('[') => match match_bracket(&pattern[p..], name[n]) {
+                Some((matched, len)) => matched.then_some(p + len),
+                // Without a closing "]", the "[" is matched literally
+                None => (name[n] == '[').then_some(p + 1),
+            },
+            Some(c) if *c == name[n] => Some(p + 1),
+            _ => None,
+        };
+
+        match (step, backtrack) {
+            (Some(next), _) => {
+                p = next;
+                n += 1;
+            }
+            (None, Some((star, matched))) => {
+                p = star + 1;
+                n = matched + 1;
+                backtrack = Some((star, matched + 1));
+            }
+            (None, None) => return false,
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Matches a single character against a bracket expression at the start of the pattern. Returns
+/// whether it matched along with the length of the bracket expression, or None if the bracket
+/// expression is not terminated.
+fn match_bracket(pattern: &[char], c: char) -> Option<(bool, usize)> {
+    let mut i = 1;
+    let negated = matches!(pattern.get(i), Some('!') | Some('^'));
+    if negated {
+        i += 1;
+    }
+
+    let mut matched = false;
+    let mut first = true;
+
+    loop {
+        let start = *pattern.get(i)?;
+        if start == ']' && !first {
+            break;
+        }
+        first = false;
+
+        if pattern.get(i + 1) == Some(&'-') && pattern.get(i + 2).is_some_and(|end| *end != ']') {
+            let end = pattern[i + 2];
+            matched |= start <= c && c <= end;
+            i += 3;
+        } else {
+            matched |= start == c;
+            i += 1;
+        }
+    }
+
+    Some((matched != negated, i + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_matches_like_fnmatch() {
+        assert!(glob_match("*.service", "dbus.service"));
+        assert!(glob_match("systemd-*", "systemd-journald.service"));
+        assert!(glob_match("getty@tty?.service", "getty@tty1.service"));
+        assert!(glob_match("[a-c]*", "cron.service"));
+        assert!(glob_match("[!a-c]*", "dbus.service"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("*.socket", "dbus.service"));
+        assert!(!glob_match("[!a-c]*", "cron.service"));
+        assert!(!glob_match("?", ""));
+        assert!(glob_match("foo[1", "foo[1"));
+        assert!(glob_match("*[", "dbus["));
+        assert!(!glob_match("foo[1", "foo1"));
+    }
+
+    #[test]
+    fn picks_most_specific_method() {
+        assert_eq!(UnitQuery::new().method(), UnitListMethod::All);
+
+        assert_eq!(
+            UnitQuery::new()
+                .with_active_state(UnitActiveState::Failed)
+                .method(),
+            UnitListMethod::Filtered {
+                states: vec!["failed".to_string()]
+            }
+        );
+
+        assert_eq!(
+            UnitQuery::new()
+                .with_load_state(UnitLoadState::Loaded)
+                .with_unit_type(UnitType::Timer)
+                .method(),
+            UnitListMethod::ByPatterns {
+                states: vec!["loaded".to_string()],
+                patterns: vec!["*.timer".to_string()]
+            }
+        );
+
+        let name = UnitName::new("dbus.service").expect("Should be a valid unit name");
+        assert_eq!(
+            UnitQuery::new().with_name(name).with_pattern("*").method(),
+            UnitListMethod::ByNames {
+                names: vec!["dbus.service".to_string()]
+            }
+        );
+    }
+}
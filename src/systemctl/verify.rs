@@ -0,0 +1,583 @@
+//! Checks unit files for mistakes without a running service manager, a subset of what
+//! `systemd-analyze verify` does.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    errors::SystemdError,
+    systemctl::{
+        ini::Ini,
+        install::InstallInfo,
+        lookup_paths::LookupPaths,
+        unit_config::{
+            AssignError, ConfigError, ConfigErrorKind, Section, Sections, UnitSection, UnitWriter,
+            read_sections,
+            service::{ServiceType, ServiceUnitConfig},
+            socket::SocketUnitConfig,
+            timer::TimerUnitConfig,
+        },
+        unit_name::{UnitName, UnitType},
+    },
+};
+
+/// Dependencies that fail the unit if the other unit does not exist.
+const REQUIREMENT_KEYS: [&str; 3] = ["Requires", "Requisite", "BindsTo"];
+
+/// Dependencies that are silently ignored if the other unit does not exist.
+const WEAK_DEPENDENCY_KEYS: [&str; 3] = ["Wants", "After", "Before"];
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Severity {
+    /// The unit fails to load or start because of this
+    Error,
+    /// The unit works, but probably not as intended
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        f.write_str(value)
+    }
+}
+
+/// What a diagnostic is about.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DiagnosticKind {
+    /// A problem with the syntax, a section, a key or a value
+    Config(ConfigErrorKind),
+    /// The file name is not a valid unit name
+    InvalidUnitName,
+    /// A service has neither ExecStart= nor ExecStop=, so there is nothing to run
+    MissingExecCommand,
+    /// A service that is not Type=oneshot has no ExecStart=
+    MissingExecStart,
+    /// A service without ExecStart= or SuccessAction= that does not set RemainAfterExit=yes
+    MissingRemainAfterExit,
+    /// A service that is not Type=oneshot has more than one ExecStart=
+    MultipleExecStart,
+    /// A dependency on a unit for which there is no unit file
+    MissingUnit { key: String, unit: String },
+    /// Units that are ordered after each other in a circle, in the order of the cycle
+    OrderingCycle(Vec<String>),
+}
+
+/// A single problem found in a unit file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Diagnostic {
+    pub path: PathBuf,
+    /// The line the problem is on, counting from 1, if it can be pinned to a line
+    pub line: Option<usize>,
+    pub severity: Severity,
+    pub kind: DiagnosticKind,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+        }
+        write!(f, ": {}: ", self.severity)?;
+
+        match &self.kind {
+            DiagnosticKind::Config(kind) => match kind {
+                ConfigErrorKind::Syntax => f.write_str("invalid syntax"),
+                ConfigErrorKind::UnknownSection => f.write_str("unknown section"),
+                ConfigErrorKind::UnknownKey => f.write_str("unknown key"),
+                ConfigErrorKind::InvalidValue { value, expected } => {
+                    write!(f, "invalid value \"{value}\", expected {expected}")
+                }
+            },
+            DiagnosticKind::InvalidUnitName => f.write_str("not a valid unit name"),
+            DiagnosticKind::MissingExecCommand => {
+                f.write_str("service has no ExecStart=, ExecStop= or SuccessAction=")
+            }
+            DiagnosticKind::MissingExecStart => {
+                f.write_str("service has no ExecStart=, which only Type=oneshot allows")
+            }
+            DiagnosticKind::MissingRemainAfterExit => f.write_str(
+                "service has no ExecStart= and no SuccessAction= settings and does not have RemainAfterExit=yes set",
+            ),
+            DiagnosticKind::MultipleExecStart => {
+                f.write_str("service has more than one ExecStart=, which only Type=oneshot allows")
+            }
+            DiagnosticKind::MissingUnit { key, unit } => {
+                write!(f, "{key}={unit} refers to a unit that does not exist")
+            }
+            DiagnosticKind::OrderingCycle(units) => {
+                write!(f, "ordering cycle {}", units.join(" → "))
+            }
+        }
+    }
+}
+
+/// Checks a set of unit files, e.g. the ones of a project in CI. Units referenced by the unit
+/// files count as existing if they are in the set, or in the search path if one is given.
+#[derive(Debug, Clone, Default)]
+pub struct Verifier {
+    lookup_paths: Option<LookupPaths>,
+    files: Vec<(PathBuf, String)>,
+}
+
+impl Verifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also look up referenced units in this search path, e.g. `LookupPaths::system` of the OS
+    /// image the unit files are meant for.
+    pub fn with_lookup_paths(mut self, lookup_paths: LookupPaths) -> Self {
+        self.lookup_paths = Some(lookup_paths);
+        self
+    }
+
+    /// Adds a unit file from disk. The file name is the unit name.
+    pub fn with_file(mut self, path: impl AsRef<Path>) -> Result<Self, SystemdError> {
+        let path = path.as_ref();
+        self.files
+            .push((path.to_path_buf(), fs::read_to_string(path)?));
+        Ok(self)
+    }
+
+    /// Adds a unit file from memory, with the path it is reported under. The file name of the
+    /// path is the unit name.
+    pub fn with_contents(mut self, path: impl AsRef<Path>, contents: &str) -> Self {
+        self.files
+            .push((path.as_ref().to_path_buf(), contents.to_string()));
+        self
+    }
+
+    /// Checks all unit files, returning the problems found ordered by file and line.
+    pub fn verify(&self) -> Vec<Diagnostic> {
+        let units: Vec<(&Path, Option<UnitName>, Ini)> = self
+            .files
+            .iter()
+            .map(|(path, contents)| {
+                let name = path
+                    .file_name()
+                    .and_then(|name| UnitName::new(name.to_string_lossy()).ok());
+                (path.as_path(), name, Ini::parse(contents))
+            })
+            .collect();
+
+        let mut diagnostics = Vec::new();
+        for (path, name, ini) in &units {
+            let Some(name) = name else {
+                diagnostics.push(Diagnostic {
+                    path: path.to_path_buf(),
+                    line: None,
+                    severity: Severity::Error,
+                    kind: DiagnosticKind::InvalidUnitName,
+                });
+                continue;
+            };
+
+            let report = |line, severity, kind| Diagnostic {
+                path: path.to_path_buf(),
+                line,
+                severity,
+                kind,
+            };
+
+            diagnostics.extend(
+                check_config(name.unit_type(), ini)
+                    .into_iter()
+                    .map(|error| report(Some(error.line), config_severity(&error), error.into())),
+            );
+            diagnostics.extend(
+                check_exec_start(name.unit_type(), ini)
+                    .map(|(line, kind)| report(line, Severity::Error, kind)),
+            );
+
+            for (line, key, unit) in dependencies(ini, &REQUIREMENT_KEYS) {
+                if !self.unit_exists(&units, &unit) {
+                    let kind = DiagnosticKind::MissingUnit { key, unit };
+                    diagnostics.push(report(Some(line), Severity::Error, kind));
+                }
+            }
+            for (line, key, unit) in dependencies(ini, &WEAK_DEPENDENCY_KEYS) {
+                if !self.unit_exists(&units, &unit) {
+                    let kind = DiagnosticKind::MissingUnit { key, unit };
+                    diagnostics.push(report(Some(line), Severity::Warning, kind));
+                }
+            }
+        }
+
+        diagnostics.extend(ordering_cycles(&units));
+        diagnostics.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+        diagnostics
+    }
+
+    fn unit_exists(&self, units: &[(&Path, Option<UnitName>, Ini)], unit: &str) -> bool {
+        // Specifiers are expanded when the unit is loaded, which is not done here
+        if unit.contains('%') {
+            return true;
+        }
+
+        let Ok(name) = UnitName::new(unit) else {
+            return false;
+        };
+        let template = name.template();
+        let in_set = units.iter().any(|(_, other, _)| {
+            other.as_ref() == Some(&name) || (template.is_some() && *other == template)
+        });
+
+        in_set
+            || self
+                .lookup_paths
+                .as_ref()
+                .is_some_and(|lookup_paths| lookup_paths.resolve(&name).is_some())
+    }
+}
+
+impl From<ConfigError> for DiagnosticKind {
+    fn from(value: ConfigError) -> Self {
+        DiagnosticKind::Config(value.kind)
+    }
+}
+
+/// Unknown sections and keys, and invalid values, according to the unit type.
+fn check_config(unit_type: UnitType, ini: &Ini) -> Vec<ConfigError> {
+    match unit_type {
+        UnitType::Service => ServiceUnitConfig::read(ini).1,
+        UnitType::Socket => SocketUnitConfig::read(ini).1,
+        UnitType::Timer => TimerUnitConfig::read(ini).1,
+        other => read_sections(ini, &mut OtherUnitConfig::new(other)),
+    }
+}
+
+/// Unknown sections and keys are ignored when the unit is loaded, so they are only warned about.
+fn config_severity(error: &ConfigError) -> Severity {
+    match error.kind {
+        ConfigErrorKind::UnknownSection | ConfigErrorKind::UnknownKey => Severity::Warning,
+        _ => Severity::Error,
+    }
+}
+
+/// The checks the service manager does on the commands of a service when loading it.
+fn check_exec_start(
+    unit_type: UnitType,
+    ini: &Ini,
+) -> impl Iterator<Item = (Option<usize>, DiagnosticKind)> {
+    let mut problems = Vec::new();
+
+    if unit_type == UnitType::Service {
+        let (config, _) = ServiceUnitConfig::read(ini);
+        let service = &config.service;
+        let exec_start = &service.exec_start;
+        // Without Type=, a service without ExecStart= is Type=oneshot
+        let oneshot = match &service.service_type {
+            Some(service_type) => *service_type == ServiceType::Oneshot,
+            None => exec_start.is_empty(),
+        };
+        let success_action = ini
+            .entries("Unit", "SuccessAction")
+            .last()
+            .is_some_and(|entry| !entry.value.is_empty() && entry.value != "none");
+        let line = ini
+            .sections
+            .iter()
+            .find(|section| section.name == "Service")
+            .map(|section| section.line);
+
+        if exec_start.is_empty() && service.exec_stop.is_empty() && !success_action {
+            problems.push((line, DiagnosticKind::MissingExecCommand));
+        } else if !oneshot && exec_start.is_empty() {
+            problems.push((line, DiagnosticKind::MissingExecStart));
+        } else if exec_start.is_empty()
+            && !success_action
+            && service.remain_after_exit != Some(true)
+        {
+            problems.push((line, DiagnosticKind::MissingRemainAfterExit));
+        }
+
+        if !oneshot && exec_start.len() > 1 {
+            let line = ini.entries("Service", "ExecStart").last().map(|e| e.line);
+            problems.push((line, DiagnosticKind::MultipleExecStart));
+        }
+    }
+
+    problems.into_iter()
+}
+
+/// The units named in the [Unit] section under these keys, with the line and key they are on.
+fn dependencies(ini: &Ini, keys: &[&str]) -> Vec<(usize, String, String)> {
+    keys.iter()
+        .flat_map(|key| {
+            ini.entries("Unit", key).flat_map(move |entry| {
+                entry
+                    .value
+                    .split_whitespace()
+                    .map(move |unit| (entry.line, key.to_string(), unit.to_string()))
+            })
+        })
+        .collect()
+}
+
+/// Finds cycles in the After=/Before= ordering of the unit files. Each cycle is reported once,
+/// at the line that closes it.
+fn ordering_cycles(units: &[(&Path, Option<UnitName>, Ini)]) -> Vec<Diagnostic> {
+    // For each unit, the units that have to start before it, with where that is declared
+    let mut after: BTreeMap<String, Vec<(String, &Path, usize)>> = BTreeMap::new();
+
+    for (path, name, ini) in units {
+        let Some(name) = name else {
+            continue;
+        };
+        let name = name.to_string();
+        after.entry(name.clone()).or_default();
+
+        for (line, key, other) in dependencies(ini, &["After", "Before"]) {
+            let (later, earlier) = if key == "After" {
+                (name.clone(), other)
+            } else {
+                (other, name.clone())
+            };
+            after.entry(later).or_default().push((earlier, path, line));
+        }
+    }
+
+    let mut seen: BTreeSet<Vec<String>> = BTreeSet::new();
+    let mut diagnostics = Vec::new();
+
+    for start in after.keys() {
+        let mut stack: Vec<(String, usize)> = vec![(start.clone(), 0)];
+        let mut on_path: Vec<String> = vec![start.clone()];
+        // Expanding each unit once per start keeps this linear, at the cost of not reporting
+        // every elementary cycle when cycles share units
+        let mut expanded: BTreeSet<String> = BTreeSet::from([start.clone()]);
+
+        while let Some((unit, index)) = stack.pop() {
+            let edges = after.get(&unit).map(Vec::as_slice).unwrap_or_default();
+            let Some((next, path, line)) = edges.get(index) else {
+                on_path.pop();
+                continue;
+            };
+            stack.push((unit, index + 1));
+
+            if let Some(position) = on_path.iter().position(|u| u == next) {
+                let mut cycle = on_path[position..].to_vec();
+                let min = cycle
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, u)| *u)
+                    .map_or(0, |(i, _)| i);
+                cycle.rotate_left(min);
+
+                if seen.insert(cycle.clone()) {
+                    cycle.push(cycle[0].clone());
+                    diagnostics.push(Diagnostic {
+                        path: path.to_path_buf(),
+                        line: Some(*line),
+                        severity: Severity::Error,
+                        kind: DiagnosticKind::OrderingCycle(cycle),
+                    });
+                }
+            } else if *next > *start && expanded.insert(next.clone()) {
+                // Cycles through units sorting before the start were found from those already
+                on_path.push(next.clone());
+                stack.push((next.clone(), 0));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// The sections of unit types without a typed model. The type specific section is accepted as
+/// it is, only [Unit] and [Install] are checked.
+struct OtherUnitConfig {
+    type_section: Option<&'static str>,
+    unit: UnitSection,
+    install: InstallInfo,
+    unchecked: Unchecked,
+}
+
+impl OtherUnitConfig {
+    fn new(unit_type: UnitType) -> Self {
+        let type_section = match unit_type {
+            UnitType::Mount => Some("Mount"),
+            UnitType::Automount => Some("Automount"),
+            UnitType::Swap => Some("Swap"),
+            UnitType::Path => Some("Path"),
+            UnitType::Slice => Some("Slice"),
+            UnitType::Scope => Some("Scope"),
+            _ => None,
+        };
+
+        Self {
+            type_section,
+            unit: UnitSection::default(),
+            install: InstallInfo::default(),
+            unchecked: Unchecked,
+        }
+    }
+}
+
+impl Sections for OtherUnitConfig {
+    fn section(&mut self, name: &str) -> Option<&mut dyn Section> {
+        match name {
+            "Unit" => Some(&mut self.unit),
+            "Install" => Some(&mut self.install),
+            name if Some(name) == self.type_section => Some(&mut self.unchecked),
+            _ => None,
+        }
+    }
+}
+
+struct Unchecked;
+
+impl Section for Unchecked {
+    fn assign(&mut self, _key: &str, _value: &str) -> Result<(), AssignError> {
+        Ok(())
+    }
+
+    fn write(&self, _writer: &mut UnitWriter) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_problems_with_lines() {
+        let diagnostics = Verifier::new()
+            .with_contents(
+                "/src/foo.service",
+                "[Unit]\nRequires=missing.service\nAfter=bar.service\n\
+                 [Service]\nRestartSec=soon\nFrobnicate=yes\n",
+            )
+            .with_contents("/src/bar.service", "[Service]\nExecStart=/bin/bar\n")
+            .with_contents("/src/app.target", "[Unit]\nWants=foo.service\n[Target]\n")
+            .verify();
+
+        let found: Vec<(&str, Option<usize>, &DiagnosticKind)> = diagnostics
+            .iter()
+            .map(|d| (d.path.to_str().unwrap_or_default(), d.line, &d.kind))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "/src/app.target",
+                    Some(3),
+                    &DiagnosticKind::Config(ConfigErrorKind::UnknownSection)
+                ),
+                (
+                    "/src/foo.service",
+                    Some(2),
+                    &DiagnosticKind::MissingUnit {
+                        key: "Requires".to_string(),
+                        unit: "missing.service".to_string()
+                    }
+                ),
+                (
+                    "/src/foo.service",
+                    Some(4),
+                    &DiagnosticKind::MissingExecCommand
+                ),
+                (
+                    "/src/foo.service",
+                    Some(5),
+                    &DiagnosticKind::Config(ConfigErrorKind::InvalidValue {
+                        value: "soon".to_string(),
+                        expected: "a time span".to_string()
+                    })
+                ),
+                (
+                    "/src/foo.service",
+                    Some(6),
+                    &DiagnosticKind::Config(ConfigErrorKind::UnknownKey)
+                ),
+            ]
+        );
+        assert_eq!(
+            diagnostics[1].to_string(),
+            "/src/foo.service:2: error: Requires=missing.service refers to a unit that does not exist"
+        );
+        assert_eq!(diagnostics[4].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn only_rejects_services_without_commands_systemd_refuses() {
+        let exec_problems = |contents: &str| {
+            let diagnostics = Verifier::new()
+                .with_contents("foo.service", contents)
+                .verify();
+            diagnostics
+                .into_iter()
+                .map(|d| d.kind)
+                .filter(|kind| !matches!(kind, DiagnosticKind::Config(_)))
+                .collect::<Vec<_>>()
+        };
+
+        // ExecStop= only, with the implied Type=oneshot
+        assert!(exec_problems("[Service]\nRemainAfterExit=yes\nExecStop=/bin/stop\n").is_empty());
+        assert!(
+            exec_problems("[Service]\nType=oneshot\nExecStart=/bin/a\nExecStart=/bin/b\n")
+                .is_empty()
+        );
+        assert!(exec_problems("[Unit]\nSuccessAction=reboot\n[Service]\n").is_empty());
+        assert_eq!(
+            exec_problems("[Service]\nType=simple\nExecStop=/bin/stop\n"),
+            vec![DiagnosticKind::MissingExecStart]
+        );
+        assert_eq!(
+            exec_problems("[Service]\nExecStop=/bin/stop\n"),
+            vec![DiagnosticKind::MissingRemainAfterExit]
+        );
+        assert_eq!(
+            exec_problems("[Service]\nExecStart=/bin/a\nExecStart=/bin/b\n"),
+            vec![DiagnosticKind::MultipleExecStart]
+        );
+
+        // Assigning the empty string resets the list
+        assert_eq!(
+            exec_problems("[Service]\nExecStart=/bin/start\nExecStart=\n"),
+            vec![DiagnosticKind::MissingExecCommand]
+        );
+        assert!(
+            exec_problems(
+                "[Service]\nExecStart=/bin/a\nExecStart=\nExecStart=/bin/b\nExecStop=/bin/c\n"
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn finds_ordering_cycles_once() {
+        let diagnostics = Verifier::new()
+            .with_contents("a.target", "[Unit]\nAfter=b.target\n")
+            .with_contents("b.target", "[Unit]\nAfter=c.target\n")
+            .with_contents("c.target", "[Unit]\nBefore=b.target\nAfter=a.target\n")
+            .verify();
+
+        let cycles: Vec<&Vec<String>> = diagnostics
+            .iter()
+            .filter_map(|d| match &d.kind {
+                DiagnosticKind::OrderingCycle(cycle) => Some(cycle),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            cycles,
+            vec![&vec![
+                "a.target".to_string(),
+                "b.target".to_string(),
+                "c.target".to_string(),
+                "a.target".to_string()
+            ]]
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+/// Which hardware watchdog timeout of the manager to get or set. See RuntimeWatchdogSec=,
+/// RebootWatchdogSec= and KExecWatchdogSec= in systemd-system.conf.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Watchdog {
+    /// The watchdog that is pinged while the system is running
+    Runtime,
+    /// The watchdog that forces a reboot if a reboot does not complete in time
+    Reboot,
+    /// The watchdog that is left enabled while a kexec reboot happens
+    Kexec,
+}
+
+/// systemd uses u64::MAX (infinity) in properties for timeouts that never elapse.
+pub(crate) fn duration_from_usec(usec: u64) -> Duration {
+    if usec == u64::MAX {
+        Duration::MAX
+    } else {
+        Duration::from_micros(usec)
+    }
+}
+
+pub(crate) fn usec_from_duration(duration: Duration) -> u64 {
+    u64::try_from(duration.as_micros()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_infinity_both_ways() {
+        assert_eq!(duration_from_usec(u64::MAX), Duration::MAX);
+        assert_eq!(usec_from_duration(Duration::MAX), u64::MAX);
+        assert_eq!(usec_from_duration(Duration::from_secs(30)), 30_000_000);
+        assert_eq!(duration_from_usec(0), Duration::ZERO);
+    }
+}
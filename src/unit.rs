@@ -0,0 +1,241 @@
+//! # D-Bus interface proxy for: `org.freedesktop.systemd1.Unit`
+//!
+//! Every unit object implements this interface, in addition to the interface specific to its type
+//! (e.g. `org.freedesktop.systemd1.Service`). Unit objects live at
+//! "/org/freedesktop/systemd1/unit/<escaped name>", so the path of the unit has to be given when
+//! building the proxy. Use GetUnit() or LoadUnit() on the manager to find it.
+//!
+//! Only the most commonly used members are included here, see "man org.freedesktop.systemd1" for
+//! the full list.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+use zbus::proxy;
+#[proxy(
+    interface = "org.freedesktop.systemd1.Unit",
+    default_service = "org.freedesktop.systemd1"
+)]
+pub trait Unit {
+    /// # Clean()
+    /// ## METHOD
+    /// Removes the configuration, state, cache, log or runtime data of the unit, selected by the
+    /// mask. See CleanUnit() on the manager.
+    fn clean(&self, mask: &[&str]) -> zbus::Result<()>;
+
+    /// # Freeze()
+    /// ## METHOD
+    /// Freezes all processes of the unit using the cgroup freezer. See FreezeUnit() on the manager.
+    fn freeze(&self) -> zbus::Result<()>;
+
+    /// # Kill()
+    /// ## METHOD
+    /// Sends a signal to the processes of the unit. See KillUnit() on the manager.
+    fn kill(&self, whom: &str, signal: i32) -> zbus::Result<()>;
+
+    /// # Reload()
+    /// ## METHOD
+    /// Reloads the unit. See ReloadUnit() on the manager.
+    fn reload(&self, mode: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+    /// # ReloadOrRestart()
+    /// ## METHOD
+    /// See ReloadOrRestartUnit() on the manager.
+    fn reload_or_restart(&self, mode: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+    /// # ResetFailed()
+    /// ## METHOD
+    /// Resets the failed state of the unit. See ResetFailedUnit() on the manager.
+    fn reset_failed(&self) -> zbus::Result<()>;
+
+    /// # Restart()
+    /// ## METHOD
+    /// Restarts the unit. See RestartUnit() on the manager.
+    fn restart(&self, mode: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+    /// # Start()
+    /// ## METHOD
+    /// Starts the unit. See StartUnit() on the manager.
+    fn start(&self, mode: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+    /// # Stop()
+    /// ## METHOD
+    /// Stops the unit. See StopUnit() on the manager.
+    fn stop(&self, mode: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+    /// # Thaw()
+    /// ## METHOD
+    /// Thaws all processes of the unit. See ThawUnit() on the manager.
+    fn thaw(&self) -> zbus::Result<()>;
+
+    /// # TryRestart()
+    /// ## METHOD
+    /// See TryRestartUnit() on the manager.
+    fn try_restart(&self, mode: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+    /// # Unref()
+    /// ## METHOD
+    /// Releases a reference taken with AddRef= when the unit was created, allowing the unit to be
+    /// garbage collected again.
+    fn unref(&self) -> zbus::Result<()>;
+
+    /// # ActiveState
+    /// ## PROPERTY
+    /// The active state (i.e. whether the unit is currently started or not).
+    #[zbus(property)]
+    fn active_state(&self) -> zbus::Result<String>;
+
+    /// # After
+    /// ## PROPERTY
+    /// The units this unit is ordered after.
+    #[zbus(property)]
+    fn after(&self) -> zbus::Result<Vec<String>>;
+
+    /// # AllowIsolate
+    /// ## PROPERTY
+    /// Whether this unit may be used with the "isolate" job mode, i.e. AllowIsolate= in the unit
+    /// file.
+    #[zbus(property)]
+    fn allow_isolate(&self) -> zbus::Result<bool>;
+
+    /// # Before
+    /// ## PROPERTY
+    /// The units this unit is ordered before.
+    #[zbus(property)]
+    fn before(&self) -> zbus::Result<Vec<String>>;
+
+    /// # CanClean
+    /// ## PROPERTY
+    /// The resource types that may be removed with Clean(), e.g. "runtime", "state", "cache",
+    /// "logs", "configuration" or "fdstore". Empty if the unit type does not support cleaning.
+    #[zbus(property)]
+    fn can_clean(&self) -> zbus::Result<Vec<String>>;
+
+    /// # CanFreeze
+    /// ## PROPERTY
+    /// Whether the unit supports being frozen.
+    #[zbus(property)]
+    fn can_freeze(&self) -> zbus::Result<bool>;
+
+    /// # CanIsolate
+    /// ## PROPERTY
+    /// Whether the unit may be started in "isolate" mode.
+    #[zbus(property)]
+    fn can_isolate(&self) -> zbus::Result<bool>;
+
+    /// # CanReload
+    /// ## PROPERTY
+    /// Whether the unit supports being reloaded.
+    #[zbus(property)]
+    fn can_reload(&self) -> zbus::Result<bool>;
+
+    /// # CanStart
+    /// ## PROPERTY
+    /// Whether the unit may be started, i.e. RefuseManualStart= is not set.
+    #[zbus(property)]
+    fn can_start(&self) -> zbus::Result<bool>;
+
+    /// # CanStop
+    /// ## PROPERTY
+    /// Whether the unit may be stopped, i.e. RefuseManualStop= is not set.
+    #[zbus(property)]
+    fn can_stop(&self) -> zbus::Result<bool>;
+
+    /// # Conflicts
+    /// ## PROPERTY
+    /// The units this unit conflicts with.
+    #[zbus(property)]
+    fn conflicts(&self) -> zbus::Result<Vec<String>>;
+
+    /// # Description
+    /// ## PROPERTY
+    /// The human readable description of the unit.
+    #[zbus(property)]
+    fn description(&self) -> zbus::Result<String>;
+
+    /// # DropInPaths
+    /// ## PROPERTY
+    /// The drop-in files that were applied to the unit.
+    #[zbus(property)]
+    fn drop_in_paths(&self) -> zbus::Result<Vec<String>>;
+
+    /// # FragmentPath
+    /// ## PROPERTY
+    /// The unit file the unit was loaded from, if any.
+    #[zbus(property)]
+    fn fragment_path(&self) -> zbus::Result<String>;
+
+    /// # FreezerState
+    /// ## PROPERTY
+    /// The freezer state of the unit, one of "running", "freezing", "frozen" or "thawing".
+    #[zbus(property)]
+    fn freezer_state(&self) -> zbus::Result<String>;
+
+    /// # Id
+    /// ## PROPERTY
+    /// The primary name of the unit.
+    #[zbus(property)]
+    fn id(&self) -> zbus::Result<String>;
+
+    /// # InvocationID
+    /// ## PROPERTY
+    /// The 128-bit invocation ID of the current run of the unit, changing each time it is started.
+    #[zbus(property, name = "InvocationID")]
+    fn invocation_id(&self) -> zbus::Result<Vec<u8>>;
+
+    /// # Job
+    /// ## PROPERTY
+    /// The id and object path of the job currently queued for the unit, id 0 if there is none.
+    #[zbus(property)]
+    fn job(&self) -> zbus::Result<(u32, zbus::zvariant::OwnedObjectPath)>;
+
+    /// # LoadState
+    /// ## PROPERTY
+    /// The load state (i.e. whether the unit file has been loaded successfully).
+    #[zbus(property)]
+    fn load_state(&self) -> zbus::Result<String>;
+
+    /// # Names
+    /// ## PROPERTY
+    /// All names of the unit, including the primary one and all aliases.
+    #[zbus(property)]
+    fn names(&self) -> zbus::Result<Vec<String>>;
+
+    /// # Requires
+    /// ## PROPERTY
+    /// The units this unit requires.
+    #[zbus(property)]
+    fn requires(&self) -> zbus::Result<Vec<String>>;
+
+    /// # SourcePath
+    /// ## PROPERTY
+    /// The configuration file this unit was generated from by a generator, if any.
+    #[zbus(property)]
+    fn source_path(&self) -> zbus::Result<String>;
+
+    /// # SubState
+    /// ## PROPERTY
+    /// The sub state, a more fine-grained version of the active state that is specific to the
+    /// unit type.
+    #[zbus(property)]
+    fn sub_state(&self) -> zbus::Result<String>;
+
+    /// # UnitFilePreset
+    /// ## PROPERTY
+    /// The preset policy for the unit file, "enabled" or "disabled".
+    #[zbus(property)]
+    fn unit_file_preset(&self) -> zbus::Result<String>;
+
+    /// # UnitFileState
+    /// ## PROPERTY
+    /// The enablement state of the unit file, as returned by GetUnitFileState() on the manager.
+    #[zbus(property)]
+    fn unit_file_state(&self) -> zbus::Result<String>;
+
+    /// # Wants
+    /// ## PROPERTY
+    /// The units this unit wants.
+    #[zbus(property)]
+    fn wants(&self) -> zbus::Result<Vec<String>>;
+}